@@ -1,17 +1,123 @@
 //! Calculator.
 
 use std::collections::HashMap;
+use std::ops::RangeInclusive;
 
 use anyhow::*;
 use etrace::*;
 
-use super::syntax::{BinOp, Command, Expression};
+use super::derivative::{self, VARIABLE};
+use super::parser;
+use super::syntax::{
+    BinOp, Command, Expression, Format, Function, Series, Setting, Statement, UnaryOp,
+};
+use super::value::Value;
+use crate::assignments::assignment06::symbolic_differentiation::{
+    Differentiable, Evaluate, Rational,
+};
+use crate::assignments::assignment09::bigint::BigInt;
+
+/// Returns the value of the built-in constant `name`, if any.
+///
+/// Constants are reserved, i.e., they cannot be assigned to.
+fn constant(name: &str) -> Option<f64> {
+    match name {
+        "pi" => Some(std::f64::consts::PI),
+        "e" => Some(std::f64::consts::E),
+        "tau" => Some(std::f64::consts::TAU),
+        _ => None,
+    }
+}
+
+/// Checks that `var` may be bound by `let`.
+pub(crate) fn check_binding(var: &str) -> Result<()> {
+    if constant(var).is_some() || var == "ans" {
+        bail!("Cannot bind reserved name: {}", var)
+    }
+    Ok(())
+}
+
+/// Evaluation mode.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Every value is `f64`.
+    #[default]
+    Float,
+    /// Values are exact rationals if possible, e.g., `1/3 * 3` is exactly `1`.
+    Rational,
+    /// Values are exact integers of arbitrary size if possible, e.g., `2^200`. Division must be
+    /// exact.
+    Integer,
+}
+
+/// Error of a script, located at the line and column where it occurred.
+#[derive(Debug)]
+pub struct ScriptError {
+    /// Line (1-based).
+    pub line: usize,
+    /// Column (1-based). For errors during calculation, it is where the expression begins.
+    pub column: usize,
+    /// Error.
+    pub error: Error,
+}
+
+impl std::fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {:#}",
+            self.line, self.column, self.error
+        )
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+/// Error during calculation, with the sub-expression that caused it.
+#[derive(Debug)]
+pub struct CalcError {
+    /// Sub-expression.
+    pub expression: Expression,
+    /// Error.
+    pub error: Error,
+}
+
+impl std::fmt::Display for CalcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} in `{}`", self.error, self.expression)
+    }
+}
+
+impl std::error::Error for CalcError {}
+
+/// Variables bound by `let`, where inner bindings shadow outer ones.
+#[derive(Debug)]
+struct Scope<'a> {
+    var: &'a str,
+    value: Value,
+    parent: Option<&'a Scope<'a>>,
+}
+
+impl Scope<'_> {
+    /// Returns the value of the innermost binding of `var`.
+    fn get(&self, var: &str) -> Option<Value> {
+        if self.var == var {
+            Some(self.value.clone())
+        } else {
+            self.parent?.get(var)
+        }
+    }
+}
 
 /// Calculator's context.
 #[derive(Debug, Default, Clone)]
 pub struct Context {
+    mode: Mode,
+    format: Format,
+    precision: Option<usize>,
     anonymous_counter: usize,
-    variables: HashMap<String, f64>,
+    variables: HashMap<String, Value>,
+    history: Vec<(String, Value)>,
 }
 
 impl Context {
@@ -20,38 +126,339 @@ impl Context {
         Self::default()
     }
 
+    /// Creates a new context with the given mode.
+    pub fn with_mode(mode: Mode) -> Self {
+        Self {
+            mode,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the mode.
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Applies `setting`, which changes how results are rendered by `render`.
+    pub fn set(&mut self, setting: Setting) {
+        match setting {
+            Setting::Precision(precision) => self.precision = precision,
+            Setting::Format(format) => self.format = format,
+        }
+    }
+
+    /// Renders `value` in the format and precision of `self`.
+    ///
+    /// In `Format::Auto`, exact values are rendered as they are, e.g., `1/3`, and so are floats
+    /// unless they are too large or small, e.g., `1.5e-7`.
+    pub fn render(&self, value: &Value) -> String {
+        let v = value.to_f64();
+        let sci = match self.format {
+            Format::Auto if value.is_exact() || !v.is_finite() => return value.to_string(),
+            Format::Auto => v != 0.0 && !(1e-4..1e15).contains(&v.abs()),
+            Format::Fixed => false,
+            Format::Sci => true,
+        };
+        match (sci, self.precision) {
+            // Integers keep all of their digits.
+            (false, precision) if matches!(value, Value::Int(_) | Value::BigInt(_)) => {
+                match precision {
+                    Some(p) if p > 0 => format!("{}.{}", value, "0".repeat(p)),
+                    _ => value.to_string(),
+                }
+            }
+            (false, None) => format!("{}", v),
+            (false, Some(p)) => format!("{:.*}", p, v),
+            (true, None) => format!("{:e}", v),
+            (true, Some(p)) => format!("{:.*e}", p, v),
+        }
+    }
+
     /// Returns the current anonymous variable counter.
     pub fn current_counter(&self) -> usize {
         self.anonymous_counter
     }
 
+    /// Returns the results of all commands calculated so far, from the oldest to the latest.
+    ///
+    /// The latest value is also available as `ans`, and the `n`-th latest one as `history(n)`.
+    pub fn history(&self) -> &[(String, Value)] {
+        &self.history
+    }
+
+    /// Returns the `n`-th latest result, where `n = 1` is the latest one.
+    fn nth_latest(&self, n: Value) -> Result<Value> {
+        match n.as_integer() {
+            Some(n) if n >= 1 && n as usize <= self.history.len() => {
+                Ok(self.history[self.history.len() - n as usize].1.clone())
+            }
+            _ => bail!("No such result in history: {}", n),
+        }
+    }
+
+    /// Returns `1` if `b` is true and `0` otherwise. Conversely, any nonzero value is true.
+    pub(crate) fn truth(&self, b: bool) -> Value {
+        match self.mode {
+            Mode::Float => Value::Float(b as u8 as f64),
+            Mode::Rational => Value::Rational(Rational::from(b as isize)),
+            Mode::Integer => Value::Int(b as i64),
+        }
+    }
+
     /// Calculates the given expression. (We assume the absence of overflow.)
+    ///
+    /// Errors are `CalcError`s with the innermost sub-expression that failed.
     pub fn calc_expression(&self, expression: &Expression) -> Result<f64> {
+        self.calc_expression_value(expression)
+            .map(|value| value.to_f64())
+    }
+
+    /// Calculates the given expression in the mode of `self`. (We assume the absence of overflow.)
+    ///
+    /// Errors are `CalcError`s with the innermost sub-expression that failed.
+    pub fn calc_expression_value(&self, expression: &Expression) -> Result<Value> {
+        self.calc_scoped(expression, None)
+    }
+
+    /// Calculates the given expression, where the variables bound by `let` are in `scope`.
+    fn calc_scoped(&self, expression: &Expression, scope: Option<&Scope<'_>>) -> Result<Value> {
+        self.calc_expression_inner(expression, scope)
+            .map_err(|error| {
+                if error.is::<CalcError>() {
+                    error
+                } else {
+                    CalcError {
+                        expression: expression.clone(),
+                        error,
+                    }
+                    .into()
+                }
+            })
+    }
+
+    fn calc_expression_inner(
+        &self,
+        expression: &Expression,
+        scope: Option<&Scope<'_>>,
+    ) -> Result<Value> {
         match expression {
-            Expression::Num(v) => Ok(*v),
-            Expression::Variable(s) => self
-                .variables
-                .get(s)
-                .copied()
-                .ok_or_else(|| anyhow::anyhow!("Undifined variable: {}", s)),
+            Expression::Num(v) => Ok(self.num(*v)),
+            Expression::Variable(s) => scope
+                .and_then(|scope| scope.get(s))
+                .map_or_else(|| self.global(s), Ok),
             Expression::BinOp { op, lhs, rhs } => {
-                let expr_l = self.calc_expression(lhs)?;
-                let expr_r = self.calc_expression(rhs)?;
+                let expr_l = self.calc_scoped(lhs, scope)?;
 
+                // `&&` and `||` short-circuit.
                 match op {
-                    BinOp::Add => Ok(expr_l + expr_r),
-                    BinOp::Subtract => Ok(expr_l - expr_r),
-                    BinOp::Multiply => Ok(expr_l * expr_r),
-                    BinOp::Divide => {
-                        if expr_r != 0.0 {
-                            Ok(expr_l / expr_r)
-                        } else {
-                            bail!("Division by zero")
-                        }
+                    BinOp::And if expr_l.is_zero() => return Ok(self.truth(false)),
+                    BinOp::Or if !expr_l.is_zero() => return Ok(self.truth(true)),
+                    _ => {}
+                }
+                let expr_r = self.calc_scoped(rhs, scope)?;
+                self.binary(*op, expr_l, expr_r)
+            }
+            Expression::UnaryOp { op, expr } => {
+                let value = self.calc_scoped(expr, scope)?;
+                self.unary(*op, value)
+            }
+            Expression::Call { func, arg } => {
+                let value = self.calc_scoped(arg, scope)?;
+                self.call(*func, value)
+            }
+            Expression::Let { var, value, body } => {
+                check_binding(var)?;
+                let scope = Scope {
+                    var,
+                    value: self.calc_scoped(value, scope)?,
+                    parent: scope,
+                };
+                self.calc_scoped(body, Some(&scope))
+            }
+            Expression::Series {
+                series,
+                var,
+                from,
+                to,
+                body,
+            } => {
+                check_binding(var)?;
+                let from = self.calc_scoped(from, scope)?;
+                let to = self.calc_scoped(to, scope)?;
+                let mut result = self.identity(*series);
+                for i in self.bounds(from, to)? {
+                    let scope = Scope {
+                        var,
+                        value: self.num(i as f64),
+                        parent: scope,
+                    };
+                    let term = self.calc_scoped(body, Some(&scope))?;
+                    result = self.accumulate(*series, result, term);
+                }
+                Ok(result)
+            }
+            Expression::Diff { expr, at } => {
+                let at = match at {
+                    Some(at) => self.calc_scoped(at, scope)?,
+                    None => self.calc_scoped(&Expression::Variable(VARIABLE.to_string()), scope)?,
+                };
+                let mut values = HashMap::new();
+                for var in derivative::free_variables(expr) {
+                    let value = self.calc_scoped(&Expression::Variable(var.to_string()), scope)?;
+                    let _unused = values.insert(var, value);
+                }
+                self.derivative(expr, at, &values)
+            }
+        }
+    }
+
+    /// Returns the derivative of `expr` by `x` at `at`, where the other variables are `values`.
+    pub(crate) fn derivative(
+        &self,
+        expr: &Expression,
+        at: Value,
+        values: &HashMap<&str, Value>,
+    ) -> Result<Value> {
+        let func = derivative::to_func(expr, values)?.diff();
+        Ok(Value::Float(func.try_evaluate(at.to_f64())?))
+    }
+
+    /// Returns the integers from `from` to `to`, which may be empty.
+    pub(crate) fn bounds(&self, from: Value, to: Value) -> Result<RangeInclusive<isize>> {
+        match (from.as_integer(), to.as_integer()) {
+            (Some(from), Some(to)) => Ok(from..=to),
+            _ => bail!("Bounds of series must be integers: {} and {}", from, to),
+        }
+    }
+
+    /// Returns the value of the empty `series`.
+    pub(crate) fn identity(&self, series: Series) -> Value {
+        match series {
+            Series::Sum => self.num(0.0),
+            Series::Product => self.num(1.0),
+        }
+    }
+
+    /// Accumulates `term` into `result` of `series`.
+    pub(crate) fn accumulate(&self, series: Series, result: Value, term: Value) -> Value {
+        match series {
+            Series::Sum => result + term,
+            Series::Product => result * term,
+        }
+    }
+
+    /// Returns the value of the number literal `v` in the mode of `self`.
+    pub(crate) fn num(&self, v: f64) -> Value {
+        match self.mode {
+            Mode::Float => Value::Float(v),
+            Mode::Rational => Value::exact(v),
+            Mode::Integer => Value::integer(v),
+        }
+    }
+
+    /// Returns the value of the variable `name`, which is not bound by `let`.
+    pub(crate) fn global(&self, name: &str) -> Result<Value> {
+        if name == "ans" {
+            return self.nth_latest(Value::Float(1.0));
+        }
+        constant(name)
+            .map(Value::Float)
+            .or_else(|| self.variables.get(name).cloned())
+            .ok_or_else(|| anyhow::anyhow!("Undifined variable: {}", name))
+    }
+
+    /// Applies `op` to `expr_l` and `expr_r`, where `&&` and `||` have already been short-circuited.
+    pub(crate) fn binary(&self, op: BinOp, expr_l: Value, expr_r: Value) -> Result<Value> {
+        match op {
+            BinOp::Add => Ok(expr_l + expr_r),
+            BinOp::Subtract => Ok(expr_l - expr_r),
+            BinOp::Multiply => Ok(expr_l * expr_r),
+            BinOp::Divide => {
+                if expr_r.is_zero() {
+                    bail!("Division by zero")
+                }
+                if self.mode == Mode::Integer && expr_l.is_exact() && expr_r.is_exact() {
+                    let result = expr_l.clone() / expr_r.clone();
+                    if !result.is_exact() {
+                        bail!("Inexact division: {} / {}", expr_l, expr_r)
                     }
-                    BinOp::Power => Ok(expr_l.powf(expr_r)),
+                    return Ok(result);
+                }
+                Ok(expr_l / expr_r)
+            }
+            BinOp::Power => {
+                if expr_l.is_zero() && expr_r < Value::Float(0.0) {
+                    bail!("Division by zero")
                 }
+                Ok(expr_l.pow(expr_r))
             }
+            BinOp::Eq => Ok(self.truth(expr_l == expr_r)),
+            BinOp::Ne => Ok(self.truth(expr_l != expr_r)),
+            BinOp::Lt => Ok(self.truth(expr_l < expr_r)),
+            BinOp::Le => Ok(self.truth(expr_l <= expr_r)),
+            BinOp::Gt => Ok(self.truth(expr_l > expr_r)),
+            BinOp::Ge => Ok(self.truth(expr_l >= expr_r)),
+            BinOp::And | BinOp::Or => Ok(self.truth(!expr_r.is_zero())),
+        }
+    }
+
+    /// Applies `op` to `value`.
+    pub(crate) fn unary(&self, op: UnaryOp, value: Value) -> Result<Value> {
+        match op {
+            UnaryOp::Neg => Ok(-value),
+            UnaryOp::Not => Ok(self.truth(value.is_zero())),
+            UnaryOp::Factorial => {
+                let n = match value.as_integer() {
+                    Some(n) if n >= 0 => n,
+                    _ => bail!("Factorial of non-integer: {}", value),
+                };
+                // `171!` already overflows `f64`.
+                let float = || Value::Float((2..=n.min(171)).fold(1.0, |acc, n| acc * n as f64));
+                match value {
+                    Value::Float(_) => Ok(float()),
+                    Value::Rational(_) => Ok((2..=n)
+                        .try_fold(1isize, |acc, n| acc.checked_mul(n))
+                        .map_or_else(float, |n| Value::Rational(Rational::from(n)))),
+                    Value::Int(_) | Value::BigInt(_) => Ok(Value::from(
+                        (2..=n as i64).fold(BigInt::from(1), |acc, n| acc * BigInt::from(n)),
+                    )),
+                }
+            }
+        }
+    }
+
+    /// Applies `func` to `value`.
+    pub(crate) fn call(&self, func: Function, value: Value) -> Result<Value> {
+        let x = value.to_f64();
+
+        match func {
+            Function::Sin => Ok(Value::Float(x.sin())),
+            Function::Cos => Ok(Value::Float(x.cos())),
+            Function::Tan => Ok(Value::Float(x.tan())),
+            Function::Sqrt => {
+                if x >= 0.0 {
+                    Ok(Value::Float(x.sqrt()))
+                } else {
+                    bail!("Domain error: sqrt({})", value)
+                }
+            }
+            Function::Ln => {
+                if x > 0.0 {
+                    Ok(Value::Float(x.ln()))
+                } else {
+                    bail!("Domain error: ln({})", value)
+                }
+            }
+            Function::Log10 => {
+                if x > 0.0 {
+                    Ok(Value::Float(x.log10()))
+                } else {
+                    bail!("Domain error: log10({})", value)
+                }
+            }
+            Function::Exp => Ok(Value::Float(x.exp())),
+            Function::Abs => Ok(value.abs()),
+            Function::History => self.nth_latest(value),
         }
     }
 
@@ -68,20 +475,71 @@ impl Context {
     ///
     /// After calculating commad `3 ^ 2` => Context's variables = `{($0,8),(v,1),($1,9)}`
     pub fn calc_command(&mut self, command: &Command) -> Result<(String, f64)> {
+        self.calc_command_value(command)
+            .map(|(var, value)| (var, value.to_f64()))
+    }
+
+    /// Calculates the given command in the mode of `self`, like `calc_command`.
+    pub fn calc_command_value(&mut self, command: &Command) -> Result<(String, Value)> {
         let (var, value) = match &command.variable {
             Some(s) => {
-                let result = self.calc_expression(&command.expression)?;
+                if constant(s).is_some() {
+                    bail!("Cannot assign to constant: {}", s);
+                }
+                if s == "ans" {
+                    bail!("Cannot assign to ans");
+                }
+                let result = self.calc_expression_value(&command.expression)?;
                 (s.clone(), result)
             }
             None => {
                 let index = self.anonymous_counter;
                 let s = format!("${}", index);
                 self.anonymous_counter += 1;
-                let result = self.calc_expression(&command.expression)?;
+                let result = self.calc_expression_value(&command.expression)?;
                 (s, result)
             }
         };
-        let _ = self.variables.insert(var.clone(), value);
+        let _unused = self.variables.insert(var.clone(), value.clone());
+        self.history.push((var.clone(), value.clone()));
         Ok((var, value))
     }
+
+    /// Calculates every line of `script` as a command, and returns the variables afterwards.
+    /// Settings are applied, and blank lines and comments are skipped.
+    ///
+    /// Stops at the first error, which is returned as a `ScriptError`.
+    pub fn run_script(&mut self, script: &str) -> Result<&HashMap<String, Value>> {
+        for (index, line) in script.lines().enumerate() {
+            let error = |column, error| ScriptError {
+                line: index + 1,
+                column,
+                error,
+            };
+            let statement = parser::parse_statement(line).map_err(|e| {
+                let column = e
+                    .downcast_ref::<parser::ParseError>()
+                    .map_or(1, |e| e.span.start + 1);
+                error(column, e)
+            })?;
+            let command = match statement {
+                Some(Statement::Command(command)) => command,
+                Some(Statement::Set(setting)) => {
+                    self.set(setting);
+                    continue;
+                }
+                None => continue,
+            };
+            let _unused = self.calc_command(&command).map_err(|e| {
+                // Skips the assignment, if any, and the leading whitespaces.
+                let start = match command.variable {
+                    Some(_) => line.find('=').map_or(0, |i| i + 1),
+                    None => 0,
+                };
+                let offset = line[start..].len() - line[start..].trim_start().len();
+                error(start + offset + 1, e)
+            })?;
+        }
+        Ok(&self.variables)
+    }
 }
@@ -0,0 +1,158 @@
+//! Derivatives, by converting expressions into functions of assignment 6.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+
+use super::syntax::{BinOp, Expression, Function, UnaryOp};
+use super::value::Value;
+use crate::assignments::assignment06::symbolic_differentiation::{
+    BaseFuncs, ComplexFuncs, Exp, Rational, SingletonPolynomial, Trignometric, MINUS_ONE, ONE, ZERO,
+};
+
+/// Variable of differentiation.
+pub const VARIABLE: &str = "x";
+
+/// Returns the free variables of `expression` other than `x`, in the order of appearance.
+pub fn free_variables(expression: &Expression) -> Vec<&str> {
+    fn collect<'a>(expression: &'a Expression, vars: &mut Vec<&'a str>) {
+        match expression {
+            Expression::Num(_) => {}
+            Expression::Variable(s) => {
+                if s != VARIABLE && !vars.contains(&s.as_str()) {
+                    vars.push(s);
+                }
+            }
+            Expression::BinOp { lhs, rhs, .. } => {
+                collect(lhs, vars);
+                collect(rhs, vars);
+            }
+            Expression::UnaryOp { expr, .. } => collect(expr, vars),
+            Expression::Call { arg, .. } => collect(arg, vars),
+            Expression::Let { value, body, .. } => {
+                collect(value, vars);
+                collect(body, vars);
+            }
+            Expression::Series { from, to, body, .. } => {
+                collect(from, vars);
+                collect(to, vars);
+                collect(body, vars);
+            }
+            Expression::Diff { expr, at } => {
+                if let Some(at) = at {
+                    collect(at, vars);
+                }
+                collect(expr, vars);
+            }
+        }
+    }
+
+    let mut vars = Vec::new();
+    collect(expression, &mut vars);
+    vars
+}
+
+/// Converts `expression` into a function of `x`, where the other variables are constants given by
+/// `values`.
+///
+/// Only arithmetic, `sin`, `cos`, `tan`, `sqrt`, `ln`, `log10` and `exp` are supported, and
+/// constants must be rationals, e.g., `pi` is approximated by the decimal `3.141592653589793`.
+pub fn to_func(
+    expression: &Expression,
+    values: &HashMap<&str, Value>,
+) -> Result<ComplexFuncs<BaseFuncs>> {
+    let func = |f: &Expression| to_func(f, values).map(Box::new);
+    let constant = |r: Rational| ComplexFuncs::Func(BaseFuncs::Const(r));
+    let compose = |f: BaseFuncs, g: &Expression| -> Result<_> {
+        Ok(ComplexFuncs::Comp(
+            Box::new(ComplexFuncs::Func(f)),
+            func(g)?,
+        ))
+    };
+
+    Ok(match expression {
+        Expression::Num(v) => constant(rational(&Value::exact(*v))?),
+        Expression::Variable(s) if s == VARIABLE => {
+            ComplexFuncs::Func(BaseFuncs::Poly(SingletonPolynomial::new_poly(ONE, ONE)))
+        }
+        Expression::Variable(s) => match values.get(s.as_str()) {
+            Some(value) => constant(rational(value)?),
+            None => bail!("Undifined variable: {}", s),
+        },
+        Expression::BinOp { op, lhs, rhs } => match op {
+            BinOp::Add => ComplexFuncs::Add(func(lhs)?, func(rhs)?),
+            BinOp::Subtract => ComplexFuncs::Sub(func(lhs)?, func(rhs)?),
+            BinOp::Multiply => ComplexFuncs::Mul(func(lhs)?, func(rhs)?),
+            BinOp::Divide => ComplexFuncs::Div(func(lhs)?, func(rhs)?),
+            BinOp::Power => {
+                let (base, exponent) = (func(lhs)?, func(rhs)?);
+                // Constant powers and bases avoid `ln` of the other, which may be negative.
+                match (as_const(&base), as_const(&exponent)) {
+                    (_, Some(n)) if n == ZERO => constant(ONE),
+                    (_, Some(n)) => ComplexFuncs::Comp(
+                        Box::new(ComplexFuncs::Func(BaseFuncs::Poly(
+                            SingletonPolynomial::new_poly(ONE, n),
+                        ))),
+                        base,
+                    ),
+                    _ if **lhs == Expression::Variable("e".to_string()) => {
+                        compose(BaseFuncs::Exp(Exp::new()), rhs)?
+                    }
+                    (Some(a), _) if a > ZERO => {
+                        compose(BaseFuncs::Exp(Exp::new_base(a, ONE)), rhs)?
+                    }
+                    _ => ComplexFuncs::Pow(base, exponent),
+                }
+            }
+            _ => bail!("Cannot differentiate: {}", expression),
+        },
+        Expression::UnaryOp {
+            op: UnaryOp::Neg,
+            expr,
+        } => ComplexFuncs::Mul(Box::new(constant(MINUS_ONE)), func(expr)?),
+        Expression::Call { func: f, arg } => match f {
+            Function::Sin => compose(BaseFuncs::Trig(Trignometric::new_sine(ONE)), arg)?,
+            Function::Cos => compose(BaseFuncs::Trig(Trignometric::new_cosine(ONE)), arg)?,
+            Function::Tan => ComplexFuncs::Div(
+                Box::new(compose(BaseFuncs::Trig(Trignometric::new_sine(ONE)), arg)?),
+                Box::new(compose(
+                    BaseFuncs::Trig(Trignometric::new_cosine(ONE)),
+                    arg,
+                )?),
+            ),
+            Function::Sqrt => compose(
+                BaseFuncs::Poly(SingletonPolynomial::new_poly(ONE, Rational::new(1, 2))),
+                arg,
+            )?,
+            Function::Ln => ComplexFuncs::Ln(func(arg)?),
+            Function::Log10 => ComplexFuncs::Div(
+                Box::new(ComplexFuncs::Ln(func(arg)?)),
+                Box::new(ComplexFuncs::Ln(Box::new(constant(Rational::from(10))))),
+            ),
+            Function::Exp => compose(BaseFuncs::Exp(Exp::new()), arg)?,
+            Function::Abs | Function::History => bail!("Cannot differentiate: {}", expression),
+        },
+        _ => bail!("Cannot differentiate: {}", expression),
+    })
+}
+
+/// Returns the value of `f` if it is a constant function.
+fn as_const(f: &ComplexFuncs<BaseFuncs>) -> Option<Rational> {
+    match f.partial_eval() {
+        ComplexFuncs::Func(f) => f.as_const(),
+        _ => None,
+    }
+}
+
+/// Converts `value` into a rational.
+fn rational(value: &Value) -> Result<Rational> {
+    match value {
+        Value::Rational(r) => Ok(*r),
+        Value::Int(n) => Ok(Rational::from(isize::try_from(*n)?)),
+        Value::Float(v) => match Value::exact(*v) {
+            Value::Rational(r) => Ok(r),
+            _ => bail!("Not a rational constant: {}", value),
+        },
+        Value::BigInt(_) => bail!("Not a rational constant: {}", value),
+    }
+}
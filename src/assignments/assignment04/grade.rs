@@ -1,6 +1,10 @@
 #[cfg(test)]
 mod test {
+    use ntest::assert_about_eq;
+
     use crate::assignments::assignment04::syntax::*;
+    use crate::assignments::assignment04::value::*;
+    use crate::assignments::assignment04::vm::*;
     use crate::assignments::assignment04::*;
 
     #[test]
@@ -311,4 +315,967 @@ mod test {
             })
             .is_err());
     }
+
+    #[test]
+    fn test_unary_minus() {
+        fn neg(expr: Expression) -> Expression {
+            Expression::UnaryOp {
+                op: UnaryOp::Neg,
+                expr: expr.into(),
+            }
+        }
+
+        fn calc(line: &str) -> f64 {
+            let command = parser::parse_command(line).unwrap();
+            context::Context::new()
+                .calc_expression(&command.expression)
+                .unwrap()
+        }
+
+        assert_eq!(
+            parser::parse_command("-3 + x").unwrap(),
+            Command {
+                variable: None,
+                expression: Expression::BinOp {
+                    op: BinOp::Add,
+                    lhs: neg(Expression::Num(3.0)).into(),
+                    rhs: Expression::Variable("x".into()).into(),
+                }
+            }
+        );
+
+        // Unary minus binds weaker than `^`, but stronger than the other operators.
+        assert_eq!(
+            parser::parse_command("-2 ^ 2").unwrap().expression,
+            neg(Expression::BinOp {
+                op: BinOp::Power,
+                lhs: Expression::Num(2.0).into(),
+                rhs: Expression::Num(2.0).into(),
+            })
+        );
+        assert_eq!(
+            parser::parse_command("2 ^ -x").unwrap().expression,
+            Expression::BinOp {
+                op: BinOp::Power,
+                lhs: Expression::Num(2.0).into(),
+                rhs: neg(Expression::Variable("x".into())).into(),
+            }
+        );
+        assert_eq!(
+            parser::parse_command("--(1)").unwrap().expression,
+            neg(neg(Expression::Num(1.0)))
+        );
+
+        assert_eq!(calc("-2 ^ 2"), -4.0);
+        assert_eq!(calc("(-2) ^ 2"), 4.0);
+        assert_eq!(calc("2 ^ -1"), 0.5);
+        assert_eq!(calc("2 ^ -2 ^ 2"), 0.0625);
+        assert_eq!(calc("3 - -2"), 5.0);
+        assert_eq!(calc("-3 * -(1 + 1)"), 6.0);
+        assert_eq!(calc("-2 ^ 2 * 3"), -12.0);
+        assert_eq!(calc("1 - 2 ^ 2"), -3.0);
+
+        let mut ctx = context::Context::new();
+        let _unused = ctx
+            .calc_command(&parser::parse_command("x = 5").unwrap())
+            .unwrap();
+        assert_eq!(
+            ctx.calc_command(&parser::parse_command("y = -x ^ 2 + 1").unwrap())
+                .unwrap(),
+            ("y".into(), -24.0)
+        );
+        assert!(parser::parse_command("3 -").is_err());
+    }
+
+    #[test]
+    fn test_functions() {
+        fn calc(line: &str) -> Result<f64, anyhow::Error> {
+            let mut ctx = context::Context::new();
+            let _unused = ctx.calc_command(&parser::parse_command("x = 4")?)?;
+            ctx.calc_expression(&parser::parse_command(line)?.expression)
+        }
+
+        assert_eq!(
+            parser::parse_command("sqrt(x + 1)").unwrap().expression,
+            Expression::Call {
+                func: Function::Sqrt,
+                arg: Expression::BinOp {
+                    op: BinOp::Add,
+                    lhs: Expression::Variable("x".into()).into(),
+                    rhs: Expression::Num(1.0).into(),
+                }
+                .into(),
+            }
+        );
+        assert_eq!(
+            parser::parse_command("-abs(x) ^ 2").unwrap().expression,
+            Expression::UnaryOp {
+                op: UnaryOp::Neg,
+                expr: Expression::BinOp {
+                    op: BinOp::Power,
+                    lhs: Expression::Call {
+                        func: Function::Abs,
+                        arg: Expression::Variable("x".into()).into(),
+                    }
+                    .into(),
+                    rhs: Expression::Num(2.0).into(),
+                }
+                .into(),
+            }
+        );
+
+        assert_about_eq!(calc("sin(0)").unwrap(), 0.0);
+        assert_about_eq!(calc("cos(0) + tan(0)").unwrap(), 1.0);
+        assert_about_eq!(calc("sqrt(x) * 3").unwrap(), 6.0);
+        assert_about_eq!(calc("ln(exp(x))").unwrap(), 4.0);
+        assert_about_eq!(calc("log10(1000)").unwrap(), 3.0);
+        assert_about_eq!(calc("abs(1 - x)").unwrap(), 3.0);
+        assert_about_eq!(calc("sqrt(sqrt(x ^ 2))").unwrap(), 2.0);
+
+        // Domain errors are reported instead of returning NaN.
+        assert!(calc("sqrt(-1)").is_err());
+        assert!(calc("ln(0)").is_err());
+        assert!(calc("log10(-x)").is_err());
+
+        // Unknown functions are rejected.
+        assert!(calc("foo(1)").is_err());
+    }
+
+    #[test]
+    fn test_constants() {
+        let mut ctx = context::Context::new();
+        let mut calc = |line: &str| ctx.calc_command(&parser::parse_command(line).unwrap());
+
+        assert_about_eq!(calc("pi").unwrap().1, std::f64::consts::PI);
+        assert_about_eq!(calc("ln(e)").unwrap().1, 1.0);
+        assert_about_eq!(calc("tau - 2 * pi").unwrap().1, 0.0);
+        assert_about_eq!(calc("x = cos(pi)").unwrap().1, -1.0);
+
+        // Constants cannot be shadowed.
+        for line in ["pi = 3", "e = 2", "tau = x"] {
+            let err = calc(line).unwrap_err();
+            assert!(err.to_string().contains("constant"), "{}", err);
+        }
+        assert_about_eq!(calc("e ^ 0 + pi").unwrap().1, 1.0 + std::f64::consts::PI);
+
+        // Other identifiers are not reserved.
+        assert_about_eq!(calc("pie = 3").unwrap().1, 3.0);
+    }
+
+    #[test]
+    fn test_factorial() {
+        fn fact(expr: Expression) -> Expression {
+            Expression::UnaryOp {
+                op: UnaryOp::Factorial,
+                expr: expr.into(),
+            }
+        }
+
+        fn calc(line: &str) -> Result<f64, anyhow::Error> {
+            context::Context::new().calc_expression(&parser::parse_command(line)?.expression)
+        }
+
+        assert_eq!(
+            parser::parse_command("2 ^ 3!").unwrap().expression,
+            Expression::BinOp {
+                op: BinOp::Power,
+                lhs: Expression::Num(2.0).into(),
+                rhs: fact(Expression::Num(3.0)).into(),
+            }
+        );
+        assert_eq!(
+            parser::parse_command("-x!!").unwrap().expression,
+            Expression::UnaryOp {
+                op: UnaryOp::Neg,
+                expr: fact(fact(Expression::Variable("x".into()))).into(),
+            }
+        );
+
+        assert_eq!(calc("0!").unwrap(), 1.0);
+        assert_eq!(calc("5!").unwrap(), 120.0);
+        assert_eq!(calc("2 ^ 3!").unwrap(), 64.0);
+        assert_eq!(calc("3! ^ 2").unwrap(), 36.0);
+        assert_eq!(calc("-3!").unwrap(), -6.0);
+        assert_eq!(calc("2 * 3! + 1").unwrap(), 13.0);
+        assert_eq!(calc("(1 + 2)!").unwrap(), 6.0);
+        assert_eq!(calc("3!!").unwrap(), 720.0);
+        assert_eq!(calc("200!").unwrap(), f64::INFINITY);
+
+        // The operand must be a non-negative integer.
+        assert!(calc("2.5!").is_err());
+        assert!(calc("(-1)!").is_err());
+        assert!(calc("3!.5").is_err());
+    }
+
+    #[test]
+    fn test_logic() {
+        fn calc(line: &str) -> Result<f64, anyhow::Error> {
+            let mut ctx = context::Context::new();
+            let _unused = ctx.calc_command(&parser::parse_command("x = 3")?)?;
+            ctx.calc_expression(&parser::parse_command(line)?.expression)
+        }
+
+        // `||` < `&&` < comparisons < arithmetic.
+        assert_eq!(
+            parser::parse_command("x < 1 + 2 || x != 0 && !x")
+                .unwrap()
+                .expression,
+            Expression::BinOp {
+                op: BinOp::Or,
+                lhs: Expression::BinOp {
+                    op: BinOp::Lt,
+                    lhs: Expression::Variable("x".into()).into(),
+                    rhs: Expression::BinOp {
+                        op: BinOp::Add,
+                        lhs: Expression::Num(1.0).into(),
+                        rhs: Expression::Num(2.0).into(),
+                    }
+                    .into(),
+                }
+                .into(),
+                rhs: Expression::BinOp {
+                    op: BinOp::And,
+                    lhs: Expression::BinOp {
+                        op: BinOp::Ne,
+                        lhs: Expression::Variable("x".into()).into(),
+                        rhs: Expression::Num(0.0).into(),
+                    }
+                    .into(),
+                    rhs: Expression::UnaryOp {
+                        op: UnaryOp::Not,
+                        expr: Expression::Variable("x".into()).into(),
+                    }
+                    .into(),
+                }
+                .into(),
+            }
+        );
+
+        for (line, expected) in [
+            ("x == 3", 1.0),
+            ("x != 3", 0.0),
+            ("x < 3", 0.0),
+            ("x <= 3", 1.0),
+            ("x > 2.5", 1.0),
+            ("x >= 4", 0.0),
+            ("1 < 2 == 1", 1.0),
+            ("x > 0 && x < 10", 1.0),
+            ("x < 0 || x > 10", 0.0),
+            ("2 && 3", 1.0),
+            ("0 || -1", 1.0),
+            ("!x", 0.0),
+            ("!0", 1.0),
+            ("!!x", 1.0),
+            ("!x == 0", 1.0),
+            ("x! != 3!", 0.0),
+            ("-x < 0", 1.0),
+        ] {
+            assert_eq!(calc(line).unwrap(), expected, "{}", line);
+        }
+
+        // `&&` and `||` short-circuit.
+        assert_eq!(calc("0 && y").unwrap(), 0.0);
+        assert_eq!(calc("1 || 1 / 0").unwrap(), 1.0);
+        assert!(calc("1 && y").is_err());
+    }
+
+    #[test]
+    fn test_history() {
+        let mut ctx = context::Context::new();
+        let mut calc = |line: &str| ctx.calc_command(&parser::parse_command(line).unwrap());
+
+        // There is no result yet.
+        assert!(calc("ans").is_err());
+        assert!(calc("history(1)").is_err());
+
+        assert_eq!(calc("1 + 2").unwrap(), ("$2".into(), 3.0));
+        assert_eq!(calc("ans * 2").unwrap(), ("$3".into(), 6.0));
+        assert_eq!(calc("v = ans + 1").unwrap(), ("v".into(), 7.0));
+        assert_eq!(calc("history(3) + ans").unwrap(), ("$4".into(), 10.0));
+        assert_eq!(calc("history(1 + 1)").unwrap(), ("$5".into(), 7.0));
+        assert!(calc("history(6)").is_err());
+        assert!(calc("history(0)").is_err());
+        assert!(calc("history(1.5)").is_err());
+        assert!(calc("ans = 1").is_err());
+
+        assert_eq!(
+            ctx.history(),
+            [
+                ("$2".into(), 3.0.into()),
+                ("$3".into(), 6.0.into()),
+                ("v".into(), 7.0.into()),
+                ("$4".into(), 10.0.into()),
+                ("$5".into(), 7.0.into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_script() {
+        let mut ctx = context::Context::new();
+        let variables = ctx.run_script("x = 3\ny = x ^ 2\n\tx + y\n").unwrap();
+        assert_eq!(variables.len(), 3);
+        assert_eq!(variables["y"], 9.0);
+        assert_eq!(variables["$0"], 12.0);
+
+        // Stops at the first error.
+        let mut ctx = context::Context::new();
+        let err = ctx.run_script("x = 1\ny = x +\nz = 2").unwrap_err();
+        let err = err.downcast_ref::<context::ScriptError>().unwrap();
+        assert_eq!((err.line, err.column), (2, 8));
+        assert!(ctx.run_script("").unwrap().contains_key("x"));
+        assert!(!ctx.run_script("").unwrap().contains_key("z"));
+
+        let err = context::Context::new()
+            .run_script("x = 1\ny = x\nz =  y / (x - 1)\nw = 0")
+            .unwrap_err();
+        let err = err.downcast_ref::<context::ScriptError>().unwrap();
+        assert_eq!((err.line, err.column), (3, 6));
+        assert!(err.to_string().starts_with("line 3, column 6: "));
+        assert!(err.to_string().contains("Division by zero"));
+    }
+
+    #[test]
+    fn test_comments() {
+        assert_eq!(parser::parse_line("").unwrap(), None);
+        assert_eq!(parser::parse_line(" \t ").unwrap(), None);
+        assert_eq!(parser::parse_line("# comment").unwrap(), None);
+        assert_eq!(
+            parser::parse_line("x = 1 # one").unwrap(),
+            Some(Command {
+                variable: Some("x".into()),
+                expression: Expression::Num(1.0),
+            })
+        );
+        assert!(parser::parse_command("2 * (1 + # (\n")
+            .unwrap_err()
+            .is::<parser::ParseError>());
+        assert_eq!(
+            parser::parse_command("  # comment")
+                .unwrap_err()
+                .to_string(),
+            "Empty command"
+        );
+
+        let script = "# Computes the area of a circle.
+r = 2
+
+area = pi * r ^ 2 # square units
+    # done
+";
+        let mut ctx = context::Context::new();
+        let variables = ctx.run_script(script).unwrap();
+        assert_eq!(variables.len(), 2);
+        assert_about_eq!(variables["area"].to_f64(), 4.0 * std::f64::consts::PI);
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = parser::parse_command("x = 1 + * 2").unwrap_err();
+        let err = err.downcast_ref::<parser::ParseError>().unwrap();
+        assert_eq!(err.span, 8..9);
+        assert!(err.expected.contains(&"number".to_string()));
+        assert!(err.expected.contains(&"`-`".to_string()));
+        assert_eq!(err.found.as_deref(), Some("*"));
+        assert!(err
+            .to_string()
+            .starts_with("Parse error at column 9: expected "));
+        assert!(err.to_string().ends_with(", found `*`"));
+
+        let err = parser::parse_command("(1 + 2").unwrap_err();
+        let err = err.downcast_ref::<parser::ParseError>().unwrap();
+        assert_eq!(err.span, 6..6);
+        assert_eq!(err.found, None);
+        assert!(err.to_string().ends_with(", found end of line"));
+
+        // Calculation errors carry the innermost sub-expression that failed.
+        let ctx = context::Context::new();
+        let calc = |line: &str| {
+            ctx.calc_expression(&parser::parse_command(line).unwrap().expression)
+                .unwrap_err()
+        };
+
+        let err = calc("1 + 2 / (3 - 3)");
+        let err = err.downcast_ref::<context::CalcError>().unwrap();
+        assert_eq!(err.expression.to_string(), "2 / (3 - 3)");
+        assert_eq!(err.to_string(), "Division by zero in `2 / (3 - 3)`");
+
+        let err = calc("sqrt(4) + -sqrt(-x!)");
+        let err = err.downcast_ref::<context::CalcError>().unwrap();
+        assert_eq!(err.expression.to_string(), "x");
+
+        let err = calc("sin(1) * ln(1 - 2)");
+        let err = err.downcast_ref::<context::CalcError>().unwrap();
+        assert_eq!(err.expression.to_string(), "ln(1 - 2)");
+
+        let err = calc("(-2)! == 1");
+        let err = err.downcast_ref::<context::CalcError>().unwrap();
+        assert_eq!(err.expression.to_string(), "(-2)!");
+    }
+
+    #[test]
+    fn test_rational_mode() {
+        use crate::assignments::assignment06::symbolic_differentiation::Rational;
+
+        let mut ctx = context::Context::with_mode(context::Mode::Rational);
+        let mut calc = |line: &str| {
+            ctx.calc_command_value(&parser::parse_command(line).unwrap())
+                .unwrap()
+                .1
+        };
+
+        assert_eq!(calc("1 / 3 * 3").to_string(), "1");
+        assert_eq!(calc("x = 1 / 3"), Value::Rational(Rational::new(1, 3)));
+        assert_eq!(calc("x + 1 / 6").to_string(), "1/2");
+        assert_eq!(calc("0.1 + 0.2 == 0.3").to_string(), "1");
+        assert_eq!(calc("0.1 + 0.2").to_string(), "3/10");
+        assert_eq!(calc("(2 / 3) ^ -2").to_string(), "9/4");
+        assert_eq!(calc("-x * 6!").to_string(), "-240");
+        assert_eq!(calc("abs(-1.25)").to_string(), "5/4");
+        assert_eq!(calc("x < 1 / 2 && !(x == 0)").to_string(), "1");
+
+        // Falls back to `f64` if it is not exact.
+        assert!(!calc("sqrt(4)").is_exact());
+        assert!(!calc("2 ^ (1 / 2)").is_exact());
+        assert!(!calc("pi * x").is_exact());
+        assert!(!calc("1e100 + 1").is_exact());
+        assert_eq!(calc("2 ^ 70"), Value::Float(2f64.powi(70)));
+        assert_about_eq!(calc("(1 / 3) ^ 40").to_f64(), 3f64.powi(-40));
+        assert!(!calc("(1 / 3) ^ 40").is_exact());
+        assert!(!calc("1e18 * 10 + 1 / 3").is_exact());
+        assert_about_eq!(calc("1e18 * 10 + 1 / 3").to_f64(), 1e19);
+        assert_about_eq!(calc("2 ^ 0.5").to_f64(), 2f64.sqrt());
+
+        // Other modes are not affected.
+        let mut ctx = context::Context::new();
+        let (_, value) = ctx
+            .calc_command_value(&parser::parse_command("1 / 3 * 3").unwrap())
+            .unwrap();
+        assert!(!value.is_exact());
+        assert_eq!(ctx.mode(), context::Mode::Float);
+
+        let mut ctx = context::Context::with_mode(context::Mode::Rational);
+        let _unused = ctx.run_script("x = 1/3\ny = x * 3\n").unwrap();
+        assert_eq!(ctx.history()[1].1.to_string(), "1");
+        assert!(ctx
+            .calc_command(&parser::parse_command("1 / (x - x)").unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn test_integer_mode() {
+        let mut ctx = context::Context::with_mode(context::Mode::Integer);
+        let mut calc = |line: &str| {
+            ctx.calc_command_value(&parser::parse_command(line).unwrap())
+                .map(|(_, value)| value)
+        };
+
+        assert_eq!(
+            calc("2 ^ 200").unwrap().to_string(),
+            "1606938044258990275541962092341162602522202993782792835301376"
+        );
+        assert_eq!(
+            calc("100!").unwrap().to_string(),
+            "93326215443944152681699238856266700490715968264381621468592963895217599993229915608941\
+             463976156518286253697920827223758251185210916864000000000000000000000000"
+        );
+        assert_eq!(calc("25! / 23!").unwrap(), Value::Int(600));
+        assert_eq!(calc("-(2 ^ 64) / 2 ^ 60").unwrap(), Value::Int(-16));
+        assert_eq!(calc("2 ^ 100 - 2 ^ 100 + 7").unwrap(), Value::Int(7));
+        assert_eq!(
+            calc("abs(-(3 ^ 50))").unwrap().to_string(),
+            "717897987691852588770249"
+        );
+
+        // Machine integers are promoted on overflow.
+        assert_eq!(
+            calc("x = 2 ^ 62 - 1 + 2 ^ 62").unwrap().to_string(),
+            "9223372036854775807"
+        );
+        assert_eq!(calc("x + 1").unwrap().to_string(), "9223372036854775808");
+        assert_eq!(calc("-x - 2").unwrap().to_string(), "-9223372036854775809");
+        assert_eq!(calc("x * x > x").unwrap(), Value::Int(1));
+        assert_eq!(calc("(x + 1) / 2").unwrap(), Value::Int(1 << 62));
+        assert!(matches!(calc("2 ^ 62 * 2").unwrap(), Value::BigInt(_)));
+        assert!(matches!(calc("2 ^ 66 / 2").unwrap(), Value::BigInt(_)));
+        assert!(matches!(
+            calc("2 ^ 66 / 2 ^ 2 / 2 ^ 2").unwrap(),
+            Value::Int(_)
+        ));
+
+        // Powers that are too large fall back to `f64`.
+        assert_eq!(calc("2 ^ 65536").unwrap().to_string().len(), 19729);
+        assert_eq!(calc("2 ^ 4000000000").unwrap(), Value::Float(f64::INFINITY));
+        assert_eq!(calc("1 ^ 4000000000").unwrap(), Value::Int(1));
+        assert_eq!(calc("(-1) ^ 4000000001").unwrap(), Value::Int(-1));
+
+        // Division must be exact.
+        assert_eq!(calc("12 / 4").unwrap(), Value::Int(3));
+        let err = calc("7 / 2").unwrap_err();
+        assert!(
+            err.to_string().contains("Inexact division: 7 / 2"),
+            "{}",
+            err
+        );
+        assert!(calc("(2 ^ 100 + 1) / 2").is_err());
+        assert!(calc("1 / 0").is_err());
+
+        // Non-integers are `f64`.
+        assert!(!calc("sqrt(16)").unwrap().is_exact());
+        assert_eq!(calc("0.5 * 4").unwrap(), 2.0);
+    }
+
+    #[test]
+    fn test_let_in() {
+        let mut ctx = context::Context::new();
+        let mut calc = |line: &str| ctx.calc_command(&parser::parse_command(line)?);
+
+        assert_eq!(
+            parser::parse_command("let x = 1 in x + 2")
+                .unwrap()
+                .expression,
+            Expression::Let {
+                var: "x".into(),
+                value: Expression::Num(1.0).into(),
+                body: Expression::BinOp {
+                    op: BinOp::Add,
+                    lhs: Expression::Variable("x".into()).into(),
+                    rhs: Expression::Num(2.0).into(),
+                }
+                .into(),
+            }
+        );
+
+        assert_eq!(calc("let x = 2 in x * x").unwrap().1, 4.0);
+        assert_eq!(calc("1 + let x = 2 in x * 3").unwrap().1, 7.0);
+        assert_eq!(calc("(let x = 2 in x) * 3").unwrap().1, 6.0);
+        assert_eq!(calc("2 ^ let y = 3 in y").unwrap().1, 8.0);
+
+        // Nesting and shadowing.
+        assert_eq!(calc("let x = 1 in let y = x + 1 in x + y").unwrap().1, 3.0);
+        assert_eq!(calc("let x = 1 in let x = x + 10 in x").unwrap().1, 11.0);
+        assert_eq!(calc("let x = 1 in (let x = 10 in x) + x").unwrap().1, 11.0);
+        assert_eq!(
+            calc("let x = (let x = 2 in x * x) in x + 1").unwrap().1,
+            5.0
+        );
+
+        // Bindings shadow globals, but do not change them.
+        assert_eq!(calc("x = 100").unwrap().1, 100.0);
+        assert_eq!(calc("y = let x = 1 in x + x").unwrap().1, 2.0);
+        assert_eq!(calc("x + y").unwrap().1, 102.0);
+        assert!(calc("let z = 1 in z").is_ok());
+        assert!(calc("z").is_err());
+
+        // Keywords are not variables, but may prefix them.
+        assert_eq!(calc("letter = 3").unwrap().1, 3.0);
+        assert_eq!(calc("let index = letter in index + letter").unwrap().1, 6.0);
+        assert!(calc("let = 3").is_err());
+        assert!(calc("in + 1").is_err());
+        assert!(calc("letx = 1 in x").is_err());
+        assert!(calc("let pi = 3 in pi").is_err());
+        assert!(calc("let x = 1").is_err());
+
+        assert_eq!(
+            parser::parse_command("2 * let x = 1 in x + 1")
+                .unwrap()
+                .expression
+                .to_string(),
+            "2 * (let x = 1 in x + 1)"
+        );
+    }
+
+    #[test]
+    fn test_pretty_print() {
+        let print = |line: &str| parser::parse_command(line).unwrap().expression.to_string();
+
+        // Only the necessary parentheses are kept.
+        assert_eq!(print("1 + 2 * 3"), "1 + 2 * 3");
+        assert_eq!(print("(1 + 2) * 3"), "(1 + 2) * 3");
+        assert_eq!(print("((1)) + (2 * 3)"), "1 + 2 * 3");
+        assert_eq!(print("1 - (2 - 3)"), "1 - (2 - 3)");
+        assert_eq!(print("(1 - 2) - 3"), "1 - 2 - 3");
+        assert_eq!(print("-2^2"), "-2 ^ 2");
+        assert_eq!(print("(-2)^2"), "(-2) ^ 2");
+        assert_eq!(print("2^3^2"), "2 ^ 3 ^ 2");
+        assert_eq!(print("(2^3)^2"), "(2 ^ 3) ^ 2");
+        assert_eq!(print("2^(-x)"), "2 ^ -x");
+        assert_eq!(print("3!!"), "3!!");
+        assert_eq!(print("-(x!)"), "-x!");
+        assert_eq!(print("(-x)!"), "(-x)!");
+        assert_eq!(print("!(x == 0) || y && z"), "!(x == 0) || y && z");
+        assert_eq!(print("(x || y) && z"), "(x || y) && z");
+        assert_eq!(print("sin((x + 1))"), "sin(x + 1)");
+        assert_eq!(print("(let x = 1 in x) + 1"), "(let x = 1 in x) + 1");
+
+        // Printing and parsing round-trips.
+        for line in [
+            "1 + 2 * 3 - 4 / 5",
+            "1 / (2 / 3) / 4",
+            "-2 ^ -3 ^ 4!",
+            "((-2)! ^ 2)!",
+            "--x + !!y",
+            "2 ^ -x * 3",
+            "!x == 0 != (y < 1) <= z",
+            "a || b && (c || d)",
+            "abs(-x) ^ 2 * ln(history(1))",
+            "let x = (let y = 1 in y) in x * (let z = 2 in z)",
+            "1.5e-3 + 2.25",
+            "-sum(i, 1, n, prod(j, 1, i, j) - 1) ^ 2",
+        ] {
+            let expression = parser::parse_command(line).unwrap().expression;
+            assert_eq!(
+                parser::parse_command(&expression.to_string())
+                    .unwrap()
+                    .expression,
+                expression,
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_simplify() {
+        let simplify = |line: &str| {
+            parser::parse_command(line)
+                .unwrap()
+                .expression
+                .simplify()
+                .to_string()
+        };
+
+        // Constant folding.
+        assert_eq!(simplify("1 + 2 * 3"), "7");
+        assert_eq!(simplify("x * (2 ^ 3 - 1)"), "x * 7");
+        assert_eq!(simplify("0.5 * 3 + x"), "1.5 + x");
+        assert_eq!(simplify("3! == 6 && !(1 > 2)"), "1");
+        assert_eq!(simplify("abs(-4) + 2!"), "6");
+
+        // Constants that are not exact in every mode are kept.
+        assert_eq!(simplify("1 / 3"), "1 / 3");
+        assert_eq!(simplify("0.1 + 0.2"), "0.1 + 0.2");
+        assert_eq!(simplify("sqrt(2)"), "sqrt(2)");
+        assert_eq!(simplify("1 / (2 - 2)"), "1 / 0");
+        assert_eq!(simplify("history(1) + 0"), "history(1)");
+
+        // Identities.
+        assert_eq!(simplify("x * 1 + 0"), "x");
+        assert_eq!(simplify("1 * (0 + x) ^ (3 - 2)"), "x");
+        assert_eq!(simplify("x / 1 - 0"), "x");
+        assert_eq!(simplify("(x + y) - (x + y)"), "x + y - (x + y)");
+        assert_eq!(simplify("(3 - 1) - 2 * 1"), "0");
+        assert_eq!(simplify("2 * x - x * (4 / 2)"), "2 * x - x * 2");
+        assert_eq!(simplify("--x"), "x");
+        assert_eq!(simplify("let y = 1 + 1 in y * 1"), "let y = 2 in y");
+
+        // `x - x` is kept if evaluating `x` may fail.
+        assert_eq!(simplify("y - y"), "y - y");
+        assert_eq!(simplify("1/0 - 1/0"), "1 / 0 - 1 / 0");
+        assert_eq!(simplify("0/0 - 0/0"), "0 / 0 - 0 / 0");
+        assert_eq!(simplify("sqrt(-1) - sqrt(-1)"), "sqrt(-1) - sqrt(-1)");
+        assert_eq!(simplify("1e400 - 1e400"), "inf - inf");
+
+        // Simplification preserves the value.
+        let mut ctx = context::Context::new();
+        let _unused = ctx.calc_command(&parser::parse_command("x = 3").unwrap());
+        for line in ["x * (2 ^ 3 - 1)", "(x - x) * 5 + 2 ^ 1", "--x! + 0 * 1"] {
+            let expression = parser::parse_command(line).unwrap().expression;
+            assert_eq!(
+                ctx.calc_expression(&expression.simplify()).unwrap(),
+                ctx.calc_expression(&expression).unwrap(),
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_vm() {
+        let lines = [
+            "1 + 2 * 3 - 4 / 5",
+            "2 ^ 3 ^ 2 + (-2) ^ 3",
+            "-x! + y * z",
+            "x / 4 + 1 / 3",
+            "0.1 + 0.2 == 0.3",
+            "x > 2 && y < 1 || !z",
+            "0 && 1 / 0",
+            "1 || 1 / 0",
+            "1 && 0 || 2",
+            "sin(x) + cos(y) * tan(z) + abs(-x) + sqrt(16) + ln(e) + log10(100) + exp(0)",
+            "history(1) + history(2) + ans",
+            "let x = 10 in let y = x * 2 in x + y + z",
+            "let x = 1 in (let x = 2 in x) + x",
+            "let a = (let b = 3 in b * b) in a + (let c = a in c)",
+            "2 ^ 40 * 3",
+            "20! / 18!",
+            "sum(i, 1, 10, i ^ 2) + prod(i, 1, 5, i)",
+            "sum(i, 3, 1, i) + prod(i, 3, 1, i)",
+            "sum(i, 1, x, sum(j, 1, i, i * j + x))",
+            "sum(i, -2, 2, let y = i in y * 2) || 0",
+            "prod(i, 1, 3, 1 / (i + 1)) * sum(x, 0, x, x)",
+            "diff(x ^ 3 + y * x) + diff(sin(x) * z, 0)",
+            "let x = 2 in diff(k * x ^ 2) + 0 * diff(x, k)",
+            "sum(x, 1, 3, diff(x ^ 2))",
+            // Errors.
+            "1 / (x - 3)",
+            "0 ^ -1",
+            "sqrt(-x) + 1",
+            "w + 1",
+            "(1.5)!",
+            "history(10)",
+            "let pi = 3 in pi",
+            "let w = 1 in w + v",
+            "1 && 1 / 0",
+            "sum(i, 1, y, i)",
+            "sum(i, 1, 3, 1 / (i - 2))",
+            "prod(e, 1, 2, e)",
+            "diff(abs(x))",
+            "diff(ln(x), 0)",
+            "diff(w * x)",
+        ];
+
+        for mode in [
+            context::Mode::Float,
+            context::Mode::Rational,
+            context::Mode::Integer,
+        ] {
+            let mut ctx = context::Context::with_mode(mode);
+            for command in ["x = 3", "y = 1 / 2", "z = 0", "k = 5"] {
+                let _unused = ctx.calc_command(&parser::parse_command(command).unwrap());
+            }
+
+            let mut vm = Vm::new();
+            for line in lines {
+                let expression = parser::parse_command(line).unwrap().expression;
+                let program = Program::compile(&expression);
+                match (
+                    ctx.calc_expression_value(&expression),
+                    vm.run(&program, &ctx),
+                ) {
+                    (Ok(expected), Ok(actual)) => {
+                        assert_eq!(actual.to_string(), expected.to_string(), "{}", line)
+                    }
+                    (Err(expected), Err(actual)) => {
+                        assert_eq!(actual.to_string(), expected.to_string(), "{}", line)
+                    }
+                    (expected, actual) => {
+                        panic!("{}: expected {:?}, found {:?}", line, expected, actual)
+                    }
+                }
+            }
+        }
+
+        // A program can be compiled once and run many times.
+        let program = Program::compile(&parser::parse_command("x ^ 2 + 1").unwrap().expression);
+        let mut vm = Vm::new();
+        for x in 0..10 {
+            let mut ctx = context::Context::new();
+            let _unused = ctx.calc_command(&parser::parse_command(&format!("x = {}", x)).unwrap());
+            assert_eq!(vm.run(&program, &ctx).unwrap(), (x * x + 1) as f64);
+        }
+    }
+
+    #[test]
+    fn test_output_format() {
+        // Scientific notation.
+        let mut ctx = context::Context::new();
+        let mut calc = |line: &str| ctx.calc_command(&parser::parse_command(line)?);
+        assert_eq!(calc("1.5e-3").unwrap().1, 0.0015);
+        assert_eq!(calc("2E3 + 1e+2").unwrap().1, 2100.0);
+        assert_eq!(calc("-1.5e-3 * 1e3").unwrap().1, -1.5);
+        assert!(calc("1.5e").is_err());
+
+        // Settings.
+        assert_eq!(
+            parser::parse_statement("set precision 3").unwrap(),
+            Some(Statement::Set(Setting::Precision(Some(3))))
+        );
+        assert_eq!(
+            parser::parse_statement("set precision auto # default").unwrap(),
+            Some(Statement::Set(Setting::Precision(None)))
+        );
+        assert_eq!(
+            parser::parse_statement("set format sci").unwrap(),
+            Some(Statement::Set(Setting::Format(Format::Sci)))
+        );
+        assert!(parser::parse_statement("set format hex").is_err());
+        assert!(parser::parse_statement("set precision -1").is_err());
+        assert!(parser::parse_line("set format fixed").is_err());
+        assert!(parser::parse_command("set precision 3").is_err());
+
+        // `set` is still a variable.
+        assert_eq!(
+            parser::parse_command("set = 1").unwrap().variable,
+            Some("set".into())
+        );
+
+        // Rendering.
+        let mut ctx = context::Context::new();
+        let render = |ctx: &context::Context, v: Value| ctx.render(&v);
+        assert_eq!(render(&ctx, Value::Float(1500.0)), "1500");
+        assert_eq!(render(&ctx, Value::Float(1.5e-7)), "1.5e-7");
+        assert_eq!(render(&ctx, Value::Float(2e20)), "2e20");
+        assert_eq!(render(&ctx, Value::Float(0.0)), "0");
+        assert_eq!(render(&ctx, Value::exact(0.25)), "1/4");
+
+        ctx.set(Setting::Precision(Some(3)));
+        assert_eq!(render(&ctx, Value::Float(2.0 / 3.0)), "0.667");
+        assert_eq!(render(&ctx, Value::Float(1.5e-7)), "1.500e-7");
+        assert_eq!(render(&ctx, Value::exact(0.25)), "1/4");
+
+        ctx.set(Setting::Format(Format::Fixed));
+        assert_eq!(render(&ctx, Value::Float(1.5e-7)), "0.000");
+        assert_eq!(render(&ctx, Value::exact(0.25)), "0.250");
+        assert_eq!(render(&ctx, Value::Int(42)), "42.000");
+
+        ctx.set(Setting::Format(Format::Sci));
+        assert_eq!(render(&ctx, Value::Float(1500.0)), "1.500e3");
+        assert_eq!(render(&ctx, Value::Int(-42)), "-4.200e1");
+
+        ctx.set(Setting::Precision(None));
+        assert_eq!(render(&ctx, Value::Float(1500.0)), "1.5e3");
+        ctx.set(Setting::Format(Format::Fixed));
+        assert_eq!(render(&ctx, Value::Float(1.5e-7)), "0.00000015");
+
+        // Integers keep all of their digits.
+        let mut ctx = context::Context::with_mode(context::Mode::Integer);
+        let (_, value) = ctx
+            .calc_command_value(&parser::parse_command("2 ^ 100").unwrap())
+            .unwrap();
+        ctx.set(Setting::Format(Format::Fixed));
+        assert_eq!(ctx.render(&value), "1267650600228229401496703205376");
+
+        // Scripts may have settings.
+        let mut ctx = context::Context::new();
+        let variables = ctx
+            .run_script("set format sci\nset precision 2\nx = 1234")
+            .unwrap()
+            .clone();
+        assert_eq!(ctx.render(&variables["x"]), "1.23e3");
+    }
+
+    #[test]
+    fn test_series() {
+        let mut ctx = context::Context::new();
+        let mut calc = |line: &str| ctx.calc_command(&parser::parse_command(line)?);
+
+        assert_eq!(calc("sum(i, 1, 100, i)").unwrap().1, 5050.0);
+        assert_eq!(calc("prod(i, 1, 5, i)").unwrap().1, 120.0);
+        assert_eq!(calc("sum(k, 0, 3, 2 ^ k) * 2").unwrap().1, 30.0);
+        assert_about_eq!(
+            calc("sum(n, 0, 20, 1 / n!)").unwrap().1,
+            std::f64::consts::E
+        );
+        assert_about_eq!(
+            calc("4 * sum(k, 0, 100000, (-1) ^ k / (2 * k + 1))")
+                .unwrap()
+                .1,
+            std::f64::consts::PI,
+            1e-5
+        );
+
+        // Empty ranges are the identities.
+        assert_eq!(calc("sum(i, 1, 0, i)").unwrap().1, 0.0);
+        assert_eq!(calc("prod(i, 1, 0, i)").unwrap().1, 1.0);
+
+        // Bounds may depend on variables, and the loop variable shadows them.
+        assert_eq!(calc("n = 4").unwrap().1, 4.0);
+        assert_eq!(calc("sum(i, 1, n, sum(j, 1, i, 1))").unwrap().1, 10.0);
+        assert_eq!(calc("sum(n, 1, n, n)").unwrap().1, 10.0);
+        assert_eq!(calc("n").unwrap().1, 4.0);
+        assert!(calc("i").is_err());
+
+        // `sum` and `prod` are still variables.
+        assert_eq!(calc("sum = 3").unwrap().1, 3.0);
+        assert_eq!(calc("sum + prod(i, 2, 3, i)").unwrap().1, 9.0);
+
+        // Errors.
+        assert!(calc("sum(i, 1, 2.5, i)").is_err());
+        assert!(calc("sum(i, 1, 3)").is_err());
+        assert!(calc("sum(1, 1, 3, 1)").is_err());
+        assert!(calc("sum(pi, 1, 3, pi)").is_err());
+
+        // Exact modes.
+        let mut ctx = context::Context::with_mode(context::Mode::Rational);
+        assert_eq!(
+            ctx.calc_command_value(&parser::parse_command("sum(i, 1, 3, 1 / i)").unwrap())
+                .unwrap()
+                .1
+                .to_string(),
+            "11/6"
+        );
+        let mut ctx = context::Context::with_mode(context::Mode::Integer);
+        assert_eq!(
+            ctx.calc_command_value(&parser::parse_command("prod(i, 1, 25, i)").unwrap())
+                .unwrap()
+                .1
+                .to_string(),
+            "15511210043330985984000000"
+        );
+
+        assert_eq!(
+            parser::parse_command("sum(i,1,10,(i^2))")
+                .unwrap()
+                .expression
+                .to_string(),
+            "sum(i, 1, 10, i ^ 2)"
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut ctx = context::Context::new();
+        let mut calc = |line: &str| ctx.calc_command(&parser::parse_command(line)?);
+
+        assert_eq!(calc("diff(x ^ 2, 3)").unwrap().1, 6.0);
+        assert_eq!(calc("diff(3 * x ^ 4 - 2 * x + 7, 1)").unwrap().1, 10.0);
+        assert_eq!(calc("diff(x ^ 2, -3)").unwrap().1, -6.0);
+        assert_eq!(calc("diff(1 / x, 2)").unwrap().1, -0.25);
+        assert_eq!(calc("diff(-x, 5)").unwrap().1, -1.0);
+        assert_about_eq!(calc("diff(sin(x), 0)").unwrap().1, 1.0);
+        assert_about_eq!(calc("diff(cos(2 * x), pi / 4)").unwrap().1, -2.0);
+        assert_about_eq!(calc("diff(tan(x), 0)").unwrap().1, 1.0);
+        assert_about_eq!(
+            calc("diff(exp(x ^ 2), 1)").unwrap().1,
+            2.0 * std::f64::consts::E
+        );
+        assert_about_eq!(
+            calc("diff(e ^ x, 2)").unwrap().1,
+            std::f64::consts::E.powi(2)
+        );
+        assert_about_eq!(calc("diff(2 ^ x, 3)").unwrap().1, 8.0 * 2f64.ln());
+        assert_about_eq!(calc("diff(ln(x), 4)").unwrap().1, 0.25);
+        assert_about_eq!(calc("diff(log10(x), 1)").unwrap().1, 1.0 / 10f64.ln());
+        assert_about_eq!(calc("diff(sqrt(x), 4)").unwrap().1, 0.25);
+        assert_about_eq!(calc("diff(x ^ x, 1)").unwrap().1, 1.0);
+
+        // Without a point, the derivative is at `x`.
+        assert_eq!(calc("x = 2").unwrap().1, 2.0);
+        assert_eq!(calc("diff(x ^ 3)").unwrap().1, 12.0);
+        assert_eq!(calc("let x = 1 in diff(x ^ 3)").unwrap().1, 3.0);
+        assert_eq!(calc("sum(x, 1, 3, diff(x ^ 2))").unwrap().1, 12.0);
+
+        // The other variables are constants.
+        assert_eq!(calc("a = 5").unwrap().1, 5.0);
+        assert_eq!(calc("diff(a * x ^ 2 + a, 1)").unwrap().1, 10.0);
+        assert_eq!(calc("let a = 3 in diff(a * x, 0)").unwrap().1, 3.0);
+        assert_about_eq!(calc("diff(pi * x, 0)").unwrap().1, std::f64::consts::PI);
+
+        // Errors.
+        assert!(calc("diff(abs(x), 1)").is_err());
+        assert!(calc("diff(x!, 1)").is_err());
+        assert!(calc("diff(x > 1, 1)").is_err());
+        assert!(calc("diff(1 / x, 0)").is_err());
+        assert!(calc("diff(b * x, 1)").is_err());
+        assert!(calc("diff()").is_err());
+
+        assert_eq!(
+            parser::parse_command("diff((x ^ 2), (1 + 2))")
+                .unwrap()
+                .expression
+                .to_string(),
+            "diff(x ^ 2, 1 + 2)"
+        );
+    }
 }
@@ -25,6 +25,10 @@
 //! and submit the generated `assignment04.zip` file in `target` directory.
 
 pub mod context;
+pub mod derivative;
 mod grade;
 pub mod parser;
+mod simplify;
 pub mod syntax;
+pub mod value;
+pub mod vm;
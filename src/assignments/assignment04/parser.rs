@@ -1,12 +1,14 @@
-#![allow(deprecated)]
-
 //! Parser.
 
+use std::fmt;
+use std::ops::Range;
+
 use anyhow::{bail, Result};
 use etrace::*;
 use lazy_static::*;
+use pest::error::{ErrorVariant, InputLocation};
 use pest::iterators::{Pair, Pairs};
-use pest::prec_climber::*;
+use pest::pratt_parser::*;
 use pest::Parser;
 
 use super::syntax::*;
@@ -27,17 +29,38 @@ use inner::*;
 ///
 /// ## Operator Associativty
 ///
-/// For associativity of each operator, please follow [here](https://docs.rs/pest/latest/pest/prec_climber/struct.PrecClimber.html#examples).
+/// For associativity of each operator, please follow [here](https://docs.rs/pest/latest/pest/pratt_parser/struct.PrattParser.html).
 ///
 /// e.g. `1+2+3` should be parsed into `(1+2)+3`, not `1+(2+3)` because the associativity of
 /// plus("add" in our hw) operator is `Left`.
 pub fn parse_command(line: &str) -> Result<Command> {
-    let mut pairs = SyntaxParser::parse(Rule::command, line)
-        .map_err(|e| anyhow::anyhow!("Parse error: {}", e))?;
+    parse_line(line)?.ok_or_else(|| anyhow::anyhow!("Empty command"))
+}
+
+/// Parses a line, which is either a command or blank. A line may end with a `# ...` comment.
+///
+/// Returns `None` if the line is blank or only has a comment.
+pub fn parse_line(line: &str) -> Result<Option<Command>> {
+    match parse_statement(line)? {
+        Some(Statement::Command(command)) => Ok(Some(command)),
+        Some(Statement::Set(_)) => bail!("Expected command, found setting"),
+        None => Ok(None),
+    }
+}
+
+/// Parses a line, which is either a command, a setting, or blank, like `parse_line`.
+pub fn parse_statement(line: &str) -> Result<Option<Statement>> {
+    let mut pairs =
+        SyntaxParser::parse(Rule::command, line).map_err(|e| ParseError::new(line, e))?;
 
     let first = pairs
         .next()
         .ok_or_else(|| anyhow::anyhow!("Empty command"))?;
+    match first.as_rule() {
+        Rule::EOI => return Ok(None),
+        Rule::precision | Rule::format => return parse_setting(first).map(Some),
+        _ => {}
+    }
 
     let (variable, expression) = if first.as_rule() == Rule::var {
         let expr = pairs
@@ -48,40 +71,156 @@ pub fn parse_command(line: &str) -> Result<Command> {
         (None, parse_expression(first)?)
     };
 
-    Ok(Command {
+    Ok(Some(Statement::Command(Command {
         variable,
         expression,
-    })
+    })))
 }
 
-lazy_static::lazy_static! {
-    static ref PREC_CLIMBER: PrecClimber<Rule> = PrecClimber::new(vec![
-        Operator::new(Rule::subtract, Assoc::Left) |
-        Operator::new(Rule::add, Assoc::Left),
-        Operator::new(Rule::divide, Assoc::Left) |
-        Operator::new(Rule::multiply, Assoc::Left),
+fn parse_setting(pair: Pair<'_, Rule>) -> Result<Statement> {
+    let rule = pair.as_rule();
+    let value = pair
+        .into_inner()
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Expected value of setting"))?;
+    let setting = match (rule, value.as_rule()) {
+        (Rule::precision, Rule::digits) => Setting::Precision(Some(value.as_str().parse()?)),
+        (Rule::precision, Rule::auto) => Setting::Precision(None),
+        (Rule::format, Rule::auto) => Setting::Format(Format::Auto),
+        (Rule::format, Rule::fixed) => Setting::Format(Format::Fixed),
+        (Rule::format, Rule::sci) => Setting::Format(Format::Sci),
+        _ => bail!("Unexpected setting: {}", value.as_str()),
+    };
+    Ok(Statement::Set(setting))
+}
 
+/// Parse error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Byte range of the line where parsing failed.
+    pub span: Range<usize>,
+    /// What was expected, e.g., `number` or `` `)` ``.
+    pub expected: Vec<String>,
+    /// What was found instead, or `None` at the end of the line.
+    pub found: Option<String>,
+}
+
+impl ParseError {
+    fn new(line: &str, error: pest::error::Error<Rule>) -> Self {
+        let span = match error.location {
+            InputLocation::Pos(pos) => {
+                pos..line[pos..]
+                    .chars()
+                    .next()
+                    .map_or(pos, |c| pos + c.len_utf8())
+            }
+            InputLocation::Span((start, end)) => start..end,
+        };
+        let expected = match error.variant {
+            ErrorVariant::ParsingError { positives, .. } => {
+                positives.into_iter().map(describe).collect()
+            }
+            ErrorVariant::CustomError { message } => vec![message],
+        };
+        let found = Some(&line[span.clone()]).filter(|found| !found.is_empty());
+        Self {
+            span,
+            expected,
+            found: found.map(str::to_string),
+        }
+    }
+}
 
-        Operator::new(Rule::power, Assoc::Right),
-    ]);
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Parse error at column {}: expected ",
+            self.span.start + 1
+        )?;
+        match self.expected.split_last() {
+            None => write!(f, "nothing")?,
+            Some((last, [])) => write!(f, "{}", last)?,
+            Some((last, init)) => write!(f, "{} or {}", init.join(", "), last)?,
+        }
+        match &self.found {
+            Some(found) => write!(f, ", found `{}`", found),
+            None => write!(f, ", found end of line"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Describes `rule` for error messages.
+fn describe(rule: Rule) -> String {
+    let description = match rule {
+        Rule::num => "number",
+        Rule::var => "variable",
+        Rule::ident | Rule::call | Rule::series | Rule::derivative => "function",
+        Rule::keyword | Rule::let_in => "`let`",
+        Rule::expr => "expression",
+        Rule::EOI => "end of line",
+        Rule::digits => "number",
+        Rule::precision => "`precision`",
+        Rule::format => "`format`",
+        Rule::auto => "`auto`",
+        Rule::fixed => "`fixed`",
+        Rule::sci => "`sci`",
+        Rule::add => "`+`",
+        Rule::subtract | Rule::neg => "`-`",
+        Rule::multiply => "`*`",
+        Rule::divide => "`/`",
+        Rule::power => "`^`",
+        Rule::eq => "`==`",
+        Rule::ne => "`!=`",
+        Rule::le => "`<=`",
+        Rule::lt => "`<`",
+        Rule::ge => "`>=`",
+        Rule::gt => "`>`",
+        Rule::and => "`&&`",
+        Rule::or => "`||`",
+        Rule::not | Rule::factorial => "`!`",
+        _ => return format!("{:?}", rule),
+    };
+    description.to_string()
+}
+
+lazy_static::lazy_static! {
+    static ref PRATT_PARSER: PrattParser<Rule> = PrattParser::new()
+        .op(Op::infix(Rule::or, Assoc::Left))
+        .op(Op::infix(Rule::and, Assoc::Left))
+        .op(Op::infix(Rule::eq, Assoc::Left)
+            | Op::infix(Rule::ne, Assoc::Left)
+            | Op::infix(Rule::lt, Assoc::Left)
+            | Op::infix(Rule::le, Assoc::Left)
+            | Op::infix(Rule::gt, Assoc::Left)
+            | Op::infix(Rule::ge, Assoc::Left))
+        .op(Op::infix(Rule::subtract, Assoc::Left) | Op::infix(Rule::add, Assoc::Left))
+        .op(Op::infix(Rule::divide, Assoc::Left) | Op::infix(Rule::multiply, Assoc::Left))
+        .op(Op::prefix(Rule::neg) | Op::prefix(Rule::not))
+        .op(Op::infix(Rule::power, Assoc::Right))
+        .op(Op::postfix(Rule::factorial));
 }
 
 fn parse_expression(pair: Pair<'_, Rule>) -> Result<Expression> {
-    PREC_CLIMBER.climb(
-        pair.into_inner(),
-        |pair| match pair.as_rule() {
-            Rule::num => Ok(Expression::Num(pair.as_str().parse()?)),
-            Rule::var => Ok(Expression::Variable(pair.as_str().to_string())),
-            Rule::expr => parse_expression(pair),
-            _ => bail!("Unexpected rule: {:?}", pair.as_rule()),
-        },
-        |lhs, op, rhs| {
+    PRATT_PARSER
+        .map_primary(parse_primary)
+        .map_infix(|lhs, op, rhs| {
             let op = match op.as_rule() {
                 Rule::add => BinOp::Add,
                 Rule::subtract => BinOp::Subtract,
                 Rule::multiply => BinOp::Multiply,
                 Rule::divide => BinOp::Divide,
                 Rule::power => BinOp::Power,
+                Rule::eq => BinOp::Eq,
+                Rule::ne => BinOp::Ne,
+                Rule::lt => BinOp::Lt,
+                Rule::le => BinOp::Le,
+                Rule::gt => BinOp::Gt,
+                Rule::ge => BinOp::Ge,
+                Rule::and => BinOp::And,
+                Rule::or => BinOp::Or,
                 _ => bail!("Unknown operator: {:?}", op.as_rule()),
             };
             Ok(Expression::BinOp {
@@ -89,6 +228,105 @@ fn parse_expression(pair: Pair<'_, Rule>) -> Result<Expression> {
                 lhs: Box::new(lhs?),
                 rhs: Box::new(rhs?),
             })
-        },
-    )
+        })
+        .map_prefix(|op, expr| {
+            let op = match op.as_rule() {
+                Rule::neg => UnaryOp::Neg,
+                Rule::not => UnaryOp::Not,
+                _ => bail!("Unknown operator: {:?}", op.as_rule()),
+            };
+            Ok(Expression::UnaryOp {
+                op,
+                expr: Box::new(expr?),
+            })
+        })
+        .map_postfix(|expr, op| match op.as_rule() {
+            Rule::factorial => Ok(Expression::UnaryOp {
+                op: UnaryOp::Factorial,
+                expr: Box::new(expr?),
+            }),
+            _ => bail!("Unknown operator: {:?}", op.as_rule()),
+        })
+        .parse(pair.into_inner())
+}
+
+fn parse_primary(pair: Pair<'_, Rule>) -> Result<Expression> {
+    match pair.as_rule() {
+        Rule::num => Ok(Expression::Num(pair.as_str().parse()?)),
+        Rule::var => Ok(Expression::Variable(pair.as_str().to_string())),
+        Rule::expr => parse_expression(pair),
+        Rule::let_in => {
+            let mut inner = pair.into_inner();
+            let (Some(var), Some(value), Some(body)) = (inner.next(), inner.next(), inner.next())
+            else {
+                bail!("Expected `let var = value in body`")
+            };
+            Ok(Expression::Let {
+                var: var.as_str().to_string(),
+                value: Box::new(parse_expression(value)?),
+                body: Box::new(parse_expression(body)?),
+            })
+        }
+        Rule::series => {
+            let mut inner = pair.into_inner();
+            let (Some(series), Some(var), Some(from), Some(to), Some(body)) = (
+                inner.next(),
+                inner.next(),
+                inner.next(),
+                inner.next(),
+                inner.next(),
+            ) else {
+                bail!("Expected `sum(var, from, to, body)` or `prod(var, from, to, body)`")
+            };
+            Ok(Expression::Series {
+                series: match series.as_rule() {
+                    Rule::sum => Series::Sum,
+                    _ => Series::Product,
+                },
+                var: var.as_str().to_string(),
+                from: Box::new(parse_expression(from)?),
+                to: Box::new(parse_expression(to)?),
+                body: Box::new(parse_expression(body)?),
+            })
+        }
+        Rule::derivative => {
+            let mut inner = pair.into_inner();
+            let expr = inner
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("Expected function"))?;
+            Ok(Expression::Diff {
+                expr: Box::new(parse_expression(expr)?),
+                at: inner
+                    .next()
+                    .map(|at| parse_expression(at).map(Box::new))
+                    .transpose()?,
+            })
+        }
+        Rule::call => {
+            let mut inner = pair.into_inner();
+            let name = inner
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("Expected function name"))?;
+            let arg = inner
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("Expected argument"))?;
+            let func = match name.as_str() {
+                "sin" => Function::Sin,
+                "cos" => Function::Cos,
+                "tan" => Function::Tan,
+                "sqrt" => Function::Sqrt,
+                "ln" => Function::Ln,
+                "log10" => Function::Log10,
+                "exp" => Function::Exp,
+                "abs" => Function::Abs,
+                "history" => Function::History,
+                name => bail!("Unknown function: {}", name),
+            };
+            Ok(Expression::Call {
+                func,
+                arg: Box::new(parse_expression(arg)?),
+            })
+        }
+        _ => bail!("Unexpected rule: {:?}", pair.as_rule()),
+    }
 }
@@ -0,0 +1,127 @@
+//! Simplification.
+
+use super::context::{Context, Mode};
+use super::syntax::{BinOp, Expression, Function, UnaryOp};
+
+impl Expression {
+    /// Returns an equivalent expression that is cheaper to evaluate.
+    ///
+    /// - Constant subtrees are folded, e.g., `2 * 3 + x` is `6 + x`.
+    /// - Identities are removed, e.g., `x * 1`, `x + 0` and `x ^ 1` are `x`.
+    ///
+    /// A constant subtree is folded only if the folded number has the same value in every [`Mode`],
+    /// e.g., `1 / 3` is kept since it is not a decimal, and `1 / 0` is kept to fail when evaluated.
+    ///
+    /// `x - x` is deliberately not rewritten to `0`, since evaluating `x` may fail, e.g., if it is
+    /// an undefined variable or `1 / 0`, and `x - x` is not `0` if `x` is infinite. If `x` is a
+    /// number, it is folded as any other constant subtree.
+    pub fn simplify(&self) -> Self {
+        let simplified = match self {
+            Self::Num(_) | Self::Variable(_) => return self.clone(),
+            Self::BinOp { op, lhs, rhs } => {
+                let (lhs, rhs) = (lhs.simplify(), rhs.simplify());
+                match (op, &lhs, &rhs) {
+                    (BinOp::Add, e, Self::Num(n)) | (BinOp::Add, Self::Num(n), e) if *n == 0.0 => {
+                        return e.clone()
+                    }
+                    (BinOp::Subtract, e, Self::Num(n)) if *n == 0.0 => return e.clone(),
+                    (BinOp::Multiply, e, Self::Num(n)) | (BinOp::Multiply, Self::Num(n), e)
+                        if *n == 1.0 =>
+                    {
+                        return e.clone()
+                    }
+                    (BinOp::Divide | BinOp::Power, e, Self::Num(n)) if *n == 1.0 => {
+                        return e.clone()
+                    }
+                    _ => Self::BinOp {
+                        op: *op,
+                        lhs: Box::new(lhs),
+                        rhs: Box::new(rhs),
+                    },
+                }
+            }
+            Self::UnaryOp { op, expr } => match (op, expr.simplify()) {
+                (
+                    UnaryOp::Neg,
+                    Self::UnaryOp {
+                        op: UnaryOp::Neg,
+                        expr,
+                    },
+                ) => return *expr,
+                (op, expr) => Self::UnaryOp {
+                    op: *op,
+                    expr: Box::new(expr),
+                },
+            },
+            Self::Call { func, arg } => Self::Call {
+                func: *func,
+                arg: Box::new(arg.simplify()),
+            },
+            Self::Let { var, value, body } => {
+                return Self::Let {
+                    var: var.clone(),
+                    value: Box::new(value.simplify()),
+                    body: Box::new(body.simplify()),
+                }
+            }
+            Self::Series {
+                series,
+                var,
+                from,
+                to,
+                body,
+            } => {
+                return Self::Series {
+                    series: *series,
+                    var: var.clone(),
+                    from: Box::new(from.simplify()),
+                    to: Box::new(to.simplify()),
+                    body: Box::new(body.simplify()),
+                }
+            }
+            Self::Diff { expr, at } => {
+                return Self::Diff {
+                    expr: Box::new(expr.simplify()),
+                    at: at.as_ref().map(|at| Box::new(at.simplify())),
+                }
+            }
+        };
+        simplified.fold().unwrap_or(simplified)
+    }
+
+    /// Folds `self` into a number if its operands are numbers.
+    fn fold(&self) -> Option<Self> {
+        let constant = match self {
+            Self::BinOp { lhs, rhs, .. } => {
+                matches!(**lhs, Self::Num(_)) && matches!(**rhs, Self::Num(_))
+            }
+            Self::UnaryOp { expr, .. } => matches!(**expr, Self::Num(_)),
+            Self::Call {
+                func: Function::History,
+                ..
+            } => false,
+            Self::Call { arg, .. } => matches!(**arg, Self::Num(_)),
+            _ => false,
+        };
+        if !constant {
+            return None;
+        }
+
+        let v = Context::new().calc_expression(self).ok()?;
+        if !v.is_finite() {
+            return None;
+        }
+        let folded = Self::Num(v);
+        for mode in [Mode::Float, Mode::Rational, Mode::Integer] {
+            let ctx = Context::with_mode(mode);
+            let (expected, actual) = (
+                ctx.calc_expression_value(self).ok()?,
+                ctx.calc_expression_value(&folded).ok()?,
+            );
+            if expected.is_exact() != actual.is_exact() || expected != actual {
+                return None;
+            }
+        }
+        Some(folded)
+    }
+}
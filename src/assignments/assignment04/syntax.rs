@@ -1,5 +1,7 @@
 //! Syntax.
 
+use std::fmt;
+
 /// Command of the form "{expression}" or "{var} = {expression}".
 #[derive(Debug, Clone, PartialEq)]
 pub struct Command {
@@ -9,6 +11,36 @@ pub struct Command {
     pub expression: Expression,
 }
 
+/// Setting of how results are rendered, e.g., `set precision 3` or `set format sci`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    /// Number of digits after the decimal point, or `None` for as many as needed.
+    Precision(Option<usize>),
+    /// Notation.
+    Format(Format),
+}
+
+/// Notation of results.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Exact values as they are, and floats in scientific notation if they are too large or small.
+    #[default]
+    Auto,
+    /// Decimal notation, e.g., `1500.000`.
+    Fixed,
+    /// Scientific notation, e.g., `1.500e3`.
+    Sci,
+}
+
+/// Line of input, which is either a command or a setting.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    /// Command.
+    Command(Command),
+    /// Setting.
+    Set(Setting),
+}
+
 /// Binary operators.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BinOp {
@@ -22,6 +54,65 @@ pub enum BinOp {
     Divide,
     /// Power.
     Power,
+    /// Equal.
+    Eq,
+    /// Not equal.
+    Ne,
+    /// Less than.
+    Lt,
+    /// Less than or equal.
+    Le,
+    /// Greater than.
+    Gt,
+    /// Greater than or equal.
+    Ge,
+    /// Logical and.
+    And,
+    /// Logical or.
+    Or,
+}
+
+/// Unary operators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    /// Negate.
+    Neg,
+    /// Factorial.
+    Factorial,
+    /// Logical not.
+    Not,
+}
+
+/// Built-in functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Function {
+    /// Sine.
+    Sin,
+    /// Cosine.
+    Cos,
+    /// Tangent.
+    Tan,
+    /// Square root.
+    Sqrt,
+    /// Natural logarithm.
+    Ln,
+    /// Common logarithm.
+    Log10,
+    /// Exponential.
+    Exp,
+    /// Absolute value.
+    Abs,
+    /// `n`-th latest result.
+    History,
+}
+
+/// Series that accumulate their terms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Series {
+    /// Summation.
+    Sum,
+    /// Product.
+    Product,
 }
 
 /// Expression.
@@ -40,4 +131,184 @@ pub enum Expression {
         /// Rhs.
         rhs: Box<Expression>,
     },
+    /// Unary operation.
+    UnaryOp {
+        /// Operator.
+        op: UnaryOp,
+        /// Operand.
+        expr: Box<Expression>,
+    },
+    /// Function call.
+    Call {
+        /// Function.
+        func: Function,
+        /// Argument.
+        arg: Box<Expression>,
+    },
+    /// `let var = value in body`, where `var` is bound only in `body`.
+    Let {
+        /// Variable.
+        var: String,
+        /// Value.
+        value: Box<Expression>,
+        /// Body.
+        body: Box<Expression>,
+    },
+    /// `sum(var, from, to, body)` or `prod(var, from, to, body)`, where `var` is bound only in
+    /// `body` to each integer from `from` to `to`.
+    Series {
+        /// Series.
+        series: Series,
+        /// Variable.
+        var: String,
+        /// First value of `var`.
+        from: Box<Expression>,
+        /// Last value of `var`.
+        to: Box<Expression>,
+        /// Body.
+        body: Box<Expression>,
+    },
+    /// `diff(expr)` or `diff(expr, at)`, the derivative of `expr` by `x` at `x` or `at`.
+    Diff {
+        /// Function of `x`.
+        expr: Box<Expression>,
+        /// Point, or `None` for the value of `x`.
+        at: Option<Box<Expression>>,
+    },
+}
+
+impl fmt::Display for BinOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self {
+            Self::Add => "+",
+            Self::Subtract => "-",
+            Self::Multiply => "*",
+            Self::Divide => "/",
+            Self::Power => "^",
+            Self::Eq => "==",
+            Self::Ne => "!=",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::And => "&&",
+            Self::Or => "||",
+        };
+        write!(f, "{}", op)
+    }
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Sin => "sin",
+            Self::Cos => "cos",
+            Self::Tan => "tan",
+            Self::Sqrt => "sqrt",
+            Self::Ln => "ln",
+            Self::Log10 => "log10",
+            Self::Exp => "exp",
+            Self::Abs => "abs",
+            Self::History => "history",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for Series {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Sum => "sum",
+            Self::Product => "prod",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Expression {
+    /// Binding power, where operands of lower precedence than their operator are parenthesized.
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Let { .. } => 0,
+            Self::BinOp { op, .. } => match op {
+                BinOp::Or => 1,
+                BinOp::And => 2,
+                BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge => 3,
+                BinOp::Add | BinOp::Subtract => 4,
+                BinOp::Multiply | BinOp::Divide => 5,
+                BinOp::Power => 7,
+            },
+            Self::UnaryOp {
+                op: UnaryOp::Neg | UnaryOp::Not,
+                ..
+            } => 6,
+            Self::Num(v) if v.is_sign_negative() => 6,
+            Self::UnaryOp {
+                op: UnaryOp::Factorial,
+                ..
+            } => 8,
+            Self::Num(_)
+            | Self::Variable(_)
+            | Self::Call { .. }
+            | Self::Series { .. }
+            | Self::Diff { .. } => 9,
+        }
+    }
+}
+
+/// Prints parseable source with minimal parentheses, e.g., `(1 + 2) * -x!`.
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        /// Operand that is parenthesized if its precedence is lower than `min`. `let` is always
+        /// parenthesized since its body would extend to the rest of the expression.
+        struct Operand<'a>(&'a Expression, u8);
+
+        impl fmt::Display for Operand<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let Self(expr, min) = *self;
+                if matches!(expr, Expression::Let { .. }) || expr.precedence() < min {
+                    write!(f, "({})", expr)
+                } else {
+                    write!(f, "{}", expr)
+                }
+            }
+        }
+
+        let prec = self.precedence();
+        match self {
+            Self::Num(v) => write!(f, "{}", v),
+            Self::Variable(s) => write!(f, "{}", s),
+            // `^` is right-associative, and its rhs may be a prefix operation, e.g., `2 ^ -x`.
+            Self::BinOp {
+                op: BinOp::Power,
+                lhs,
+                rhs,
+            } => write!(f, "{} ^ {}", Operand(lhs, prec + 1), Operand(rhs, prec - 1)),
+            Self::BinOp { op, lhs, rhs } => {
+                write!(
+                    f,
+                    "{} {} {}",
+                    Operand(lhs, prec),
+                    op,
+                    Operand(rhs, prec + 1)
+                )
+            }
+            Self::UnaryOp { op, expr } => match op {
+                UnaryOp::Neg => write!(f, "-{}", Operand(expr, prec)),
+                UnaryOp::Not => write!(f, "!{}", Operand(expr, prec)),
+                UnaryOp::Factorial => write!(f, "{}!", Operand(expr, prec)),
+            },
+            Self::Call { func, arg } => write!(f, "{}({})", func, arg),
+            Self::Let { var, value, body } => write!(f, "let {} = {} in {}", var, value, body),
+            Self::Series {
+                series,
+                var,
+                from,
+                to,
+                body,
+            } => write!(f, "{}({}, {}, {}, {})", series, var, from, to, body),
+            Self::Diff { expr, at: None } => write!(f, "diff({})", expr),
+            Self::Diff { expr, at: Some(at) } => write!(f, "diff({}, {})", expr, at),
+        }
+    }
 }
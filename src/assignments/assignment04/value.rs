@@ -0,0 +1,315 @@
+//! Values.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::assignments::assignment06::symbolic_differentiation::{Evaluate, Rational};
+use crate::assignments::assignment09::bigint::BigInt;
+
+/// The largest number of bits of an exact integer power. Larger powers fall back to `f64`, rather
+/// than taking too much time and memory.
+const MAX_POW_BITS: f64 = 65536.0;
+
+/// Value of an expression.
+///
+/// Operations on two exact values are exact if possible. Otherwise, e.g., `sqrt(2)` or `1/3 + pi`,
+/// they fall back to `f64`. Integers are promoted to `BigInt` on overflow, and demoted back to `i64`
+/// if they fit.
+#[derive(Debug, Clone)]
+pub enum Value {
+    /// Floating-point number.
+    Float(f64),
+    /// Exact rational number.
+    Rational(Rational),
+    /// Exact integer that fits in `i64`.
+    Int(i64),
+    /// Exact integer that does not fit in `i64`.
+    BigInt(BigInt),
+}
+
+impl Value {
+    /// Returns the exact value of `v` if it is a decimal that fits in `Rational`, e.g., `0.1` is
+    /// `1/10`. Otherwise, returns `Value::Float(v)`.
+    pub fn exact(v: f64) -> Self {
+        // `Display` of `f64` is the shortest decimal that round-trips, without exponents.
+        let s = v.abs().to_string();
+        let (int, frac) = s.split_once('.').unwrap_or((&s, ""));
+        let rational = (|| {
+            let denominator = 10isize.checked_pow(frac.len() as u32)?;
+            let numerator = int
+                .parse::<isize>()
+                .ok()?
+                .checked_mul(denominator)?
+                .checked_add(if frac.is_empty() {
+                    0
+                } else {
+                    frac.parse().ok()?
+                })?;
+            Some(Rational::new(numerator, denominator))
+        })();
+        match rational {
+            Some(r) if v.is_sign_negative() => Self::Rational(-r),
+            Some(r) => Self::Rational(r),
+            None => Self::Float(v),
+        }
+    }
+
+    /// Returns the exact integer `v` if it is an integer. Otherwise, returns `Value::Float(v)`.
+    ///
+    /// Note that integers beyond 2^53 may already have been rounded in `v`.
+    pub fn integer(v: f64) -> Self {
+        if !v.is_finite() || v.fract() != 0.0 {
+            return Self::Float(v);
+        }
+        if v.abs() < i64::MAX as f64 {
+            return Self::Int(v as i64);
+        }
+
+        // `v` is `mantissa * 2^exp` with a 53-bit mantissa.
+        let exp = v.abs().log2().floor() as u32 - 52;
+        let mantissa = (v / 2f64.powi(exp as i32)) as i64;
+        Self::from(BigInt::from(mantissa) * BigInt::from(2).pow(exp))
+    }
+
+    /// Converts `self` into `f64`.
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Self::Float(v) => *v,
+            Self::Rational(r) => r.evaluate(0.0),
+            Self::Int(n) => *n as f64,
+            Self::BigInt(n) => n.to_f64(),
+        }
+    }
+
+    /// Converts `self` into `BigInt` if it is an exact integer.
+    fn to_bigint(&self) -> Option<BigInt> {
+        match self {
+            Self::Int(n) => Some(BigInt::from(*n)),
+            Self::BigInt(n) => Some(n.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if `self` is exact.
+    pub fn is_exact(&self) -> bool {
+        !matches!(self, Self::Float(_))
+    }
+
+    /// Returns `true` if `self` is zero.
+    pub fn is_zero(&self) -> bool {
+        match self {
+            Self::BigInt(n) => n.is_zero(),
+            _ => self.to_f64() == 0.0,
+        }
+    }
+
+    /// Returns the value of `self` if it is an integer that fits in `isize`.
+    pub fn as_integer(&self) -> Option<isize> {
+        match self {
+            Self::Float(v) if v.fract() == 0.0 && v.abs() < isize::MAX as f64 => Some(*v as isize),
+            Self::Float(_) | Self::BigInt(_) => None,
+            Self::Rational(r) => r.as_integer(),
+            Self::Int(n) => isize::try_from(*n).ok(),
+        }
+    }
+
+    /// Applies `rational` to two rationals, `int` to two integers, or `float` otherwise. If
+    /// `rational` or `int` fails, falls back to `float`.
+    fn binary(
+        self,
+        rhs: Self,
+        rational: impl FnOnce(Rational, Rational) -> Option<Rational>,
+        int: impl FnOnce(BigInt, BigInt) -> Option<BigInt>,
+        float: impl FnOnce(f64, f64) -> f64,
+    ) -> Self {
+        match (&self, &rhs) {
+            (Self::Rational(l), Self::Rational(r)) => {
+                if let Some(result) = rational(*l, *r) {
+                    return Self::Rational(result);
+                }
+            }
+            (Self::Int(_) | Self::BigInt(_), Self::Int(_) | Self::BigInt(_)) => {
+                if let Some(result) = int(self.to_bigint().unwrap(), rhs.to_bigint().unwrap()) {
+                    return Self::from(result);
+                }
+            }
+            _ => {}
+        }
+        Self::Float(float(self.to_f64(), rhs.to_f64()))
+    }
+
+    /// Raises `self` to the power of `rhs`, which is exact for integer exponents (non-negative
+    /// ones for integers) unless the result has more than `MAX_POW_BITS` bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is zero and rational, and `rhs` is a negative integer.
+    pub fn pow(self, rhs: Self) -> Self {
+        let exp = rhs.as_integer();
+        self.binary(
+            rhs,
+            |l, _| l.checked_pow(i32::try_from(exp?).ok()?),
+            |l, _| {
+                let exp = u32::try_from(exp?).ok()?;
+                (l.to_f64().abs().log2() * exp as f64 <= MAX_POW_BITS).then(|| l.pow(exp))
+            },
+            f64::powf,
+        )
+    }
+
+    /// Returns the absolute value.
+    pub fn abs(self) -> Self {
+        match self {
+            Self::Float(v) => Self::Float(v.abs()),
+            Self::Rational(r) => Self::Rational(r.abs()),
+            n @ (Self::Int(_) | Self::BigInt(_)) if n < Self::Int(0) => -n,
+            n => n,
+        }
+    }
+}
+
+impl Add for Value {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        if let (Self::Int(l), Self::Int(r)) = (&self, &rhs) {
+            if let Some(n) = l.checked_add(*r) {
+                return Self::Int(n);
+            }
+        }
+        self.binary(
+            rhs,
+            |l, r| l.checked_add(r),
+            |l, r| Some(l + r),
+            |l, r| l + r,
+        )
+    }
+}
+
+impl Sub for Value {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        if let (Self::Int(l), Self::Int(r)) = (&self, &rhs) {
+            if let Some(n) = l.checked_sub(*r) {
+                return Self::Int(n);
+            }
+        }
+        self.binary(
+            rhs,
+            |l, r| l.checked_sub(r),
+            |l, r| Some(l + -r),
+            |l, r| l - r,
+        )
+    }
+}
+
+impl Mul for Value {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        if let (Self::Int(l), Self::Int(r)) = (&self, &rhs) {
+            if let Some(n) = l.checked_mul(*r) {
+                return Self::Int(n);
+            }
+        }
+        self.binary(
+            rhs,
+            |l, r| l.checked_mul(r),
+            |l, r| Some(l * r),
+            |l, r| l * r,
+        )
+    }
+}
+
+/// The quotient of integers is exact only if the division is.
+///
+/// Panics if `rhs` is zero and exact.
+impl Div for Value {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.binary(
+            rhs,
+            |l, r| l.checked_mul(r.reciprocal()),
+            |l, r| {
+                let (quotient, remainder) = l.div_rem(&r);
+                remainder.is_zero().then_some(quotient)
+            },
+            |l, r| l / r,
+        )
+    }
+}
+
+impl Neg for Value {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        match self {
+            Self::Float(v) => Self::Float(-v),
+            Self::Rational(r) => Self::Rational(-r),
+            Self::Int(n) => n
+                .checked_neg()
+                .map_or_else(|| Self::from(-BigInt::from(n)), Self::Int),
+            Self::BigInt(n) => Self::from(-n),
+        }
+    }
+}
+
+/// Values are compared by what they represent, e.g., `Float(0.5) == Rational(1/2)`.
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Self::Rational(l), Self::Rational(r)) => Some(l.cmp(r)),
+            (Self::Int(l), Self::Int(r)) => Some(l.cmp(r)),
+            (Self::Int(_) | Self::BigInt(_), Self::Int(_) | Self::BigInt(_)) => {
+                Some(self.to_bigint()?.cmp(&other.to_bigint()?))
+            }
+            _ => self.to_f64().partial_cmp(&other.to_f64()),
+        }
+    }
+}
+
+impl PartialEq<f64> for Value {
+    fn eq(&self, other: &f64) -> bool {
+        self.to_f64() == *other
+    }
+}
+
+impl From<f64> for Value {
+    fn from(v: f64) -> Self {
+        Self::Float(v)
+    }
+}
+
+impl From<Rational> for Value {
+    fn from(r: Rational) -> Self {
+        Self::Rational(r)
+    }
+}
+
+/// Integers that fit in `i64` are `Value::Int`.
+impl From<BigInt> for Value {
+    fn from(n: BigInt) -> Self {
+        n.to_i64().map_or(Self::BigInt(n), Self::Int)
+    }
+}
+
+/// Rationals are displayed as `p/q`, and integers in decimal.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Float(v) => write!(f, "{}", v),
+            Self::Rational(r) => write!(f, "{}", r),
+            Self::Int(n) => write!(f, "{}", n),
+            Self::BigInt(n) => write!(f, "{}", n.to_decimal()),
+        }
+    }
+}
@@ -0,0 +1,300 @@
+//! Bytecode virtual machine.
+//!
+//! Tree-walking in [`Context::calc_expression_value`] is the reference implementation. For
+//! evaluating the same expression many times, e.g., for different values of a variable, compile it
+//! into a [`Program`] once and run it with a [`Vm`], which gives the same results.
+
+use anyhow::Result;
+
+use super::context::{check_binding, CalcError, Context};
+use super::derivative::{free_variables, VARIABLE};
+use super::syntax::{BinOp, Expression, Function, Series, UnaryOp};
+use super::value::Value;
+
+/// Instruction of the stack machine.
+#[derive(Debug, Clone, PartialEq)]
+enum Instruction {
+    /// Pushes a number literal.
+    Num(f64),
+    /// Pushes a variable that is not bound by `let`.
+    Global(String),
+    /// Pushes the `n`-th local variable, counting from the outermost one.
+    Local(usize),
+    /// Pops rhs and lhs, and pushes the result.
+    Binary(BinOp),
+    /// Pops an operand, and pushes the result.
+    Unary(UnaryOp),
+    /// Pops an argument, and pushes the result.
+    Call(Function),
+    /// If the lhs of `&&` on the top is false, replaces it with false and jumps to the target.
+    And(usize),
+    /// If the lhs of `||` on the top is true, replaces it with true and jumps to the target.
+    Or(usize),
+    /// Fails if the variable cannot be bound by `let`.
+    CheckBinding(String),
+    /// Pops a value into a new local variable.
+    Bind,
+    /// Drops the innermost local variable.
+    Unbind,
+    /// Pops the last and first bounds of a series. If it is empty, pushes its value and jumps to
+    /// the target. Otherwise, pushes the initial value and binds the first bound.
+    Range(Series, usize),
+    /// Pops a term into the value of the series below it. If there are more terms, binds the next
+    /// bound and jumps to the target. Otherwise, drops the bound.
+    Accumulate(Series, usize),
+    /// Pops the values of the free variables of the function, and the point. Then, pushes the
+    /// derivative of the function at the point.
+    Diff(Box<Expression>),
+}
+
+/// Compiled expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Program {
+    code: Vec<Instruction>,
+    /// Sub-expression that each instruction comes from, for errors.
+    sources: Vec<Expression>,
+}
+
+impl Program {
+    /// Compiles `expression`.
+    pub fn compile(expression: &Expression) -> Self {
+        let mut program = Self {
+            code: Vec::new(),
+            sources: Vec::new(),
+        };
+        program.compile_inner(expression, &mut Vec::new());
+        program
+    }
+
+    /// Returns the number of instructions.
+    pub fn len(&self) -> usize {
+        self.code.len()
+    }
+
+    /// Returns `true` if there are no instructions.
+    pub fn is_empty(&self) -> bool {
+        self.code.is_empty()
+    }
+
+    fn emit(&mut self, instruction: Instruction, source: &Expression) {
+        self.code.push(instruction);
+        self.sources.push(source.clone());
+    }
+
+    /// Loads the variable `name`, where `locals` are the variables bound by `let`.
+    fn load(&mut self, name: &str, locals: &[&str]) {
+        let instruction = match locals.iter().rposition(|local| *local == name) {
+            Some(n) => Instruction::Local(n),
+            None => Instruction::Global(name.to_string()),
+        };
+        self.emit(instruction, &Expression::Variable(name.to_string()));
+    }
+
+    /// Compiles `expression`, where `locals` are the variables bound by `let` from the outermost.
+    fn compile_inner<'a>(&mut self, expression: &'a Expression, locals: &mut Vec<&'a str>) {
+        match expression {
+            Expression::Num(v) => {
+                self.emit(Instruction::Num(*v), expression);
+            }
+            Expression::Variable(s) => self.load(s, locals),
+            Expression::BinOp { op, lhs, rhs } => {
+                self.compile_inner(lhs, locals);
+                let jump = matches!(op, BinOp::And | BinOp::Or).then_some(self.code.len());
+                match op {
+                    BinOp::And => self.emit(Instruction::And(0), expression),
+                    BinOp::Or => self.emit(Instruction::Or(0), expression),
+                    _ => {}
+                }
+                self.compile_inner(rhs, locals);
+                self.emit(Instruction::Binary(*op), expression);
+
+                // Patches the jump to skip the rhs.
+                if let Some(jump) = jump {
+                    let target = self.code.len();
+                    match &mut self.code[jump] {
+                        Instruction::And(t) | Instruction::Or(t) => *t = target,
+                        _ => unreachable!(),
+                    }
+                }
+            }
+            Expression::UnaryOp { op, expr } => {
+                self.compile_inner(expr, locals);
+                self.emit(Instruction::Unary(*op), expression);
+            }
+            Expression::Call { func, arg } => {
+                self.compile_inner(arg, locals);
+                self.emit(Instruction::Call(*func), expression);
+            }
+            Expression::Let { var, value, body } => {
+                self.emit(Instruction::CheckBinding(var.clone()), expression);
+                self.compile_inner(value, locals);
+                self.emit(Instruction::Bind, expression);
+                locals.push(var);
+                self.compile_inner(body, locals);
+                let _ = locals.pop();
+                self.emit(Instruction::Unbind, expression);
+            }
+            Expression::Series {
+                series,
+                var,
+                from,
+                to,
+                body,
+            } => {
+                self.emit(Instruction::CheckBinding(var.clone()), expression);
+                self.compile_inner(from, locals);
+                self.compile_inner(to, locals);
+                let range = self.code.len();
+                self.emit(Instruction::Range(*series, 0), expression);
+                locals.push(var);
+                self.compile_inner(body, locals);
+                let _ = locals.pop();
+                self.emit(Instruction::Accumulate(*series, range + 1), expression);
+
+                // Patches the jump to skip the loop.
+                let target = self.code.len();
+                self.code[range] = Instruction::Range(*series, target);
+            }
+            Expression::Diff { expr, at } => {
+                match at {
+                    Some(at) => self.compile_inner(at, locals),
+                    None => self.load(VARIABLE, locals),
+                }
+                for var in free_variables(expr) {
+                    self.load(var, locals);
+                }
+                self.emit(Instruction::Diff(expr.clone()), expression);
+            }
+        }
+    }
+}
+
+/// Stack machine that runs [`Program`]s.
+///
+/// Its stacks are reused across runs.
+#[derive(Debug, Default, Clone)]
+pub struct Vm {
+    stack: Vec<Value>,
+    locals: Vec<Value>,
+    /// Current and last bounds of the series being calculated.
+    counters: Vec<(isize, isize)>,
+}
+
+impl Vm {
+    /// Creates a new virtual machine.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs `program`, where variables, history and the mode are those of `ctx`.
+    ///
+    /// Errors are `CalcError`s with the innermost sub-expression that failed, as in
+    /// [`Context::calc_expression_value`].
+    pub fn run(&mut self, program: &Program, ctx: &Context) -> Result<Value> {
+        self.stack.clear();
+        self.locals.clear();
+        self.counters.clear();
+
+        let mut pc = 0;
+        while let Some(instruction) = program.code.get(pc) {
+            pc = self
+                .step(instruction, pc + 1, ctx)
+                .map_err(|error| CalcError {
+                    expression: program.sources[pc].clone(),
+                    error,
+                })?;
+        }
+        Ok(self.pop())
+    }
+
+    /// Executes `instruction`, and returns the next program counter.
+    fn step(&mut self, instruction: &Instruction, next: usize, ctx: &Context) -> Result<usize> {
+        let value = match instruction {
+            Instruction::Num(v) => ctx.num(*v),
+            Instruction::Global(s) => ctx.global(s)?,
+            Instruction::Local(n) => self.locals[*n].clone(),
+            Instruction::Binary(op) => {
+                let expr_r = self.pop();
+                let expr_l = self.pop();
+                ctx.binary(*op, expr_l, expr_r)?
+            }
+            Instruction::Unary(op) => {
+                let value = self.pop();
+                ctx.unary(*op, value)?
+            }
+            Instruction::Call(func) => {
+                let value = self.pop();
+                ctx.call(*func, value)?
+            }
+            Instruction::And(target) => {
+                let lhs = self.pop();
+                if lhs.is_zero() {
+                    self.stack.push(ctx.truth(false));
+                    return Ok(*target);
+                }
+                lhs
+            }
+            Instruction::Or(target) => {
+                let lhs = self.pop();
+                if !lhs.is_zero() {
+                    self.stack.push(ctx.truth(true));
+                    return Ok(*target);
+                }
+                lhs
+            }
+            Instruction::CheckBinding(var) => {
+                check_binding(var)?;
+                return Ok(next);
+            }
+            Instruction::Bind => {
+                let value = self.pop();
+                self.locals.push(value);
+                return Ok(next);
+            }
+            Instruction::Unbind => {
+                let _unused = self.locals.pop();
+                return Ok(next);
+            }
+            Instruction::Range(series, target) => {
+                let to = self.pop();
+                let from = self.pop();
+                let bounds = ctx.bounds(from, to)?;
+                self.stack.push(ctx.identity(*series));
+                if bounds.is_empty() {
+                    return Ok(*target);
+                }
+                self.counters.push((*bounds.start(), *bounds.end()));
+                self.locals.push(ctx.num(*bounds.start() as f64));
+                return Ok(next);
+            }
+            Instruction::Accumulate(series, target) => {
+                let term = self.pop();
+                let result = self.pop();
+                self.stack.push(ctx.accumulate(*series, result, term));
+
+                let (i, end) = self.counters.last_mut().expect("no series");
+                if *i < *end {
+                    *i += 1;
+                    *self.locals.last_mut().expect("no bound") = ctx.num(*i as f64);
+                    return Ok(*target);
+                }
+                let _unused = self.counters.pop();
+                let _unused = self.locals.pop();
+                return Ok(next);
+            }
+            Instruction::Diff(expr) => {
+                let vars = free_variables(expr);
+                let values = self.stack.split_off(self.stack.len() - vars.len());
+                let at = self.pop();
+                ctx.derivative(expr, at, &vars.into_iter().zip(values).collect())?
+            }
+        };
+        self.stack.push(value);
+        Ok(next)
+    }
+
+    /// Pops the top of the stack, which compiled programs never underflow.
+    fn pop(&mut self) -> Value {
+        self.stack.pop().expect("stack underflow")
+    }
+}
@@ -12,8 +12,14 @@
 //! ```
 //! and submit the generated `assignment06.zip` file in `target` directory.
 
+pub mod multivariable;
+pub mod plot;
 pub mod semiring;
+pub mod shared;
 pub mod symbolic_differentiation;
 
+mod multivariable_grade;
+mod plot_grade;
 mod semiring_grade;
+mod shared_grade;
 mod symbolic_differentiation_grade;
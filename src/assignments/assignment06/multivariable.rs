@@ -0,0 +1,141 @@
+//! Multivariable functions built on top of symbolic differentiation.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::assignments::assignment06::symbolic_differentiation::*;
+
+/// Multivariable functions.
+///
+/// Single-variable functions are lifted with `Apply`, e.g., `sin(x * y)` is represented as
+/// `Apply(sin(x), x * y)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MultiFuncs {
+    /// Named variable
+    Var(String),
+    /// Constant
+    Const(Rational),
+    /// Single-variable function applied to a multivariable function
+    Apply(ComplexFuncs<BaseFuncs>, Box<MultiFuncs>),
+    /// Addition
+    Add(Box<MultiFuncs>, Box<MultiFuncs>),
+    /// Subtraction
+    Sub(Box<MultiFuncs>, Box<MultiFuncs>),
+    /// Multipliciation
+    Mul(Box<MultiFuncs>, Box<MultiFuncs>),
+    /// Division
+    Div(Box<MultiFuncs>, Box<MultiFuncs>),
+}
+
+impl MultiFuncs {
+    /// Creates a variable.
+    pub fn var(name: &str) -> Self {
+        Self::Var(name.to_string())
+    }
+
+    /// Applies the single-variable function `f` to `arg`.
+    pub fn apply(f: ComplexFuncs<BaseFuncs>, arg: Self) -> Self {
+        Self::Apply(f, Box::new(arg))
+    }
+
+    /// Differentiates `self` with respect to `var`, regarding the other variables as constants.
+    ///
+    /// HINT: Consult <https://en.wikipedia.org/wiki/Partial_derivative>
+    pub fn partial(&self, var: &str) -> Self {
+        match self {
+            Self::Var(name) => Self::Const(if name == var { ONE } else { ZERO }),
+            Self::Const(_) => Self::Const(ZERO),
+            Self::Apply(f, g) => Self::Mul(
+                Box::new(Self::Apply(f.diff(), g.clone())),
+                Box::new(g.partial(var)),
+            ),
+            Self::Add(f1, f2) => Self::Add(Box::new(f1.partial(var)), Box::new(f2.partial(var))),
+            Self::Sub(f1, f2) => Self::Sub(Box::new(f1.partial(var)), Box::new(f2.partial(var))),
+            Self::Mul(f1, f2) => Self::Add(
+                Box::new(Self::Mul(Box::new(f1.partial(var)), f2.clone())),
+                Box::new(Self::Mul(f1.clone(), Box::new(f2.partial(var)))),
+            ),
+            Self::Div(f1, f2) => Self::Div(
+                Box::new(Self::Sub(
+                    Box::new(Self::Mul(Box::new(f1.partial(var)), f2.clone())),
+                    Box::new(Self::Mul(f1.clone(), Box::new(f2.partial(var)))),
+                )),
+                Box::new(Self::Mul(f2.clone(), f2.clone())),
+            ),
+        }
+    }
+
+    /// Returns the partial derivatives of `self` with respect to each of `vars`, in order.
+    pub fn gradient(&self, vars: &[&str]) -> Vec<Self> {
+        vars.iter().map(|var| self.partial(var)).collect()
+    }
+
+    /// Evaluates `self` where each variable takes its value in `vars`.
+    pub fn try_evaluate(&self, vars: &HashMap<&str, f64>) -> Result<f64, EvalError> {
+        match self {
+            Self::Var(name) => vars
+                .get(name.as_str())
+                .copied()
+                .ok_or(EvalError::UnboundVariable),
+            Self::Const(r) => r.try_evaluate(0.0),
+            Self::Apply(f, g) => f.try_evaluate(g.try_evaluate(vars)?),
+            Self::Add(f1, f2) => check(f1.try_evaluate(vars)? + f2.try_evaluate(vars)?),
+            Self::Sub(f1, f2) => check(f1.try_evaluate(vars)? - f2.try_evaluate(vars)?),
+            Self::Mul(f1, f2) => check(f1.try_evaluate(vars)? * f2.try_evaluate(vars)?),
+            Self::Div(f1, f2) => {
+                let denominator = f2.try_evaluate(vars)?;
+                if denominator == 0.0 {
+                    Err(EvalError::DivisionByZero)
+                } else {
+                    check(f1.try_evaluate(vars)? / denominator)
+                }
+            }
+        }
+    }
+
+    /// Evaluates `self` where each variable takes its value in `vars`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `try_evaluate` fails.
+    pub fn evaluate(&self, vars: &HashMap<&str, f64>) -> f64 {
+        match self.try_evaluate(vars) {
+            Ok(value) => value,
+            Err(e) => panic!("{e}"),
+        }
+    }
+}
+
+/// Returns the Jacobian matrix of `funcs` with respect to `vars`.
+///
+/// The `i`-th row is the gradient of `funcs[i]`.
+pub fn jacobian(funcs: &[MultiFuncs], vars: &[&str]) -> Vec<Vec<MultiFuncs>> {
+    funcs.iter().map(|f| f.gradient(vars)).collect()
+}
+
+/// Evaluates every entry of the Jacobian matrix of `funcs` with respect to `vars`, at the point
+/// given by `values`.
+pub fn evaluate_jacobian(
+    funcs: &[MultiFuncs],
+    vars: &[&str],
+    values: &HashMap<&str, f64>,
+) -> Result<Vec<Vec<f64>>, EvalError> {
+    jacobian(funcs, vars)
+        .iter()
+        .map(|row| row.iter().map(|f| f.try_evaluate(values)).collect())
+        .collect()
+}
+
+impl fmt::Display for MultiFuncs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Var(name) => write!(f, "{name}"),
+            Self::Const(r) => write!(f, "{r}"),
+            Self::Apply(func, arg) => write!(f, "({func} ∘ {arg})"),
+            Self::Add(l, r) => write!(f, "({l} + {r})"),
+            Self::Sub(l, r) => write!(f, "({l} - {r})"),
+            Self::Mul(l, r) => write!(f, "({l} * {r})"),
+            Self::Div(l, r) => write!(f, "({l} / {r})"),
+        }
+    }
+}
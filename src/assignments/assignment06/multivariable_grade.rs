@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use ntest::assert_about_eq;
+
+    use crate::assignments::assignment06::multivariable::*;
+    use crate::assignments::assignment06::symbolic_differentiation::*;
+    use crate::func;
+
+    type MF = MultiFuncs;
+
+    fn add(f1: MF, f2: MF) -> MF {
+        MF::Add(Box::new(f1), Box::new(f2))
+    }
+
+    fn mul(f1: MF, f2: MF) -> MF {
+        MF::Mul(Box::new(f1), Box::new(f2))
+    }
+
+    #[test]
+    fn test_gradient() {
+        // f(x, y) = x^2 * y + sin(x * y)
+        let x = MF::var("x");
+        let y = MF::var("y");
+        let f = add(
+            mul(MF::apply(func!(x ^ 2), x.clone()), y.clone()),
+            MF::apply(func!(sin(x)), mul(x, y)),
+        );
+
+        let values = HashMap::from([("x", 1.5), ("y", -0.4)]);
+        let grad = f.gradient(&["x", "y"]);
+        assert_eq!(grad.len(), 2);
+
+        // df/dx = 2xy + y * cos(xy), df/dy = x^2 + x * cos(xy)
+        assert_about_eq!(
+            grad[0].evaluate(&values),
+            2.0 * 1.5 * -0.4 + -0.4 * (-0.6f64).cos()
+        );
+        assert_about_eq!(grad[1].evaluate(&values), 2.25 + 1.5 * (-0.6f64).cos());
+
+        // The derivative with respect to an absent variable is zero.
+        assert_about_eq!(f.partial("z").evaluate(&values), 0.0);
+    }
+
+    #[test]
+    fn test_jacobian() {
+        // (x / y, exp(x) - y)
+        let x = MF::var("x");
+        let y = MF::var("y");
+        let funcs = [
+            MF::Div(Box::new(x.clone()), Box::new(y.clone())),
+            MF::Sub(Box::new(MF::apply(func!(exp(x)), x)), Box::new(y)),
+        ];
+
+        let values = HashMap::from([("x", 2.0), ("y", 4.0)]);
+        let matrix = evaluate_jacobian(&funcs, &["x", "y"], &values).unwrap();
+        assert_eq!(matrix.len(), 2);
+        assert_about_eq!(matrix[0][0], 0.25);
+        assert_about_eq!(matrix[0][1], -0.125);
+        assert_about_eq!(matrix[1][0], 2f64.exp());
+        assert_about_eq!(matrix[1][1], -1.0);
+
+        assert_eq!(
+            jacobian(&funcs, &["y"]),
+            vec![funcs[0].gradient(&["y"]), funcs[1].gradient(&["y"])]
+        );
+
+        let values = HashMap::from([("x", 2.0)]);
+        assert_eq!(
+            evaluate_jacobian(&funcs, &["x", "y"], &values),
+            Err(EvalError::UnboundVariable)
+        );
+    }
+}
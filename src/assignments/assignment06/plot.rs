@@ -0,0 +1,145 @@
+//! Plotting functions as ASCII charts or SVG images.
+//!
+//! Each series is a function with its marker, e.g., `&[(&f, '*'), (&f.diff(), '.')]` plots `f`
+//! next to its derivative. Points where a function fails to evaluate are skipped.
+
+use std::fmt::Write;
+
+use crate::assignments::assignment06::symbolic_differentiation::Evaluate;
+
+/// Samples every series at `width` evenly spaced points from `a` to `b`, and returns the values
+/// with the range of all finite values.
+fn sample_all<T>(
+    funcs: &[(&dyn Evaluate, T)],
+    a: f64,
+    b: f64,
+    width: usize,
+) -> (Vec<Vec<Option<f64>>>, f64, f64) {
+    assert!(width >= 2, "plot must be at least 2 points wide");
+    let xs = (0..width)
+        .map(|i| a + (b - a) * i as f64 / (width - 1) as f64)
+        .collect::<Vec<_>>();
+    // `evaluate_many` panics if any point fails, so failures are retried point by point to skip
+    // only the failing points.
+    let values = funcs
+        .iter()
+        .map(|(f, _)| match f.try_evaluate_many(&xs) {
+            Ok(ys) => ys.into_iter().map(Some).collect(),
+            Err(_) => xs.iter().map(|x| f.try_evaluate(*x).ok()).collect(),
+        })
+        .collect::<Vec<Vec<_>>>();
+
+    let (lo, hi) = values
+        .iter()
+        .flatten()
+        .flatten()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), y| {
+            (lo.min(*y), hi.max(*y))
+        });
+    let (lo, hi) = if lo > hi {
+        (-1.0, 1.0)
+    } else if lo == hi {
+        (lo - 1.0, hi + 1.0)
+    } else {
+        (lo, hi)
+    };
+    (values, lo, hi)
+}
+
+/// Renders `funcs` over `[a, b]` as an ASCII chart of `width` columns and `height` rows.
+///
+/// The vertical range is fitted to the values. The axes are drawn with `-`, `|`, and `+` if they
+/// are in range, and later series are drawn over earlier ones. The last line describes the ranges,
+/// e.g., `x: [-1, 1], y: [0, 1]`.
+///
+/// # Panics
+///
+/// Panics if `width` or `height` is less than 2.
+pub fn ascii(
+    funcs: &[(&dyn Evaluate, char)],
+    a: f64,
+    b: f64,
+    width: usize,
+    height: usize,
+) -> String {
+    assert!(height >= 2, "plot must be at least 2 rows high");
+    let (values, lo, hi) = sample_all(funcs, a, b, width);
+    let row = |y: f64| ((hi - y) / (hi - lo) * (height - 1) as f64).round() as usize;
+    let col = |x: f64| ((x - a) / (b - a) * (width - 1) as f64).round() as usize;
+
+    let mut grid = vec![vec![' '; width]; height];
+    if lo <= 0.0 && 0.0 <= hi {
+        grid[row(0.0)].fill('-');
+    }
+    if a.min(b) <= 0.0 && 0.0 <= a.max(b) {
+        for line in &mut grid {
+            line[col(0.0)] = if line[col(0.0)] == '-' { '+' } else { '|' };
+        }
+    }
+    for ((_, marker), ys) in funcs.iter().zip(&values) {
+        for (i, y) in ys.iter().enumerate() {
+            if let Some(y) = y {
+                grid[row(*y)][i] = *marker;
+            }
+        }
+    }
+
+    let mut ret = String::new();
+    for line in grid {
+        ret.extend(line);
+        ret.push('\n');
+    }
+    let _unused = writeln!(ret, "x: [{a}, {b}], y: [{lo}, {hi}]");
+    ret
+}
+
+/// Renders `funcs` over `[a, b]` as an SVG image of `width` by `height` pixels.
+///
+/// Each series is drawn as polylines in its color, e.g., `"red"` or `"#0000ff"`, broken at points
+/// where the function fails to evaluate. Each series is sampled once per pixel column.
+///
+/// # Panics
+///
+/// Panics if `width` or `height` is less than 2.
+pub fn svg(funcs: &[(&dyn Evaluate, &str)], a: f64, b: f64, width: usize, height: usize) -> String {
+    assert!(height >= 2, "plot must be at least 2 pixels high");
+    let (values, lo, hi) = sample_all(funcs, a, b, width);
+    let y_pixel = |y: f64| (hi - y) / (hi - lo) * (height - 1) as f64;
+    let x_pixel = |x: f64| (x - a) / (b - a) * (width - 1) as f64;
+
+    let mut ret = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\">\n"
+    );
+    if lo <= 0.0 && 0.0 <= hi {
+        let y = y_pixel(0.0);
+        let _unused = writeln!(
+            ret,
+            "<line x1=\"0\" y1=\"{y}\" x2=\"{width}\" y2=\"{y}\" stroke=\"gray\"/>"
+        );
+    }
+    if a.min(b) <= 0.0 && 0.0 <= a.max(b) {
+        let x = x_pixel(0.0);
+        let _unused = writeln!(
+            ret,
+            "<line x1=\"{x}\" y1=\"0\" x2=\"{x}\" y2=\"{height}\" stroke=\"gray\"/>"
+        );
+    }
+    for ((_, color), ys) in funcs.iter().zip(&values) {
+        let mut points = Vec::new();
+        for (i, y) in ys.iter().chain([&None]).enumerate() {
+            if let Some(y) = y {
+                points.push(format!("{i},{}", y_pixel(*y)));
+            } else if !points.is_empty() {
+                let _unused = writeln!(
+                    ret,
+                    "<polyline points=\"{}\" fill=\"none\" stroke=\"{color}\"/>",
+                    points.join(" ")
+                );
+                points.clear();
+            }
+        }
+    }
+    ret.push_str("</svg>\n");
+    ret
+}
@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod test {
+    use crate::assignments::assignment06::plot::*;
+    use crate::assignments::assignment06::symbolic_differentiation::*;
+    use crate::func;
+
+    #[test]
+    fn test_ascii() {
+        let f = func!(x ^ 2);
+        let chart = ascii(&[(&f, '*')], -2.0, 2.0, 5, 5);
+        assert_eq!(
+            chart,
+            [
+                "* | *",
+                "  |  ",
+                "  |  ",
+                " *|* ",
+                "--*--",
+                "x: [-2, 2], y: [0, 4]",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_ascii_with_derivative() {
+        let f = func!(sin(x));
+        let df = f.diff();
+        let chart = ascii(&[(&f, '*'), (&df, '.')], 0.0, 6.0, 61, 11);
+        let lines = chart.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 12);
+        assert!(lines[..11].iter().all(|line| line.chars().count() == 61));
+        assert!(lines[0].starts_with('.'));
+        assert!(lines[10].contains('.'));
+        assert!(lines[5].contains('*'));
+        assert!(lines[11].starts_with("x: [0, 6], y: [-0.99"));
+    }
+
+    #[test]
+    fn test_ascii_skips_invalid_points() {
+        let f = func!(1 / x);
+        let chart = ascii(&[(&f, '*')], -1.0, 1.0, 3, 3);
+        assert_eq!(
+            chart,
+            [" |*", "-+-", "*| ", "x: [-1, 1], y: [-1, 1]", ""].join("\n")
+        );
+    }
+
+    #[test]
+    fn test_svg() {
+        let f = func!(ln(x));
+        let image = svg(&[(&f, "red")], -1.0, 1.0, 5, 3);
+
+        assert!(image.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"5\""));
+        assert!(image.ends_with("</svg>\n"));
+        assert!(image.contains("<line x1=\"2\" y1=\"0\" x2=\"2\" y2=\"3\" stroke=\"gray\"/>"));
+        assert_eq!(image.matches("<polyline").count(), 1);
+        assert!(image.contains("points=\"3,2 4,0\" fill=\"none\" stroke=\"red\""));
+    }
+}
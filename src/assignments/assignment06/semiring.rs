@@ -1,10 +1,14 @@
 //! Semiring
 
 use std::collections::HashMap;
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
 
 use itertools::Itertools;
 
+use crate::assignments::assignment06::symbolic_differentiation::{
+    Differentiable, Rational, ONE, ZERO,
+};
+
 /// Semiring.
 ///
 /// Consult <https://en.wikipedia.org/wiki/Semiring>.
@@ -17,18 +21,148 @@ pub trait Semiring: Debug + Clone + PartialEq {
     fn add(&self, rhs: &Self) -> Self;
     /// Multiplication operation.
     fn mul(&self, rhs: &Self) -> Self;
+    /// Converts integer to semiring value, i.e., the sum of `value` ones.
+    ///
+    /// The default implementation adds up doublings of one, taking `O(log value)` operations.
+    /// Numeric semirings override it with a direct conversion.
+    fn from_usize(value: usize) -> Self {
+        let mut ret = Self::zero();
+        let mut unit = Self::one();
+        let mut value = value;
+        while value > 0 {
+            if value % 2 == 1 {
+                ret = ret.add(&unit);
+            }
+            value /= 2;
+            if value > 0 {
+                unit = unit.add(&unit);
+            }
+        }
+        ret
+    }
+
+    /// Additive inverse, if `self` has one.
+    ///
+    /// The default implementation only negates zero. Rings override it with their `neg`.
+    fn checked_neg(&self) -> Option<Self> {
+        (*self == Self::zero()).then(|| self.clone())
+    }
+}
+
+/// Ring, i.e., a semiring with additive inverses.
+///
+/// Consult <https://en.wikipedia.org/wiki/Ring_(mathematics)>.
+pub trait Ring: Semiring {
+    /// Additive inverse.
+    fn neg(&self) -> Self;
+    /// Subtraction operation.
+    fn sub(&self, rhs: &Self) -> Self {
+        self.add(&rhs.neg())
+    }
+}
+
+/// Field, i.e., a ring where every nonzero element has a multiplicative inverse.
+///
+/// Consult <https://en.wikipedia.org/wiki/Field_(mathematics)>.
+pub trait Field: Ring {
+    /// Multiplicative inverse. It may panic if `self` is zero.
+    fn inv(&self) -> Self;
+    /// Division operation. It may panic if `rhs` is zero.
+    fn div(&self, rhs: &Self) -> Self {
+        self.mul(&rhs.inv())
+    }
+}
+
+/// Closed semiring, i.e., a semiring with the Kleene star `a* = 1 + a + a^2 + ...`.
+///
+/// Consult <https://en.wikipedia.org/wiki/Semiring#Closed_semirings>.
+pub trait StarSemiring: Semiring {
+    /// Kleene star, satisfying `a* = 1 + a * a*`.
+    fn star(&self) -> Self;
+}
+
+/// Returns the Kleene closure `A* = I + A + A^2 + ...` of the square matrix `matrix`.
+///
+/// If `matrix[i][j]` is the weight of the edge from `i` to `j`, `A*[i][j]` is the sum of the weights
+/// of all paths from `i` to `j`. For example, it is the reflexive transitive closure over `bool`,
+/// and the all-pairs shortest distances over `MinPlus`.
+///
+/// HINT: Consult <https://en.wikipedia.org/wiki/Floyd%E2%80%93Warshall_algorithm>
+///
+/// # Panics
+///
+/// Panics if `matrix` is not square.
+pub fn kleene_closure<T: StarSemiring>(matrix: &[Vec<T>]) -> Vec<Vec<T>> {
+    let n = matrix.len();
+    assert!(
+        matrix.iter().all(|row| row.len() == n),
+        "matrix must be square"
+    );
+
+    let mut ret = matrix.to_vec();
+    for k in 0..n {
+        let loop_k = ret[k][k].star();
+        let row_k = ret[k].clone();
+        for row in &mut ret {
+            let via_k = row[k].mul(&loop_k);
+            for (weight, weight_kj) in row.iter_mut().zip(&row_k) {
+                *weight = weight.add(&via_k.mul(weight_kj));
+            }
+        }
+    }
+    for (i, row) in ret.iter_mut().enumerate() {
+        row[i] = row[i].add(&T::one());
+    }
+    ret
 }
 
 /// Converts integer to semiring value.
 pub fn from_usize<T: Semiring>(value: usize) -> T {
-    let mut result = T::zero();
-    let one = T::one();
+    T::from_usize(value)
+}
+
+/// Computes `base^exp` by repeated squaring.
+fn power<T: Semiring>(base: &T, mut exp: u64) -> T {
+    let mut ret = T::one();
+    let mut base = base.clone();
+    while exp > 0 {
+        if exp % 2 == 1 {
+            ret = ret.mul(&base);
+        }
+        exp /= 2;
+        if exp > 0 {
+            base = base.mul(&base);
+        }
+    }
+    ret
+}
+
+impl Semiring for bool {
+    fn zero() -> Self {
+        false
+    }
 
-    for _ in 0..value {
-        result = T::add(&result, &one);
+    fn one() -> Self {
+        true
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        *self || *rhs
+    }
+
+    fn mul(&self, rhs: &Self) -> Self {
+        *self && *rhs
     }
 
-    result
+    fn from_usize(value: usize) -> Self {
+        value > 0
+    }
+}
+
+impl StarSemiring for bool {
+    fn star(&self) -> Self {
+        true
+    }
 }
 
 impl Semiring for u64 {
@@ -47,6 +181,10 @@ impl Semiring for u64 {
     fn mul(&self, rhs: &Self) -> Self {
         self * rhs
     }
+
+    fn from_usize(value: usize) -> Self {
+        value as u64
+    }
 }
 
 impl Semiring for i64 {
@@ -65,6 +203,24 @@ impl Semiring for i64 {
     fn mul(&self, rhs: &Self) -> Self {
         self * rhs
     }
+
+    fn from_usize(value: usize) -> Self {
+        value as i64
+    }
+
+    fn checked_neg(&self) -> Option<Self> {
+        i64::checked_neg(*self)
+    }
+}
+
+impl Ring for i64 {
+    fn neg(&self) -> Self {
+        -self
+    }
+
+    fn sub(&self, rhs: &Self) -> Self {
+        self - rhs
+    }
 }
 
 impl Semiring for f64 {
@@ -83,6 +239,216 @@ impl Semiring for f64 {
     fn mul(&self, rhs: &Self) -> Self {
         self * rhs
     }
+
+    fn from_usize(value: usize) -> Self {
+        value as f64
+    }
+
+    fn checked_neg(&self) -> Option<Self> {
+        Some(-self)
+    }
+}
+
+impl Ring for f64 {
+    fn neg(&self) -> Self {
+        -self
+    }
+
+    fn sub(&self, rhs: &Self) -> Self {
+        self - rhs
+    }
+}
+
+impl Field for f64 {
+    fn inv(&self) -> Self {
+        1.0 / self
+    }
+
+    fn div(&self, rhs: &Self) -> Self {
+        self / rhs
+    }
+}
+
+impl Semiring for Rational {
+    fn zero() -> Self {
+        ZERO
+    }
+
+    fn one() -> Self {
+        ONE
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        *self + *rhs
+    }
+
+    fn mul(&self, rhs: &Self) -> Self {
+        *self * *rhs
+    }
+
+    fn from_usize(value: usize) -> Self {
+        Rational::new(value as isize, 1)
+    }
+
+    fn checked_neg(&self) -> Option<Self> {
+        Some(-*self)
+    }
+}
+
+impl Ring for Rational {
+    fn neg(&self) -> Self {
+        -*self
+    }
+}
+
+impl Field for Rational {
+    fn inv(&self) -> Self {
+        self.reciprocal()
+    }
+}
+
+/// Product of two semirings, where every operation is component-wise.
+///
+/// For example, `(u64, MinPlus)` counts paths and finds the shortest one at the same time.
+impl<A: Semiring, B: Semiring> Semiring for (A, B) {
+    fn zero() -> Self {
+        (A::zero(), B::zero())
+    }
+
+    fn one() -> Self {
+        (A::one(), B::one())
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        (self.0.add(&rhs.0), self.1.add(&rhs.1))
+    }
+
+    fn mul(&self, rhs: &Self) -> Self {
+        (self.0.mul(&rhs.0), self.1.mul(&rhs.1))
+    }
+
+    fn from_usize(value: usize) -> Self {
+        (A::from_usize(value), B::from_usize(value))
+    }
+}
+
+/// Semiring `T` with a new zero `None` adjoined, which is absorbing for multiplication.
+///
+/// `None` stands for "no value", e.g., an unreachable pair of vertices, while `Some(T::zero())` is
+/// an ordinary value.
+impl<T: Semiring> Semiring for Option<T> {
+    fn zero() -> Self {
+        None
+    }
+
+    fn one() -> Self {
+        Some(T::one())
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        match (self, rhs) {
+            (Some(l), Some(r)) => Some(l.add(r)),
+            (Some(v), None) | (None, Some(v)) => Some(v.clone()),
+            (None, None) => None,
+        }
+    }
+
+    fn mul(&self, rhs: &Self) -> Self {
+        match (self, rhs) {
+            (Some(l), Some(r)) => Some(l.mul(r)),
+            _ => None,
+        }
+    }
+
+    fn from_usize(value: usize) -> Self {
+        if value == 0 {
+            None
+        } else {
+            Some(T::from_usize(value))
+        }
+    }
+}
+
+/// Min-plus (tropical) semiring, where addition is `min` and multiplication is `+`.
+///
+/// The additive identity is `+∞` and the multiplicative identity is `0`. For example, sums of
+/// products over paths compute shortest distances.
+///
+/// Consult <https://en.wikipedia.org/wiki/Tropical_semiring>.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct MinPlus(pub f64);
+
+impl Semiring for MinPlus {
+    fn zero() -> Self {
+        MinPlus(f64::INFINITY)
+    }
+
+    fn one() -> Self {
+        MinPlus(0.0)
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        MinPlus(self.0.min(rhs.0))
+    }
+
+    fn mul(&self, rhs: &Self) -> Self {
+        MinPlus(self.0 + rhs.0)
+    }
+
+    fn from_usize(value: usize) -> Self {
+        if value == 0 {
+            Self::zero()
+        } else {
+            Self::one()
+        }
+    }
+}
+
+/// The star of a nonnegative weight is `0`, as going around a nonnegative cycle never helps, and
+/// that of a negative weight is `-∞`.
+impl StarSemiring for MinPlus {
+    fn star(&self) -> Self {
+        if self.0 >= 0.0 {
+            Self::one()
+        } else {
+            MinPlus(f64::NEG_INFINITY)
+        }
+    }
+}
+
+/// Max-plus (tropical) semiring, where addition is `max` and multiplication is `+`.
+///
+/// The additive identity is `-∞` and the multiplicative identity is `0`. For example, sums of
+/// products over paths compute longest distances, e.g., critical paths of schedules.
+///
+/// Consult <https://en.wikipedia.org/wiki/Tropical_semiring>.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct MaxPlus(pub f64);
+
+impl Semiring for MaxPlus {
+    fn zero() -> Self {
+        MaxPlus(f64::NEG_INFINITY)
+    }
+
+    fn one() -> Self {
+        MaxPlus(0.0)
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        MaxPlus(self.0.max(rhs.0))
+    }
+
+    fn mul(&self, rhs: &Self) -> Self {
+        MaxPlus(self.0 + rhs.0)
+    }
+
+    fn from_usize(value: usize) -> Self {
+        if value == 0 {
+            Self::zero()
+        } else {
+            Self::one()
+        }
+    }
 }
 
 /// Polynomials with coefficient in `C`.
@@ -98,6 +464,10 @@ impl Semiring for f64 {
 ///     },
 /// }
 /// ```
+///
+/// Polynomials are kept in canonical form, i.e., zero coefficients are never stored. Hence the zero
+/// polynomial has no coefficients at all, and two polynomials are equal if and only if their
+/// coefficients are.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Polynomial<C: Semiring> {
     coefficients: HashMap<u64, C>,
@@ -105,15 +475,13 @@ pub struct Polynomial<C: Semiring> {
 
 impl<C: Semiring> Semiring for Polynomial<C> {
     fn zero() -> Self {
-        let mut ret = HashMap::new();
-        let _unused = ret.insert(0, C::zero());
-        Polynomial { coefficients: ret }
+        Polynomial {
+            coefficients: HashMap::new(),
+        }
     }
 
     fn one() -> Self {
-        let mut ret = HashMap::new();
-        let _unused = ret.insert(0, C::one());
-        Polynomial { coefficients: ret }
+        Polynomial::from(C::one())
     }
 
     fn add(&self, rhs: &Self) -> Self {
@@ -130,7 +498,7 @@ impl<C: Semiring> Semiring for Polynomial<C> {
         if !temp.is_empty() {
             ret.extend(temp);
         }
-        Polynomial { coefficients: ret }
+        Polynomial::normalized(ret)
     }
 
     fn mul(&self, rhs: &Self) -> Self {
@@ -141,29 +509,68 @@ impl<C: Semiring> Semiring for Polynomial<C> {
                 *entry = entry.add(&v1.mul(v2));
             }
         }
-        ret.retain(|_, value| *value != C::zero());
-        Polynomial { coefficients: ret }
+        Polynomial::normalized(ret)
+    }
+
+    fn from_usize(value: usize) -> Self {
+        Polynomial::from(C::from_usize(value))
+    }
+
+    fn checked_neg(&self) -> Option<Self> {
+        let coefficients = self
+            .coefficients
+            .iter()
+            .map(|(k, v)| Some((*k, v.checked_neg()?)))
+            .collect::<Option<_>>()?;
+        Some(Polynomial { coefficients })
+    }
+}
+
+impl<C: Ring> Ring for Polynomial<C> {
+    fn neg(&self) -> Self {
+        let coefficients = self
+            .coefficients
+            .iter()
+            .map(|(k, v)| (*k, v.neg()))
+            .collect();
+        Polynomial { coefficients }
     }
 }
 
 impl<C: Semiring> Polynomial<C> {
+    /// Constructs a polynomial from `coefficients`, stripping zero coefficients.
+    fn normalized(mut coefficients: HashMap<u64, C>) -> Self {
+        coefficients.retain(|_, value| *value != C::zero());
+        Polynomial { coefficients }
+    }
+
     /// Constructs polynomial `x`.
     pub fn x() -> Self {
-        let mut ret = HashMap::new();
-        let _unused = ret.insert(1, C::one());
-        Polynomial { coefficients: ret }
+        Polynomial::term(C::one(), 1)
     }
 
     /// Evaluates the polynomial with the given value.
+    ///
+    /// Terms are visited once in decreasing degree with Horner's method, and the gaps between the
+    /// degrees of sparse terms are bridged by repeated squaring.
+    ///
+    /// HINT: Consult <https://en.wikipedia.org/wiki/Horner%27s_method>
     pub fn eval(&self, value: C) -> C {
         let mut ret = C::zero();
-        for (k, v) in &self.coefficients {
-            let mut temp = C::one();
-            for _ in 0..*k {
-                temp = temp.mul(&value);
+        let mut prev = None;
+        for (k, v) in self
+            .coefficients
+            .iter()
+            .sorted_by_key(|(k, _)| std::cmp::Reverse(**k))
+        {
+            if let Some(prev) = prev {
+                ret = ret.mul(&power(&value, prev - k));
             }
-            temp = temp.mul(v);
-            ret = ret.add(&temp);
+            ret = ret.add(v);
+            prev = Some(*k);
+        }
+        if let Some(prev) = prev {
+            ret = ret.mul(&power(&value, prev));
         }
         ret
     }
@@ -172,58 +579,399 @@ impl<C: Semiring> Polynomial<C> {
     pub fn term(a: C, n: u64) -> Self {
         let mut ret = HashMap::new();
         let _unused = ret.insert(n, a);
-        Polynomial { coefficients: ret }
+        Polynomial::normalized(ret)
+    }
+
+    /// Raises the polynomial to the power of `exp` by repeated squaring.
+    pub fn pow(&self, exp: u32) -> Self {
+        power(self, exp.into())
+    }
+
+    /// Returns the degree of the polynomial, or `None` if it is zero.
+    pub fn degree(&self) -> Option<u64> {
+        self.coefficients.keys().max().copied()
+    }
+
+    /// Returns the coefficient of the highest degree term, or zero if the polynomial is zero.
+    pub fn leading_coefficient(&self) -> C {
+        self.degree()
+            .map_or_else(C::zero, |degree| self.coefficient(degree))
+    }
+
+    /// Returns the coefficient of `x^n`.
+    pub fn coefficient(&self, n: u64) -> C {
+        self.coefficients.get(&n).cloned().unwrap_or_else(C::zero)
+    }
+
+    /// Returns `true` if the polynomial is zero.
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    /// Returns the formal derivative of the polynomial, where `ax^n` becomes `(n * a)x^(n-1)`.
+    ///
+    /// HINT: Consult <https://en.wikipedia.org/wiki/Formal_derivative>
+    pub fn derivative(&self) -> Self {
+        let mut ret = HashMap::new();
+        for (k, v) in &self.coefficients {
+            if *k > 0 {
+                let _unused = ret.insert(*k - 1, from_usize::<C>(*k as usize).mul(v));
+            }
+        }
+        Polynomial::normalized(ret)
+    }
+}
+
+impl<C: Field> Polynomial<C> {
+    /// Divides `self` by `divisor`, returning the quotient and the remainder.
+    ///
+    /// The remainder is either zero or of a lower degree than `divisor`, and `self` is equal to
+    /// `quotient * divisor + remainder`.
+    ///
+    /// HINT: Consult <https://en.wikipedia.org/wiki/Polynomial_long_division>
+    ///
+    /// # Panics
+    ///
+    /// Panics if `divisor` is zero.
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        let Some(degree) = divisor.degree() else {
+            panic!("divide by zero polynomial");
+        };
+        let lead = divisor.leading_coefficient();
+
+        let mut quotient = HashMap::new();
+        let mut remainder = self.clone();
+        while let Some(k) = remainder.degree() {
+            if k < degree {
+                break;
+            }
+            let coeff = remainder.leading_coefficient().div(&lead);
+            let term = Polynomial::term(coeff.clone(), k - degree);
+            remainder = remainder.sub(&term.mul(divisor));
+            // The leading term cancels out, but rounding may leave a residue behind.
+            let _unused = remainder.coefficients.remove(&k);
+            let _unused = quotient.insert(k - degree, coeff);
+        }
+        (Polynomial::normalized(quotient), remainder)
+    }
+}
+
+impl<C: Field> Polynomial<C> {
+    /// Returns the greatest common divisor of `self` and `rhs`, normalized to be monic, i.e., to
+    /// have the leading coefficient one. The greatest common divisor of two zero polynomials is
+    /// zero.
+    ///
+    /// With inexact coefficients such as `f64`, rounding errors may leave nonzero remainders behind,
+    /// so common factors may be missed.
+    ///
+    /// HINT: Consult <https://en.wikipedia.org/wiki/Polynomial_greatest_common_divisor#Euclidean_algorithm>
+    pub fn gcd(&self, rhs: &Self) -> Self {
+        let (mut a, mut b) = (self.clone(), rhs.clone());
+        while !b.is_zero() {
+            let (_, r) = a.div_rem(&b);
+            a = b;
+            b = r;
+        }
+        if a.is_zero() {
+            return a;
+        }
+        let scale = Polynomial::from(a.leading_coefficient().inv());
+        a.mul(&scale)
+    }
+}
+
+impl<C: Field> std::ops::Div for Polynomial<C> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.div_rem(&rhs).0
+    }
+}
+
+impl<C: Field> std::ops::Rem for Polynomial<C> {
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.div_rem(&rhs).1
+    }
+}
+
+impl<C: Semiring> Differentiable for Polynomial<C> {
+    fn diff(&self) -> Self {
+        self.derivative()
     }
 }
 
 impl<C: Semiring> From<C> for Polynomial<C> {
     fn from(value: C) -> Self {
-        let mut ret = HashMap::new();
-        let _unused = ret.insert(0, value);
-        Polynomial { coefficients: ret }
+        Polynomial::term(value, 0)
+    }
+}
+
+/// Prints terms in decreasing degree as `a`, `x`, `ax`, `x^n`, or `ax^n`, separated by ` + `, or by
+/// ` - ` if the printed coefficient of the next term starts with `-`.
+///
+/// Terms with zero coefficients are omitted, as are coefficients equal to one in front of `x`, and
+/// a coefficient equal to minus one is printed as `-`, e.g., `-x^2 - x`. The zero polynomial is
+/// printed as the zero coefficient. Hence `FromStr` parses the output back to an equal polynomial
+/// if every coefficient is an integer.
+impl<C: Semiring + fmt::Display> fmt::Display for Polynomial<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let terms = self
+            .coefficients
+            .iter()
+            .sorted_by_key(|(k, _)| std::cmp::Reverse(**k))
+            .map(|(k, v)| {
+                let coeff = if *v == C::one() && *k > 0 {
+                    String::new()
+                } else if v.checked_neg() == Some(C::one()) && *k > 0 {
+                    "-".to_string()
+                } else {
+                    v.to_string()
+                };
+                match k {
+                    0 => coeff,
+                    1 => format!("{coeff}x"),
+                    _ => format!("{coeff}x^{k}"),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let Some((first, rest)) = terms.split_first() else {
+            return write!(f, "{}", C::zero());
+        };
+        write!(f, "{first}")?;
+        for term in rest {
+            match term.strip_prefix('-') {
+                Some(term) => write!(f, " - {term}")?,
+                None => write!(f, " + {term}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Error from parsing a `Polynomial`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsePolynomialError {
+    /// The input has no terms.
+    Empty,
+    /// The term, without its sign, is not of the form `a`, `x`, `ax`, `x^n`, or `ax^n`.
+    InvalidTerm(String),
+}
+
+impl fmt::Display for ParsePolynomialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty polynomial"),
+            Self::InvalidTerm(term) => write!(f, "invalid term `{term}`"),
+        }
     }
 }
 
+impl std::error::Error for ParsePolynomialError {}
+
 /// Given a string `s`, parse it into a `Polynomial<C>`.
-/// You may assume that `s` follows the criteria below.
-/// Therefore, you do not have to return `Err`.
 ///
-/// Assumptions:
-/// - Each term is separated by ` + `.
+/// Grammar:
+/// - Terms are separated by `+` or `-`, and the first term may be preceded by a sign.
 /// - Each term is one of the following form: `a`, `x`, `ax`, `x^n`, and `ax^n`, where `a` is a
-///   `usize` number and `n` is a `u64` number. This `a` should then be converted to a `C` type.
-/// - In `a`, it is guaranteed that `a >= 1`.
-/// - In `ax` and `ax^n`, it is guaranteed that `a >= 2`.
-/// - In `x^n` and `ax^n`, it is guaranteed that `n >= 2`.
-/// - All terms have unique degrees.
+///   `usize` number and `n` is a `u64` number. This `a` is then converted to a `C` type, and
+///   negated if the sign of the term is `-`. A negative term is invalid if `C` cannot negate it,
+///   e.g., `x - 1` is not a `Polynomial<u64>`. See `Semiring::checked_neg`.
+/// - Whitespace is ignored anywhere.
+/// - Terms may appear in any order. Terms of the same degree are added up.
 ///
-/// Consult `assignment06/grade.rs` for example valid strings.
-///
-/// Hint: `.split`, `.parse`, and `Polynomial::term`
+/// For example, `-x^2 + 3 - 1x + 2x^2` is parsed to `x^2 - x + 3`.
 impl<C: Semiring> std::str::FromStr for Polynomial<C> {
-    type Err = (); // Ignore this for now...
+    type Err = ParsePolynomialError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut ret = HashMap::new();
-        for item in s.split(" + ") {
-            let (a, n) = if let Some((a, n)) = item.split_once("x^") {
-                let coeff = if a.is_empty() {
-                    1
-                } else {
-                    a.parse::<usize>().unwrap()
-                };
-                (coeff, n.parse::<u64>().unwrap())
-            } else if item == "x" {
-                (1, 1)
-            } else if let Some((a, _)) = item.split_once("x") {
-                (a.parse::<usize>().unwrap(), 1)
+        let s = s.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+        if s.is_empty() {
+            return Err(ParsePolynomialError::Empty);
+        }
+
+        let mut ret = Polynomial::zero();
+        let mut rest = s.as_str();
+        let mut first = true;
+        while !rest.is_empty() {
+            let (negative, body) = match rest.strip_prefix('-') {
+                Some(body) => (true, body),
+                None => match rest.strip_prefix('+') {
+                    Some(body) if !first => (false, body),
+                    Some(_) => return Err(ParsePolynomialError::InvalidTerm("+".to_string())),
+                    None if first => (false, rest),
+                    None => unreachable!("terms are split at signs"),
+                },
+            };
+            let end = body.find(['+', '-']).unwrap_or(body.len());
+            let (item, next) = body.split_at(end);
+            let invalid = || ParsePolynomialError::InvalidTerm(item.to_string());
+
+            let (a, n) = match item.split_once('x') {
+                Some((a, n)) => {
+                    let a = if a.is_empty() {
+                        1
+                    } else {
+                        a.parse::<usize>().map_err(|_| invalid())?
+                    };
+                    let n = if n.is_empty() {
+                        1
+                    } else {
+                        n.strip_prefix('^')
+                            .and_then(|n| n.parse::<u64>().ok())
+                            .ok_or_else(invalid)?
+                    };
+                    (a, n)
+                }
+                None => (item.parse::<usize>().map_err(|_| invalid())?, 0),
+            };
+
+            let a = from_usize::<C>(a);
+            let a = if negative {
+                a.checked_neg()
+                    .ok_or_else(|| ParsePolynomialError::InvalidTerm(format!("-{}", item)))?
             } else {
-                (item.parse::<usize>().unwrap(), 0)
+                a
             };
+            ret = ret.add(&Polynomial::term(a, n));
+            rest = next;
+            first = false;
+        }
+        Ok(ret)
+    }
+}
+
+/// Polynomials with coefficient in `C`, stored densely.
+///
+/// The coefficient of `x^n` is stored at index `n`, e.g., polynomial `x^2 + 5x + 6` is represented
+/// in `DensePolynomial<u64>` as `[6, 5, 1]`. Like `Polynomial`, it is kept in canonical form, i.e.,
+/// the last coefficient is never zero.
+///
+/// Dense polynomials are faster to add, multiply, and evaluate when most coefficients up to the
+/// degree are nonzero, while `Polynomial` is better for sparse polynomials of a high degree such as
+/// `x^100000 + 1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DensePolynomial<C: Semiring> {
+    coefficients: Vec<C>,
+}
+
+impl<C: Semiring> DensePolynomial<C> {
+    /// Constructs a polynomial from `coefficients`, stripping trailing zeros.
+    fn normalized(mut coefficients: Vec<C>) -> Self {
+        while coefficients.last().is_some_and(|c| *c == C::zero()) {
+            let _unused = coefficients.pop();
+        }
+        DensePolynomial { coefficients }
+    }
+
+    /// Constructs polynomial `x`.
+    pub fn x() -> Self {
+        DensePolynomial::term(C::one(), 1)
+    }
+
+    /// Constructs polynomial `ax^n`.
+    pub fn term(a: C, n: u64) -> Self {
+        let mut coefficients = vec![C::zero(); n as usize];
+        coefficients.push(a);
+        DensePolynomial::normalized(coefficients)
+    }
+
+    /// Evaluates the polynomial with the given value with Horner's method.
+    pub fn eval(&self, value: C) -> C {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(C::zero(), |acc, c| acc.mul(&value).add(c))
+    }
+
+    /// Returns the degree of the polynomial, or `None` if it is zero.
+    pub fn degree(&self) -> Option<u64> {
+        self.coefficients.len().checked_sub(1).map(|n| n as u64)
+    }
+
+    /// Returns the coefficient of `x^n`.
+    pub fn coefficient(&self, n: u64) -> C {
+        self.coefficients
+            .get(n as usize)
+            .cloned()
+            .unwrap_or_else(C::zero)
+    }
+}
+
+impl<C: Semiring> Semiring for DensePolynomial<C> {
+    fn zero() -> Self {
+        DensePolynomial {
+            coefficients: Vec::new(),
+        }
+    }
 
-            let coeff = from_usize::<C>(a);
-            let _unused = ret.insert(n, coeff);
+    fn one() -> Self {
+        DensePolynomial::from(C::one())
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        let (long, short) = if self.coefficients.len() >= rhs.coefficients.len() {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+        let mut ret = long.coefficients.clone();
+        for (r, c) in ret.iter_mut().zip(&short.coefficients) {
+            *r = r.add(c);
         }
-        Ok(Polynomial { coefficients: ret })
+        DensePolynomial::normalized(ret)
+    }
+
+    fn mul(&self, rhs: &Self) -> Self {
+        if self.coefficients.is_empty() || rhs.coefficients.is_empty() {
+            return DensePolynomial::zero();
+        }
+        let mut ret = vec![C::zero(); self.coefficients.len() + rhs.coefficients.len() - 1];
+        for (i, c1) in self.coefficients.iter().enumerate() {
+            if *c1 == C::zero() {
+                continue;
+            }
+            for (j, c2) in rhs.coefficients.iter().enumerate() {
+                ret[i + j] = ret[i + j].add(&c1.mul(c2));
+            }
+        }
+        DensePolynomial::normalized(ret)
+    }
+
+    fn from_usize(value: usize) -> Self {
+        DensePolynomial::from(C::from_usize(value))
+    }
+}
+
+impl<C: Semiring> From<C> for DensePolynomial<C> {
+    fn from(value: C) -> Self {
+        DensePolynomial::term(value, 0)
+    }
+}
+
+impl<C: Semiring> From<Polynomial<C>> for DensePolynomial<C> {
+    fn from(value: Polynomial<C>) -> Self {
+        let Some(degree) = value.degree() else {
+            return DensePolynomial::zero();
+        };
+        let mut coefficients = vec![C::zero(); degree as usize + 1];
+        for (k, v) in value.coefficients {
+            coefficients[k as usize] = v;
+        }
+        DensePolynomial { coefficients }
+    }
+}
+
+impl<C: Semiring> From<DensePolynomial<C>> for Polynomial<C> {
+    fn from(value: DensePolynomial<C>) -> Self {
+        let coefficients = value
+            .coefficients
+            .into_iter()
+            .enumerate()
+            .map(|(k, v)| (k as u64, v))
+            .collect();
+        Polynomial::normalized(coefficients)
     }
 }
@@ -1,8 +1,12 @@
 #[cfg(test)]
 mod test {
+    use std::hint;
+    use std::time::{Duration, Instant};
+
     use ntest::assert_about_eq;
 
     use crate::assignments::assignment06::semiring::*;
+    use crate::assignments::assignment06::symbolic_differentiation::{Differentiable, Rational};
 
     fn test_from_str(s: &str, f: impl Fn(i64) -> i64) {
         let poly = s.parse::<Polynomial<i64>>().unwrap();
@@ -134,4 +138,659 @@ mod test {
             )
         );
     }
+
+    /// Product of square matrices over a semiring.
+    fn mat_mul<T: Semiring>(lhs: &[Vec<T>], rhs: &[Vec<T>]) -> Vec<Vec<T>> {
+        (0..lhs.len())
+            .map(|i| {
+                (0..rhs[0].len())
+                    .map(|j| {
+                        (0..rhs.len()).fold(T::zero(), |acc, k| acc.add(&lhs[i][k].mul(&rhs[k][j])))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_min_plus() {
+        assert_eq!(MinPlus(3.0).add(&MinPlus::zero()), MinPlus(3.0));
+        assert_eq!(MinPlus(3.0).mul(&MinPlus::one()), MinPlus(3.0));
+        assert_eq!(MinPlus(3.0).mul(&MinPlus::zero()), MinPlus::zero());
+        assert_eq!(MinPlus(3.0).add(&MinPlus(2.0)), MinPlus(2.0));
+
+        // Shortest paths of length at most 3 in a graph with edges 0->1 (1), 1->2 (2), 0->2 (5),
+        // and 2->3 (1).
+        let inf = f64::INFINITY;
+        let adj = [
+            [0.0, 1.0, 5.0, inf],
+            [inf, 0.0, 2.0, inf],
+            [inf, inf, 0.0, 1.0],
+            [inf, inf, inf, 0.0],
+        ]
+        .map(|row| row.map(MinPlus).to_vec())
+        .to_vec();
+        let dist = mat_mul(&mat_mul(&adj, &adj), &adj);
+        assert_eq!(dist[0], [0.0, 1.0, 3.0, 4.0].map(MinPlus));
+        assert_eq!(dist[3][0], MinPlus::zero());
+
+        // `2x^2 + 1x + 5` in the min-plus semiring is `min(2 + 2x, 1 + x, 5)`.
+        let poly = Polynomial::add(
+            &Polynomial::add(
+                &Polynomial::term(MinPlus(2.0), 2),
+                &Polynomial::term(MinPlus(1.0), 1),
+            ),
+            &Polynomial::from(MinPlus(5.0)),
+        );
+        assert_eq!(poly.eval(MinPlus(1.0)), MinPlus(2.0));
+        assert_eq!(poly.eval(MinPlus(-3.0)), MinPlus(-4.0));
+        assert_eq!(poly.eval(MinPlus(4.0)), MinPlus(5.0));
+    }
+
+    #[test]
+    fn test_max_plus() {
+        assert_eq!(MaxPlus(3.0).add(&MaxPlus::zero()), MaxPlus(3.0));
+        assert_eq!(MaxPlus(3.0).mul(&MaxPlus::one()), MaxPlus(3.0));
+        assert_eq!(MaxPlus(3.0).add(&MaxPlus(2.0)), MaxPlus(3.0));
+
+        // Task 0 takes 2 days and is followed by tasks 1 and 2, which take 3 and 4 days before
+        // milestone 3. Longest paths give the earliest time to reach the milestone.
+        let ninf = f64::NEG_INFINITY;
+        let adj = [
+            [0.0, 2.0, 2.0, ninf],
+            [ninf, 0.0, ninf, 3.0],
+            [ninf, ninf, 0.0, 4.0],
+            [ninf, ninf, ninf, 0.0],
+        ]
+        .map(|row| row.map(MaxPlus).to_vec())
+        .to_vec();
+        let finish = mat_mul(&adj, &adj);
+        assert_eq!(finish[0][3], MaxPlus(6.0));
+    }
+
+    #[test]
+    fn test_ring() {
+        assert_eq!(Ring::neg(&3i64), -3);
+        assert_eq!(Ring::sub(&3i64, &5), -2);
+        assert_eq!(Ring::sub(&1.5f64, &0.5), 1.0);
+
+        // (2x^2 + 3x + 1) - (x^2 + 5x + 6) = x^2 - 2x - 5
+        let p = "2x^2 + 3x + 1".parse::<Polynomial<i64>>().unwrap();
+        let q = "x^2 + 5x + 6".parse::<Polynomial<i64>>().unwrap();
+        let diff = p.sub(&q);
+        for i in -5..5 {
+            assert_eq!(diff.eval(i), i * i - 2 * i - 5);
+        }
+        assert_eq!(diff.add(&q), p);
+        assert_eq!(p.neg().neg(), p);
+
+        // Subtraction over polynomial coefficients: (x + y) - y = x.
+        let x = Polynomial::from(Polynomial::<f64>::x());
+        let y = Polynomial::<Polynomial<f64>>::x();
+        let z = Polynomial::sub(&Polynomial::add(&x, &y), &y);
+        assert_eq!(z.eval(Polynomial::from(7.0)).eval(2.0), 2.0);
+    }
+
+    /// Builds a polynomial from `(coefficient, degree)` pairs.
+    fn poly<C: Semiring>(terms: &[(C, u64)]) -> Polynomial<C> {
+        terms
+            .iter()
+            .map(|(c, n)| Polynomial::term(c.clone(), *n))
+            .reduce(|acc, term| acc.add(&term))
+            .unwrap_or_else(Polynomial::zero)
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let r = |n| Rational::new(n, 1);
+
+        // (x^3 - 2x^2 - 4) / (x - 3) = x^2 + x + 3, remainder 5
+        let p = poly(&[(r(1), 3), (r(-2), 2), (r(-4), 0)]);
+        let d = poly(&[(r(1), 1), (r(-3), 0)]);
+        let (q, rem) = p.div_rem(&d);
+        assert_eq!(q, poly(&[(r(1), 2), (r(1), 1), (r(3), 0)]));
+        assert_eq!(rem, Polynomial::from(r(5)));
+        assert_eq!(q.mul(&d).add(&rem), p);
+        assert_eq!(p.clone() / d.clone(), q);
+        assert_eq!(p % d, rem);
+
+        // Non-monic divisors need fractional coefficients: (x^2 + 1) / (2x) = x/2, remainder 1.
+        let p = poly(&[(r(1), 2), (r(1), 0)]);
+        let d = poly(&[(r(2), 1)]);
+        let (q, rem) = p.div_rem(&d);
+        assert_eq!(q, Polynomial::term(Rational::new(1, 2), 1));
+        assert_eq!(rem, Polynomial::from(r(1)));
+
+        // Dividing by a polynomial of a higher degree leaves everything in the remainder.
+        let (q, rem) = d.div_rem(&p);
+        assert_eq!(q.eval(r(3)), r(0));
+        assert_eq!(rem, d);
+    }
+
+    #[test]
+    fn test_div_rem_f64() {
+        // (x^2 - 1)(x + 0.5) + 0.25 divided by x^2 - 1.
+        let d = poly(&[(1.0, 2), (-1.0, 0)]);
+        let p = d
+            .mul(&poly(&[(1.0, 1), (0.5, 0)]))
+            .add(&Polynomial::from(0.25));
+        let (q, rem) = p.div_rem(&d);
+        for x in [-2.0, 0.0, 0.5, 3.0] {
+            assert_about_eq!(q.eval(x), x + 0.5);
+            assert_about_eq!(rem.eval(x), 0.25);
+            assert_about_eq!(q.mul(&d).add(&rem).eval(x), p.eval(x));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "divide by zero polynomial")]
+    fn test_div_rem_by_zero() {
+        let _unused = Polynomial::<f64>::x().div_rem(&Polynomial::zero());
+    }
+
+    #[test]
+    fn test_derivative() {
+        // d/dx (2x^3 + 3x^2 + 5x + 12) = 6x^2 + 6x + 5
+        let p = "2x^3 + 3x^2 + 5x + 12".parse::<Polynomial<i64>>().unwrap();
+        assert_eq!(p.derivative(), poly(&[(6, 2), (6, 1), (5, 0)]));
+        assert_eq!(p.diff(), p.derivative());
+        assert_eq!(p.derivative().derivative(), poly(&[(12, 1), (6, 0)]));
+        for i in 0..10 {
+            assert_eq!(p.derivative().derivative().derivative().eval(i), 12);
+            assert_eq!(p.diff().diff().diff().diff().eval(i), 0);
+        }
+
+        // Coefficients are scaled within the semiring, e.g., `3 = 1 + 1 + 1` in `f64`.
+        let p = poly(&[(0.5, 3), (2.0, 0)]);
+        assert_eq!(p.derivative(), poly(&[(1.5, 2)]));
+
+        // Over polynomial coefficients, only `x` is differentiated: d/dx (y x^2) = 2y x.
+        let y = Polynomial::<u64>::x();
+        let p = Polynomial::term(y.clone(), 2);
+        assert_eq!(p.derivative(), Polynomial::term(y.add(&y), 1));
+    }
+
+    #[test]
+    fn test_display() {
+        for s in [
+            "123",
+            "x",
+            "24x",
+            "2x + 3",
+            "x^3",
+            "2x^3 + 3x^2 + 5x + 12",
+            "x^5 + 1",
+        ] {
+            let poly = s.parse::<Polynomial<u64>>().unwrap();
+            assert_eq!(poly.to_string(), s);
+            assert_eq!(poly.to_string().parse::<Polynomial<u64>>().unwrap(), poly);
+        }
+
+        assert_eq!(Polynomial::<i64>::zero().to_string(), "0");
+        assert_eq!(poly(&[(3i64, 0), (1, 1), (0, 2)]).to_string(), "x + 3");
+        assert_eq!(poly(&[(-2i64, 4), (1, 0)]).to_string(), "-2x^4 + 1");
+        assert_eq!(
+            poly(&[(2i64, 4), (-1, 1), (-3, 0)]).to_string(),
+            "2x^4 - x - 3"
+        );
+        assert_eq!(poly(&[(0.5, 2), (1.0, 0)]).to_string(), "0.5x^2 + 1");
+        assert_eq!(poly(&[(-1i64, 3), (-1, 0)]).to_string(), "-x^3 - 1");
+        assert_eq!(poly(&[(-1.0, 1), (-1.5, 0)]).to_string(), "-x - 1.5");
+    }
+
+    #[test]
+    fn test_canonical_form() {
+        let zero = Polynomial::<i64>::zero();
+        assert!(zero.is_zero());
+        assert_eq!(zero.degree(), None);
+        assert_eq!(zero.leading_coefficient(), 0);
+        assert_eq!(zero, Polynomial::from(0));
+        assert_eq!(zero, Polynomial::term(0, 3));
+        assert_eq!(zero, "0".parse().unwrap());
+
+        // x - x is the zero polynomial.
+        let x = Polynomial::<i64>::x();
+        assert_eq!(x.sub(&x), zero);
+        assert!(x.sub(&x).is_zero());
+        assert_eq!(x.mul(&zero), zero);
+        assert_eq!(zero.add(&zero), zero);
+
+        // (x + 1)(x - 1) + 1 = x^2
+        let p = Polynomial::add(
+            &x.add(&Polynomial::one()).mul(&x.sub(&Polynomial::one())),
+            &Polynomial::one(),
+        );
+        assert_eq!(p, Polynomial::term(1, 2));
+        assert_eq!(p.degree(), Some(2));
+        assert_eq!(p.coefficient(2), 1);
+        assert_eq!(p.coefficient(0), 0);
+
+        let p = "2x^3 + 3x^2 + 5x + 12".parse::<Polynomial<i64>>().unwrap();
+        assert_eq!(p.degree(), Some(3));
+        assert_eq!(p.leading_coefficient(), 2);
+        assert_eq!(p.coefficient(1), 5);
+        assert_eq!(p.coefficient(7), 0);
+        assert!(!p.is_zero());
+        assert_eq!(Polynomial::<i64>::one().degree(), Some(0));
+    }
+
+    #[test]
+    fn test_eval_sparse() {
+        // x^100 + x^3 + 2 in `u64`, `f64`, and the min-plus semiring.
+        let p = poly(&[(1u64, 100), (1, 3), (2, 0)]);
+        assert_eq!(p.eval(1), 4);
+        assert_eq!(p.eval(0), 2);
+        let p = poly(&[(1.0, 100), (1.0, 3), (2.0, 0)]);
+        assert_about_eq!(p.eval(-1.0), 2.0);
+        assert_about_eq!(p.eval(0.5), 0.5f64.powi(100) + 0.125 + 2.0);
+        let p = poly(&[(MinPlus(0.0), 100), (MinPlus(0.0), 3), (MinPlus(2.0), 0)]);
+        assert_eq!(p.eval(MinPlus(-1.0)), MinPlus(-100.0));
+        assert_eq!(p.eval(MinPlus(1.0)), MinPlus(2.0));
+        assert_eq!(Polynomial::<u64>::zero().eval(5), 0);
+    }
+
+    /// Evaluates `poly` by computing every power of `value` from scratch.
+    fn eval_naive(poly: &Polynomial<f64>, value: f64, degree: u64) -> f64 {
+        (0..=degree)
+            .map(|k| {
+                let mut temp = 1.0;
+                for _ in 0..k {
+                    temp *= value;
+                }
+                temp * poly.coefficient(k)
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_eval_high_degree() {
+        let degree = 1000;
+        let p = poly(
+            &(0..=degree)
+                .map(|k| (1.0 / (k + 1) as f64, k))
+                .collect::<Vec<_>>(),
+        );
+
+        assert_about_eq!(eval_naive(&p, 0.999, degree), p.eval(0.999), 1e-9);
+    }
+
+    /// Returns the shortest time `f` takes over a few runs.
+    fn time<T>(mut f: impl FnMut() -> T) -> Duration {
+        (0..5)
+            .map(|_| {
+                let now = Instant::now();
+                let _unused = hint::black_box(f());
+                now.elapsed()
+            })
+            .min()
+            .unwrap()
+    }
+
+    #[test]
+    #[ignore = "benchmark, run with `--ignored --nocapture`"]
+    fn bench_eval_high_degree() {
+        let degree = 1000;
+        let p = poly(
+            &(0..=degree)
+                .map(|k| (1.0 / (k + 1) as f64, k))
+                .collect::<Vec<_>>(),
+        );
+
+        let naive = time(|| eval_naive(hint::black_box(&p), 0.999, degree));
+        let horner = time(|| hint::black_box(&p).eval(0.999));
+        println!("naive: {:?}, horner: {:?}", naive, horner);
+    }
+
+    #[test]
+    fn test_from_str_extended() {
+        let parse = |s: &str| s.parse::<Polynomial<i64>>();
+
+        // Signs, coefficient `1`, whitespace, and term order.
+        assert_eq!(parse("-3 + x"), Ok(poly(&[(1, 1), (-3, 0)])));
+        assert_eq!(
+            parse("x^2-1x+ -0"),
+            Err(ParsePolynomialError::InvalidTerm("".to_string()))
+        );
+        assert_eq!(parse("x^2-1x-0"), Ok(poly(&[(1, 2), (-1, 1)])));
+        assert_eq!(
+            parse("  5 +2x ^ 3- x"),
+            Ok(poly(&[(2, 3), (-1, 1), (5, 0)]))
+        );
+        assert_eq!(parse("-1x^2"), Ok(Polynomial::term(-1, 2)));
+
+        // Terms of the same degree are merged.
+        assert_eq!(
+            parse("-x^2 + 3 - 1x + 2x^2"),
+            Ok(poly(&[(1, 2), (-1, 1), (3, 0)]))
+        );
+        assert_eq!(parse("x - x"), Ok(Polynomial::zero()));
+
+        // Round trip with negative coefficients.
+        let p = poly(&[(-4, 5), (1, 2), (-1, 1), (-7, 0)]);
+        assert_eq!(p.to_string(), "-4x^5 + x^2 - x - 7");
+        assert_eq!(parse(&p.to_string()), Ok(p));
+
+        // Errors.
+        assert_eq!(parse(""), Err(ParsePolynomialError::Empty));
+        assert_eq!(parse("   "), Err(ParsePolynomialError::Empty));
+        assert_eq!(
+            parse("x +"),
+            Err(ParsePolynomialError::InvalidTerm("".to_string()))
+        );
+        assert_eq!(
+            parse("+x"),
+            Err(ParsePolynomialError::InvalidTerm("+".to_string()))
+        );
+        assert_eq!(
+            parse("2y"),
+            Err(ParsePolynomialError::InvalidTerm("2y".to_string()))
+        );
+        assert_eq!(
+            parse("x^-2"),
+            Err(ParsePolynomialError::InvalidTerm("x^".to_string()))
+        );
+        assert_eq!(
+            parse("3x2"),
+            Err(ParsePolynomialError::InvalidTerm("3x2".to_string()))
+        );
+
+        // Coefficients that cannot be negated.
+        assert_eq!(
+            "x^2 - 3x".parse::<Polynomial<u64>>(),
+            Err(ParsePolynomialError::InvalidTerm("-3x".to_string()))
+        );
+        assert_eq!("-0 + x".parse::<Polynomial<u64>>(), Ok(Polynomial::x()));
+        assert_eq!(
+            "2x + 1".parse::<Polynomial<MinPlus>>(),
+            Ok(poly(&[(MinPlus(0.0), 1), (MinPlus(0.0), 0)]))
+        );
+        assert_eq!(
+            ParsePolynomialError::InvalidTerm("2y".to_string()).to_string(),
+            "invalid term `2y`"
+        );
+    }
+
+    #[test]
+    fn test_gcd() {
+        let parse = |s: &str| s.parse::<Polynomial<Rational>>().unwrap();
+
+        // gcd((x - 1)(x + 2)(x + 3), 2(x - 1)(x + 3)(x - 5)) = (x - 1)(x + 3)
+        let common = parse("x - 1").mul(&parse("x + 3"));
+        let p = common.mul(&parse("x + 2"));
+        let q = common.mul(&parse("2x - 10"));
+        assert_eq!(p.gcd(&q), common);
+        assert_eq!(q.gcd(&p), common);
+
+        // The result is monic even if every input is a multiple of 3.
+        let p = parse("3x^2 - 3");
+        assert_eq!(p.gcd(&p), parse("x^2 - 1"));
+        assert_eq!(p.gcd(&Polynomial::zero()), parse("x^2 - 1"));
+        assert_eq!(Polynomial::zero().gcd(&p), parse("x^2 - 1"));
+
+        // Coprime polynomials and zeros.
+        assert_eq!(parse("x^2 + 1").gcd(&parse("x - 1")), Polynomial::one());
+        assert_eq!(parse("x^2 + 1").gcd(&parse("7")), Polynomial::one());
+        let zero = Polynomial::<Rational>::zero();
+        assert_eq!(zero.gcd(&zero), zero);
+
+        // gcd((x + 1)^3, (x + 1)^2 (x - 1)) = (x + 1)^2
+        let x1 = parse("x + 1");
+        let p = x1.mul(&x1).mul(&x1);
+        let q = x1.mul(&x1).mul(&parse("x - 1"));
+        assert_eq!(p.gcd(&q), x1.mul(&x1));
+
+        // Exact coefficients also work for `f64` when no rounding happens.
+        let p = "x^2 - 1".parse::<Polynomial<f64>>().unwrap();
+        let q = "x^2 + 2x + 1".parse::<Polynomial<f64>>().unwrap();
+        assert_eq!(p.gcd(&q), "x + 1".parse().unwrap());
+    }
+
+    #[test]
+    fn test_pow() {
+        // (x + 1)^10 has binomial coefficients.
+        let p = "x + 1".parse::<Polynomial<i64>>().unwrap();
+        let p10 = p.pow(10);
+        let binomial = [1, 10, 45, 120, 210, 252, 210, 120, 45, 10, 1];
+        for (k, c) in binomial.iter().enumerate() {
+            assert_eq!(p10.coefficient(k as u64), *c);
+        }
+        assert_eq!(p10, (0..10).fold(Polynomial::one(), |acc, _| acc.mul(&p)));
+        assert_eq!(p.pow(0), Polynomial::one());
+        assert_eq!(p.pow(1), p);
+        assert_eq!(Polynomial::<i64>::zero().pow(3), Polynomial::zero());
+        assert_eq!(Polynomial::<i64>::x().pow(100), Polynomial::term(1, 100));
+
+        // (x - 1)^3 over `f64`.
+        let p = "x - 1".parse::<Polynomial<f64>>().unwrap().pow(3);
+        assert_eq!(p, "x^3 - 3x^2 + 3x - 1".parse().unwrap());
+    }
+
+    #[test]
+    fn test_from_usize_fast() {
+        assert_eq!(from_usize::<u64>(1 << 40), 1 << 40);
+        assert_eq!(from_usize::<i64>(1 << 40), 1 << 40);
+        assert_eq!(from_usize::<f64>(1 << 40), (1u64 << 40) as f64);
+        assert_eq!(from_usize::<Rational>(12), Rational::new(12, 1));
+        assert_eq!(from_usize::<MinPlus>(0), MinPlus::zero());
+        assert_eq!(from_usize::<MinPlus>(1 << 40), MinPlus(0.0));
+        assert_eq!(from_usize::<MaxPlus>(1 << 40), MaxPlus(0.0));
+        assert_eq!(
+            from_usize::<Polynomial<Polynomial<u64>>>(1 << 40),
+            Polynomial::from(Polynomial::from(1 << 40))
+        );
+    }
+
+    #[test]
+    fn test_dense_polynomial() {
+        let sparse = "2x^3 + 3x^2 + 5x + 12".parse::<Polynomial<i64>>().unwrap();
+        let dense = DensePolynomial::from(sparse.clone());
+        assert_eq!(dense.degree(), Some(3));
+        assert_eq!(dense.coefficient(2), 3);
+        assert_eq!(dense.coefficient(9), 0);
+        assert_eq!(Polynomial::from(dense.clone()), sparse);
+        for i in -5..5 {
+            assert_eq!(dense.eval(i), sparse.eval(i));
+        }
+
+        // Arithmetic agrees with the sparse representation.
+        let other = "x^5 - 3x + 1".parse::<Polynomial<i64>>().unwrap();
+        let dense_other = DensePolynomial::from(other.clone());
+        assert_eq!(
+            Polynomial::from(dense.add(&dense_other)),
+            sparse.add(&other)
+        );
+        assert_eq!(
+            Polynomial::from(dense.mul(&dense_other)),
+            sparse.mul(&other)
+        );
+
+        // Canonical form.
+        let x = DensePolynomial::<i64>::x();
+        let minus_x = DensePolynomial::term(-1, 1);
+        assert_eq!(x.add(&minus_x), DensePolynomial::zero());
+        assert_eq!(DensePolynomial::<i64>::zero().degree(), None);
+        assert_eq!(DensePolynomial::term(0i64, 5), DensePolynomial::zero());
+        assert_eq!(
+            DensePolynomial::<i64>::zero().mul(&x),
+            DensePolynomial::zero()
+        );
+        assert_eq!(
+            DensePolynomial::<i64>::from(Polynomial::zero()),
+            DensePolynomial::zero()
+        );
+        test_polynomial::<DensePolynomial<u64>>();
+    }
+
+    #[test]
+    fn test_dense_polynomial_mul() {
+        // Every coefficient is nonzero.
+        let terms = (0..300).map(|k| (k as f64 + 1.0, k)).collect::<Vec<_>>();
+        let sparse = poly(&terms);
+        let dense = DensePolynomial::<f64>::from(sparse.clone());
+        assert_eq!(
+            Polynomial::<f64>::from(dense.mul(&dense)),
+            sparse.mul(&sparse)
+        );
+
+        // Most coefficients are zero.
+        let sparse = poly(&[(1.0, 100_000), (1.0, 0)]);
+        let dense = DensePolynomial::<f64>::from(sparse.clone());
+        assert_eq!(
+            DensePolynomial::<f64>::from(sparse.mul(&sparse)),
+            dense.mul(&dense)
+        );
+    }
+
+    #[test]
+    #[ignore = "benchmark, run with `--ignored --nocapture`"]
+    fn bench_dense_polynomial() {
+        // Dense polynomials win when every coefficient is nonzero.
+        let terms = (0..300).map(|k| (k as f64 + 1.0, k)).collect::<Vec<_>>();
+        let sparse = poly(&terms);
+        let dense = DensePolynomial::<f64>::from(sparse.clone());
+        let elapsed_sparse = time(|| hint::black_box(&sparse).mul(&sparse));
+        let elapsed_dense = time(|| hint::black_box(&dense).mul(&dense));
+        println!(
+            "full: sparse {:?}, dense {:?}",
+            elapsed_sparse, elapsed_dense
+        );
+        assert!(elapsed_dense < elapsed_sparse);
+
+        // Sparse polynomials win when most coefficients are zero.
+        let sparse = poly(&[(1.0, 100_000), (1.0, 0)]);
+        let dense = DensePolynomial::<f64>::from(sparse.clone());
+        let elapsed_sparse = time(|| hint::black_box(&sparse).mul(&sparse));
+        let elapsed_dense = time(|| hint::black_box(&dense).mul(&dense));
+        println!(
+            "sparse: sparse {:?}, dense {:?}",
+            elapsed_sparse, elapsed_dense
+        );
+        assert!(elapsed_sparse < elapsed_dense);
+    }
+
+    #[test]
+    fn test_product_semiring() {
+        let a = (2u64, MinPlus(3.0));
+        let b = (5u64, MinPlus(1.0));
+        assert_eq!(a.add(&b), (7, MinPlus(1.0)));
+        assert_eq!(a.mul(&b), (10, MinPlus(4.0)));
+        assert_eq!(a.add(&Semiring::zero()), a);
+        assert_eq!(a.mul(&Semiring::one()), a);
+        assert_eq!(from_usize::<(u64, f64)>(3), (3, 3.0));
+
+        // Numbers of paths and shortest distances in a graph with edges 0->1 (1), 1->2 (2),
+        // 0->2 (5), and 2->3 (1), counting paths of length exactly 2.
+        let inf = MinPlus::zero();
+        let edge = |d| (1u64, MinPlus(d));
+        let none = (0u64, inf);
+        let adj = vec![
+            vec![none, edge(1.0), edge(5.0), none],
+            vec![none, none, edge(2.0), none],
+            vec![none, none, none, edge(1.0)],
+            vec![none, none, none, none],
+        ];
+        let two = mat_mul(&adj, &adj);
+        assert_eq!(two[0][2], (1, MinPlus(3.0)));
+        assert_eq!(two[0][3], (1, MinPlus(6.0)));
+        assert_eq!(two[1][3], (1, MinPlus(3.0)));
+        assert_eq!(two[0][1], none);
+
+        // Polynomials over a product semiring.
+        let p = Polynomial::add(
+            &Polynomial::term((1i64, 2.0), 2),
+            &Polynomial::from((3, 0.5)),
+        );
+        assert_eq!(p.eval((2, 3.0)), (7, 18.5));
+    }
+
+    #[test]
+    fn test_option_semiring() {
+        let none = None::<i64>;
+        assert_eq!(Option::<i64>::zero(), none);
+        assert_eq!(Option::<i64>::one(), Some(1));
+
+        // `None` is absorbing, while `Some(0)` is not.
+        assert_eq!(Some(3).mul(&none), None);
+        assert_eq!(Some(3i64).mul(&Some(0)), Some(0));
+        assert_eq!(Some(3).add(&none), Some(3));
+        assert_eq!(none.add(&none), None);
+        assert_eq!(Some(3i64).add(&Some(-3)), Some(0));
+        assert_eq!(from_usize::<Option<i64>>(0), None);
+        assert_eq!(from_usize::<Option<i64>>(4), Some(4));
+
+        // Sums of products of costs, where `None` marks missing edges.
+        let adj = vec![vec![None, Some(2.0)], vec![Some(3.0), None]];
+        let two = mat_mul(&adj, &adj);
+        assert_eq!(two, vec![vec![Some(6.0), None], vec![None, Some(6.0)]]);
+    }
+
+    #[test]
+    fn test_star() {
+        assert!(false.star());
+        assert!(true.star());
+        assert_eq!(MinPlus(3.0).star(), MinPlus(0.0));
+        assert_eq!(MinPlus(-1.0).star(), MinPlus(f64::NEG_INFINITY));
+
+        // a* = 1 + a * a*
+        for a in [MinPlus(0.0), MinPlus(2.5), MinPlus(-2.0), MinPlus::zero()] {
+            assert_eq!(a.star(), MinPlus::one().add(&a.mul(&a.star())));
+        }
+    }
+
+    #[test]
+    fn test_transitive_closure() {
+        // 0 -> 1 -> 2 -> 0 and 3 -> 2
+        let edges = [(0, 1), (1, 2), (2, 0), (3, 2)];
+        let mut adj = vec![vec![false; 4]; 4];
+        for (i, j) in edges {
+            adj[i][j] = true;
+        }
+        let closure = kleene_closure(&adj);
+        for (i, row) in closure.iter().enumerate() {
+            for (j, reachable) in row.iter().enumerate() {
+                assert_eq!(*reachable, i == 3 || j != 3);
+            }
+        }
+        assert_eq!(kleene_closure::<bool>(&[]), Vec::<Vec<bool>>::new());
+    }
+
+    #[test]
+    fn test_all_pairs_shortest_paths() {
+        let inf = f64::INFINITY;
+        let adj = [
+            [inf, 1.0, 5.0, inf],
+            [inf, inf, 2.0, inf],
+            [inf, inf, inf, 1.0],
+            [0.5, inf, inf, inf],
+        ]
+        .map(|row| row.map(MinPlus).to_vec())
+        .to_vec();
+        let dist = kleene_closure(&adj);
+        let expected = [
+            [0.0, 1.0, 3.0, 4.0],
+            [3.5, 0.0, 2.0, 3.0],
+            [1.5, 2.5, 0.0, 1.0],
+            [0.5, 1.5, 3.5, 0.0],
+        ]
+        .map(|row| row.map(MinPlus).to_vec())
+        .to_vec();
+        assert_eq!(dist, expected);
+
+        // A negative cycle makes every distance through it `-∞`.
+        let adj = vec![
+            vec![MinPlus(inf), MinPlus(-1.0)],
+            vec![MinPlus(0.5), MinPlus(inf)],
+        ];
+        let dist = kleene_closure(&adj);
+        assert!(dist
+            .iter()
+            .flatten()
+            .all(|d| *d == MinPlus(f64::NEG_INFINITY)));
+    }
+
+    #[test]
+    #[should_panic(expected = "matrix must be square")]
+    fn test_kleene_closure_not_square() {
+        let _unused = kleene_closure(&[vec![true, false]]);
+    }
 }
@@ -0,0 +1,316 @@
+//! Hash-consed complex functions, where identical subexpressions are shared.
+//!
+//! Differentiating `ComplexFuncs` clones whole subtrees, e.g., the derivative of `f * g` contains
+//! both `f` and `g` again. Here every node is interned in a `FuncStore`, so structurally equal
+//! subexpressions are represented by the same `Rc` and the derivative only allocates new nodes.
+//!
+//! HINT: Consult <https://en.wikipedia.org/wiki/Hash_consing>
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+use crate::assignments::assignment06::symbolic_differentiation::*;
+
+/// Shared reference to an interned node.
+///
+/// Since all nodes are interned, two `SharedFunc`s are structurally equal if and only if they
+/// point to the same node. Hence equality and hashing only look at the pointer.
+#[derive(Debug, Clone)]
+pub struct SharedFunc(Rc<Node>);
+
+impl PartialEq for SharedFunc {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for SharedFunc {}
+
+impl Hash for SharedFunc {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Rc::as_ptr(&self.0).hash(state);
+    }
+}
+
+/// Node of a shared function, mirroring `ComplexFuncs<BaseFuncs>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Node {
+    /// Basic functions
+    Func(BaseFuncs),
+    /// Addition
+    Add(SharedFunc, SharedFunc),
+    /// Subtraction
+    Sub(SharedFunc, SharedFunc),
+    /// Multipliciation
+    Mul(SharedFunc, SharedFunc),
+    /// Division
+    Div(SharedFunc, SharedFunc),
+    /// Composition
+    Comp(SharedFunc, SharedFunc),
+    /// Power with a function exponent
+    Pow(SharedFunc, SharedFunc),
+    /// Natural logarithm
+    Ln(SharedFunc),
+}
+
+impl Node {
+    /// Returns the children of `self`.
+    fn children(&self) -> Vec<&SharedFunc> {
+        match self {
+            Self::Func(_) => vec![],
+            Self::Ln(f) => vec![f],
+            Self::Add(f1, f2)
+            | Self::Sub(f1, f2)
+            | Self::Mul(f1, f2)
+            | Self::Div(f1, f2)
+            | Self::Comp(f1, f2)
+            | Self::Pow(f1, f2) => vec![f1, f2],
+        }
+    }
+}
+
+/// Store of interned nodes.
+///
+/// Every `SharedFunc` should be created by `intern` of a single store, otherwise equal
+/// subexpressions are not guaranteed to be shared.
+#[derive(Debug, Default)]
+pub struct FuncStore {
+    nodes: HashMap<Node, SharedFunc>,
+    derivatives: HashMap<SharedFunc, SharedFunc>,
+}
+
+impl FuncStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of distinct nodes in the store.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if the store has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns the shared node equal to `node`, creating one if it does not exist yet.
+    pub fn intern(&mut self, node: Node) -> SharedFunc {
+        if let Some(f) = self.nodes.get(&node) {
+            return f.clone();
+        }
+        let f = SharedFunc(Rc::new(node.clone()));
+        let _unused = self.nodes.insert(node, f.clone());
+        f
+    }
+
+    /// Interns every subexpression of `f`.
+    pub fn from_complex(&mut self, f: &ComplexFuncs<BaseFuncs>) -> SharedFunc {
+        type CF = ComplexFuncs<BaseFuncs>;
+        let node = match f {
+            CF::Func(func) => Node::Func(*func),
+            CF::Add(f1, f2) => Node::Add(self.from_complex(f1), self.from_complex(f2)),
+            CF::Sub(f1, f2) => Node::Sub(self.from_complex(f1), self.from_complex(f2)),
+            CF::Mul(f1, f2) => Node::Mul(self.from_complex(f1), self.from_complex(f2)),
+            CF::Div(f1, f2) => Node::Div(self.from_complex(f1), self.from_complex(f2)),
+            CF::Comp(f1, f2) => Node::Comp(self.from_complex(f1), self.from_complex(f2)),
+            CF::Pow(f1, f2) => Node::Pow(self.from_complex(f1), self.from_complex(f2)),
+            CF::Ln(f1) => Node::Ln(self.from_complex(f1)),
+        };
+        self.intern(node)
+    }
+
+    /// Differentiates `f` with the same rules as `ComplexFuncs::diff`.
+    ///
+    /// Derivatives are memoized, so each shared subexpression is differentiated only once.
+    pub fn diff(&mut self, f: &SharedFunc) -> SharedFunc {
+        if let Some(d) = self.derivatives.get(f) {
+            return d.clone();
+        }
+
+        let d = match f.node() {
+            Node::Func(func) => self.intern(Node::Func(func.diff())),
+            Node::Add(f1, f2) => {
+                let (d1, d2) = (self.diff(f1), self.diff(f2));
+                self.intern(Node::Add(d1, d2))
+            }
+            Node::Sub(f1, f2) => {
+                let (d1, d2) = (self.diff(f1), self.diff(f2));
+                self.intern(Node::Sub(d1, d2))
+            }
+            Node::Mul(f1, f2) => {
+                let (d1, d2) = (self.diff(f1), self.diff(f2));
+                let l = self.intern(Node::Mul(d1, f2.clone()));
+                let r = self.intern(Node::Mul(f1.clone(), d2));
+                self.intern(Node::Add(l, r))
+            }
+            Node::Div(f1, f2) => {
+                let (d1, d2) = (self.diff(f1), self.diff(f2));
+                let l = self.intern(Node::Mul(d1, f2.clone()));
+                let r = self.intern(Node::Mul(f1.clone(), d2));
+                let numerator = self.intern(Node::Sub(l, r));
+                let denominator = self.intern(Node::Mul(f2.clone(), f2.clone()));
+                self.intern(Node::Div(numerator, denominator))
+            }
+            Node::Comp(f1, f2) => {
+                let (d1, d2) = (self.diff(f1), self.diff(f2));
+                let outer = self.intern(Node::Comp(d1, f2.clone()));
+                self.intern(Node::Mul(d2, outer))
+            }
+            Node::Pow(f1, f2) => {
+                let (d1, d2) = (self.diff(f1), self.diff(f2));
+                let ln = self.intern(Node::Ln(f1.clone()));
+                let l = self.intern(Node::Mul(d2, ln));
+                let r = self.intern(Node::Mul(f2.clone(), d1));
+                let r = self.intern(Node::Div(r, f1.clone()));
+                let sum = self.intern(Node::Add(l, r));
+                self.intern(Node::Mul(f.clone(), sum))
+            }
+            Node::Ln(f1) => {
+                let d1 = self.diff(f1);
+                self.intern(Node::Div(d1, f1.clone()))
+            }
+        };
+        let _unused = self.derivatives.insert(f.clone(), d.clone());
+        d
+    }
+}
+
+impl SharedFunc {
+    /// Returns the node `self` points to.
+    pub fn node(&self) -> &Node {
+        &self.0
+    }
+
+    /// Expands `self` into a tree, duplicating shared subexpressions.
+    pub fn to_complex(&self) -> ComplexFuncs<BaseFuncs> {
+        type CF = ComplexFuncs<BaseFuncs>;
+        let tree = |f: &Self| Box::new(f.to_complex());
+        match self.node() {
+            Node::Func(func) => CF::Func(*func),
+            Node::Add(f1, f2) => CF::Add(tree(f1), tree(f2)),
+            Node::Sub(f1, f2) => CF::Sub(tree(f1), tree(f2)),
+            Node::Mul(f1, f2) => CF::Mul(tree(f1), tree(f2)),
+            Node::Div(f1, f2) => CF::Div(tree(f1), tree(f2)),
+            Node::Comp(f1, f2) => CF::Comp(tree(f1), tree(f2)),
+            Node::Pow(f1, f2) => CF::Pow(tree(f1), tree(f2)),
+            Node::Ln(f1) => CF::Ln(tree(f1)),
+        }
+    }
+
+    /// Returns the number of distinct nodes reachable from `self`.
+    pub fn size(&self) -> usize {
+        let mut visited = HashSet::new();
+        let mut stack = vec![self];
+        while let Some(f) = stack.pop() {
+            if visited.insert(f) {
+                stack.extend(f.node().children());
+            }
+        }
+        visited.len()
+    }
+
+    /// Returns the number of nodes of `self` expanded into a tree, i.e., `self.to_complex().size()`
+    /// without building the tree. Saturates at `usize::MAX`.
+    pub fn tree_size(&self) -> usize {
+        fn go(f: &SharedFunc, memo: &mut HashMap<SharedFunc, usize>) -> usize {
+            if let Some(size) = memo.get(f) {
+                return *size;
+            }
+            let size = f
+                .node()
+                .children()
+                .into_iter()
+                .fold(1usize, |acc, g| acc.saturating_add(go(g, memo)));
+            let _unused = memo.insert(f.clone(), size);
+            size
+        }
+        go(self, &mut HashMap::new())
+    }
+
+    /// Returns the depth of `self`, which is the same as `self.to_complex().depth()`.
+    pub fn depth(&self) -> usize {
+        fn go(f: &SharedFunc, memo: &mut HashMap<SharedFunc, usize>) -> usize {
+            if let Some(depth) = memo.get(f) {
+                return *depth;
+            }
+            let depth = 1 + f
+                .node()
+                .children()
+                .into_iter()
+                .map(|g| go(g, memo))
+                .max()
+                .unwrap_or(0);
+            let _unused = memo.insert(f.clone(), depth);
+            depth
+        }
+        go(self, &mut HashMap::new())
+    }
+
+    /// Evaluates `self` at `x`, where each shared subexpression is evaluated once per argument.
+    fn eval_memo(
+        &self,
+        x: f64,
+        memo: &mut HashMap<(SharedFunc, u64), Result<f64, EvalError>>,
+    ) -> Result<f64, EvalError> {
+        let key = (self.clone(), x.to_bits());
+        if let Some(value) = memo.get(&key) {
+            return *value;
+        }
+
+        let value = match self.node() {
+            Node::Func(f) => f.try_evaluate(x),
+            Node::Add(f1, f2) => f1
+                .eval_memo(x, memo)
+                .and_then(|v1| check(v1 + f2.eval_memo(x, memo)?)),
+            Node::Sub(f1, f2) => f1
+                .eval_memo(x, memo)
+                .and_then(|v1| check(v1 - f2.eval_memo(x, memo)?)),
+            Node::Mul(f1, f2) => f1
+                .eval_memo(x, memo)
+                .and_then(|v1| check(v1 * f2.eval_memo(x, memo)?)),
+            Node::Div(f1, f2) => f2.eval_memo(x, memo).and_then(|denominator| {
+                if denominator == 0.0 {
+                    Err(EvalError::DivisionByZero)
+                } else {
+                    check(f1.eval_memo(x, memo)? / denominator)
+                }
+            }),
+            Node::Comp(f1, f2) => f2
+                .eval_memo(x, memo)
+                .and_then(|inner| f1.eval_memo(inner, memo)),
+            Node::Pow(f1, f2) => f1.eval_memo(x, memo).and_then(|base| {
+                let exponent = f2.eval_memo(x, memo)?;
+                if base == 0.0 && exponent < 0.0 {
+                    Err(EvalError::DivisionByZero)
+                } else {
+                    check(base.powf(exponent))
+                }
+            }),
+            Node::Ln(f) => f.eval_memo(x, memo).and_then(|value| {
+                if value <= 0.0 {
+                    Err(EvalError::Domain)
+                } else {
+                    check(value.ln())
+                }
+            }),
+        };
+        let _unused = memo.insert(key, value);
+        value
+    }
+}
+
+impl Evaluate for SharedFunc {
+    fn try_evaluate(&self, x: f64) -> Result<f64, EvalError> {
+        self.eval_memo(x, &mut HashMap::new())
+    }
+}
+
+impl fmt::Display for SharedFunc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_complex())
+    }
+}
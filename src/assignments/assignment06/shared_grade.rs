@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod test {
+    use ntest::assert_about_eq;
+
+    use crate::assignments::assignment06::shared::*;
+    use crate::assignments::assignment06::symbolic_differentiation::*;
+    use crate::func;
+
+    #[test]
+    fn test_intern() {
+        let mut store = FuncStore::new();
+        assert!(store.is_empty());
+
+        // `sin(x)` appears twice but is stored once.
+        let f = store.from_complex(&func!(sin(x) * sin(x) + x));
+        let sin = store.from_complex(&func!(sin(x)));
+        assert_eq!(store.len(), 4);
+        assert_eq!(f.size(), 4);
+        assert_eq!(f.tree_size(), 5);
+        assert_eq!(f.depth(), 3);
+        assert!(
+            matches!(f.node(), Node::Add(g, _) if *g.node() == Node::Mul(sin.clone(), sin.clone()))
+        );
+
+        let g = store.intern(Node::Mul(sin.clone(), sin));
+        assert_eq!(store.len(), 4);
+        assert_eq!(store.from_complex(&f.to_complex()), f);
+        assert_ne!(f, g);
+    }
+
+    #[test]
+    fn test_shared_diff() {
+        let mut store = FuncStore::new();
+        let complex = func!(sin(x) * exp(x) / (x ^ 2 + 1));
+        let f = store.from_complex(&complex);
+        let d = store.diff(&f);
+
+        assert_eq!(d.to_complex(), complex.diff());
+        assert_eq!(d.tree_size(), complex.diff().size());
+        assert_eq!(d.depth(), complex.diff().depth());
+        assert!(d.size() < d.tree_size());
+        assert_eq!(store.diff(&f), d);
+
+        for x in [-1.0, 0.0, 0.5, 2.0] {
+            assert_about_eq!(d.evaluate(x), complex.diff().evaluate(x));
+        }
+    }
+
+    #[test]
+    fn test_deep_diff() {
+        // f_0 = x, f_{n+1} = f_n * f_n, whose tree doubles at each step.
+        let mut store = FuncStore::new();
+        let mut f = store.from_complex(&func!(x));
+        for _ in 0..30 {
+            f = store.intern(Node::Mul(f.clone(), f));
+        }
+        assert_eq!(f.size(), 31);
+        assert_eq!(f.tree_size(), (1 << 31) - 1);
+        assert_eq!(f.depth(), 31);
+
+        // The 5th derivative of x^(2^30) is still small when shared, unlike its tree.
+        let mut d = f.clone();
+        for _ in 0..5 {
+            d = store.diff(&d);
+        }
+        assert!(d.size() < 2000);
+        assert!(d.tree_size() > 1 << 40);
+
+        assert_about_eq!(f.evaluate(1.0), 1.0);
+        assert_about_eq!(store.diff(&f).evaluate(1.0), 2f64.powi(30));
+    }
+
+    #[test]
+    fn test_size_depth() {
+        let f = func!(sin(x) * sin(x) + x);
+        assert_eq!(f.size(), 5);
+        assert_eq!(f.depth(), 3);
+        assert_eq!(func!(x).size(), 1);
+        assert_eq!(func!(x).depth(), 1);
+        assert_eq!(func!(ln(x)).depth(), 2);
+    }
+}
@@ -1,20 +1,21 @@
 //! Symbolic differentiation with rational coefficents.
 
-use num::integer::gcd;
 use std::fmt;
 use std::ops::*;
 
+use crate::assignments::assignment06::semiring::{Polynomial, Semiring};
+
 /// Rational number represented by two isize, numerator and denominator.
 ///
 /// Each Rational number should be normalized so that `demoninator` is nonnegative and `numerator`
-/// and `demoninator` are coprime. See `normalize` for examples. As a corner case, 0 is represented
-/// by `Rational { numerator: 0, demoninator: 0 }`.
+/// and `demoninator` are coprime. See `new` for examples. As a corner case, 0 is represented by
+/// `Rational { numerator: 0, demoninator: 0 }`.
 ///
-/// For "natural use", it also overloads standard arithmetic operations, i.e, `+`, `-`, `*`, and
-/// `/`.
+/// For "natural use", it also overloads standard arithmetic operations, i.e, `+`, `-`, `*`, `/`,
+/// and unary `-`.
 ///
 /// See [here](https://doc.rust-lang.org/core/ops/index.html) for details.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Rational {
     numerator: isize,
     denominator: isize,
@@ -29,78 +30,240 @@ pub const ONE: Rational = Rational::new(1, 1);
 /// Minus one
 pub const MINUS_ONE: Rational = Rational::new(-1, 1);
 
+/// Greatest common divisor, usable in `const` contexts.
+const fn gcd(a: isize, b: isize) -> isize {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
 impl Rational {
-    /// Creates a new rational number.
+    /// Creates a new rational number, normalized.
+    ///
+    /// For example, `Rational::new(2, -4)` is `-1/2`, and `Rational::new(0, 5)` is `ZERO`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `denominator` is zero but `numerator` is not.
     pub const fn new(numerator: isize, denominator: isize) -> Self {
+        if numerator == 0 {
+            return Self {
+                numerator: 0,
+                denominator: 0,
+            };
+        }
+        if denominator == 0 {
+            panic!("devide by zero");
+        }
+
+        let gcd = gcd(numerator, denominator);
+        let sign = if denominator < 0 { -1 } else { 1 };
         Self {
-            numerator,
-            denominator,
+            numerator: sign * numerator / gcd,
+            denominator: sign * denominator / gcd,
         }
     }
-}
 
-impl Add for Rational {
-    type Output = Self;
+    /// Approximates `x` with the closest rational number whose denominator is at most
+    /// `max_denominator`.
+    ///
+    /// Returns `None` if `x` is not finite or does not fit in `isize`.
+    ///
+    /// HINT: Consult <https://en.wikipedia.org/wiki/Continued_fraction#Best_rational_approximations>
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_denominator` is not positive.
+    pub fn from_f64(x: f64, max_denominator: isize) -> Option<Self> {
+        assert!(max_denominator > 0, "max_denominator must be positive");
+        if !x.is_finite() || x.abs() >= isize::MAX as f64 {
+            return None;
+        }
 
-    fn add(self, rhs: Self) -> Self::Output {
+        // Convergents `p0/q0` and `p1/q1` of the continued fraction of `|x|`.
+        let (mut p0, mut q0, mut p1, mut q1) = (0, 1, 1, 0);
+        let mut v = x.abs();
+        loop {
+            let a = v.floor();
+            if q0 as f64 + a * q1 as f64 > max_denominator as f64 {
+                break;
+            }
+            let a = a as isize;
+            let Some(p) = a.checked_mul(p1).and_then(|p| p.checked_add(p0)) else {
+                break;
+            };
+            (p0, q0, p1, q1) = (p1, q1, p, q0 + a * q1);
+
+            let frac = v - v.floor();
+            if frac == 0.0 {
+                break;
+            }
+            v = 1.0 / frac;
+        }
+
+        // The best approximation is either the last convergent or a semiconvergent, unless the
+        // latter does not fit in `isize`.
+        let k = (max_denominator - q0) / q1;
+        let semi = k
+            .checked_mul(p1)
+            .and_then(|p| p.checked_add(p0))
+            .map(|p| Self::new(p, q0 + k * q1));
+        let conv = Self::new(p1, q1);
+        let error = |r: Self| (r.evaluate(x) - x.abs()).abs();
+        let ret = match semi {
+            Some(semi) if error(semi) < error(conv) => semi,
+            _ => conv,
+        };
+        Some(if x < 0.0 { -ret } else { ret })
+    }
+
+    /// Returns the absolute value.
+    pub fn abs(self) -> Self {
+        Self::new(self.numerator.abs(), self.denominator)
+    }
+
+    /// Returns the multiplicative inverse.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is zero.
+    pub fn reciprocal(self) -> Self {
         if self == ZERO {
-            rhs
-        } else if rhs == ZERO {
-            self
-        } else {
-            let mut numerator = self.numerator * rhs.denominator + self.denominator * rhs.numerator;
-            let mut denominator = self.denominator * rhs.denominator;
-            let gcd = gcd(numerator, denominator);
+            panic!("devide by zero");
+        }
+        Self::new(self.denominator, self.numerator)
+    }
+
+    /// Raises `self` to the power of `exp`. Negative powers are powers of the reciprocal.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is zero and `exp` is negative, or the result does not fit in `isize`.
+    pub fn pow(self, exp: i32) -> Self {
+        self.checked_pow(exp)
+            .expect("attempt to raise to a power with overflow")
+    }
+
+    /// Checked addition. Returns `None` if the result does not fit in `isize`.
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        let numerator = self
+            .numerator
+            .checked_mul(rhs.denom())?
+            .checked_add(self.denom().checked_mul(rhs.numerator)?)?;
+        Self::checked_new(numerator, self.denom().checked_mul(rhs.denom())?)
+    }
 
-            numerator /= gcd;
-            denominator /= gcd;
+    /// Checked subtraction. Returns `None` if the result does not fit in `isize`.
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_add(Self {
+            numerator: rhs.numerator.checked_neg()?,
+            denominator: rhs.denominator,
+        })
+    }
 
-            if denominator < 0 {
-                numerator = -numerator;
-                denominator = -numerator;
-            }
+    /// Checked multiplication. Returns `None` if the result does not fit in `isize`.
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        Self::checked_new(
+            self.numerator.checked_mul(rhs.numerator)?,
+            self.denom().checked_mul(rhs.denom())?,
+        )
+    }
 
-            Self {
-                numerator,
-                denominator,
+    /// Checked exponentiation. Returns `None` if the result does not fit in `isize`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is zero and `exp` is negative.
+    pub fn checked_pow(self, exp: i32) -> Option<Self> {
+        let mut base = if exp < 0 { self.reciprocal() } else { self };
+        let mut exp = exp.unsigned_abs();
+        let mut acc = ONE;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                acc = acc.checked_mul(base)?;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.checked_mul(base)?;
             }
         }
+        Some(acc)
     }
-}
 
-impl Mul for Rational {
-    type Output = Self;
+    /// Returns the value of `self` if it is an integer.
+    pub fn as_integer(self) -> Option<isize> {
+        if self == ZERO {
+            Some(0)
+        } else if self.denominator == 1 {
+            Some(self.numerator)
+        } else {
+            None
+        }
+    }
 
-    fn mul(self, rhs: Self) -> Self::Output {
-        if self == ZERO || rhs == ZERO {
-            ZERO
+    /// Like `new`, but returns `None` if normalization would overflow.
+    fn checked_new(numerator: isize, denominator: isize) -> Option<Self> {
+        if numerator == isize::MIN || denominator == isize::MIN {
+            return None;
+        }
+        Some(Self::new(numerator, denominator))
+    }
+
+    /// Denominator used for arithmetic, where zero is regarded as `0/1`.
+    fn denom(self) -> isize {
+        if self == ZERO {
+            1
         } else {
-            let mut numerator = self.numerator * rhs.numerator;
-            let mut denominator = self.denominator * rhs.denominator;
-            let gcd = gcd(numerator, denominator);
+            self.denominator
+        }
+    }
+}
 
-            numerator /= gcd;
-            denominator /= gcd;
+impl From<isize> for Rational {
+    fn from(value: isize) -> Self {
+        Self::new(value, 1)
+    }
+}
 
-            if denominator < 0 {
-                numerator = -numerator;
-                denominator = -denominator;
-            }
+impl Neg for Rational {
+    type Output = Self;
 
-            Self {
-                numerator,
-                denominator,
-            }
+    fn neg(self) -> Self::Output {
+        Self {
+            numerator: -self.numerator,
+            denominator: self.denominator,
         }
     }
 }
 
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(
+            self.numerator * rhs.denom() + self.denom() * rhs.numerator,
+            self.denom() * rhs.denom(),
+        )
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::new(self.numerator * rhs.numerator, self.denom() * rhs.denom())
+    }
+}
+
 impl Sub for Rational {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        let neg = rhs.mul(MINUS_ONE);
-        self.add(neg)
+        self.add(-rhs)
     }
 }
 
@@ -108,15 +271,65 @@ impl Div for Rational {
     type Output = Self;
 
     fn div(self, rhs: Self) -> Self::Output {
-        if rhs == ZERO {
-            panic!("devide by zero");
-        } else {
-            let rev = Self {
-                numerator: rhs.denominator,
-                denominator: rhs.numerator,
-            };
-            self.mul(rev)
+        self.mul(rhs.reciprocal())
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let cross = |l: Self, r: Self| l.numerator as i128 * r.denom() as i128;
+        cross(*self, *other).cmp(&cross(*other, *self))
+    }
+}
+
+/// Error type for parsing rationals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseRationalError {
+    /// The numerator or the denominator is not a valid integer.
+    InvalidInteger(std::num::ParseIntError),
+    /// The numerator or the denominator is out of the range of `Rational`, i.e., `isize::MIN`.
+    OutOfRange,
+    /// The denominator is zero.
+    ZeroDenominator,
+}
+
+impl From<std::num::ParseIntError> for ParseRationalError {
+    fn from(err: std::num::ParseIntError) -> Self {
+        Self::InvalidInteger(err)
+    }
+}
+
+impl fmt::Display for ParseRationalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidInteger(err) => write!(f, "{}", err),
+            Self::OutOfRange => write!(f, "out of range"),
+            Self::ZeroDenominator => write!(f, "zero denominator"),
+        }
+    }
+}
+
+impl std::error::Error for ParseRationalError {}
+
+impl std::str::FromStr for Rational {
+    type Err = ParseRationalError;
+
+    /// Parses `n` or `p/q`, as printed by `Display`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (p, q) = match s.split_once('/') {
+            Some((p, q)) => (p.parse()?, q.parse()?),
+            None => (s.parse()?, 1),
+        };
+        if q == 0 {
+            return Err(ParseRationalError::ZeroDenominator);
         }
+        Self::checked_new(p, q).ok_or(ParseRationalError::OutOfRange)
     }
 }
 
@@ -142,7 +355,7 @@ impl Differentiable for Rational {
 ///
 /// Unlike regular polynomials, this type only represents a single term.
 /// The `Const` variant is included to make `Polynomial` closed under differentiation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SingletonPolynomial {
     /// Constant polynomial.
     Const(Rational),
@@ -185,14 +398,59 @@ impl Differentiable for SingletonPolynomial {
     }
 }
 
-/// Expoential function.(`e^x`)
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct Exp;
+/// Expoential function.(`c * ln(a)^m * a^(kx)`)
+///
+/// The base `a` is either `e` or a positive rational number. The coefficient `c` and the power of
+/// `ln(a)` are carried to be closed under differentiation, since `d/dx a^(kx) = k * ln(a) *
+/// a^(kx)`. For base `e`, `m` is always zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Exp {
+    coeff: Rational,
+    /// `None` for base `e`.
+    base: Option<Rational>,
+    rate: Rational,
+    log_power: u32,
+}
 
 impl Exp {
-    /// Creates a new exponential function.
+    /// Creates a new exponential function.(`e^x`)
     pub fn new() -> Self {
-        Exp
+        Self::new_natural(ONE)
+    }
+
+    /// Creates a new natural exponential function with the given rate.(`e^(kx)`)
+    pub fn new_natural(rate: Rational) -> Self {
+        Self {
+            coeff: ONE,
+            base: None,
+            rate,
+            log_power: 0,
+        }
+    }
+
+    /// Creates a new exponential function with the given base and rate.(`a^(kx)`)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `base` is not positive.
+    pub fn new_base(base: Rational, rate: Rational) -> Self {
+        assert!(base > ZERO, "base must be positive");
+        Self {
+            coeff: ONE,
+            base: Some(base),
+            rate,
+            log_power: 0,
+        }
+    }
+
+    /// Returns `ln(a)`.
+    fn ln_base(&self) -> f64 {
+        self.base.map_or(1.0, |base| base.evaluate(0.0).ln())
+    }
+
+    /// Returns the constant factor `c * ln(a)^m`.
+    fn scale(&self) -> f64 {
+        self.coeff.evaluate(0.0) * self.ln_base().powi(self.log_power as i32)
     }
 }
 
@@ -205,14 +463,18 @@ impl Default for Exp {
 impl Differentiable for Exp {
     /// HINT: Consult <https://en.wikipedia.org/wiki/Differentiation_rules#Derivatives_of_exponential_and_logarithmic_functions>
     fn diff(&self) -> Self {
-        Exp
+        Self {
+            coeff: self.coeff * self.rate,
+            log_power: self.log_power + u32::from(self.base.is_some()),
+            ..*self
+        }
     }
 }
 
 /// Trigonometric functions.
 ///
 /// The trig fucntions carry their coefficents to be closed under differntiation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Trignometric {
     /// Sine function.
     Sine {
@@ -252,7 +514,7 @@ impl Differentiable for Trignometric {
 }
 
 /// Basic functions
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum BaseFuncs {
     /// Constant
     Const(Rational),
@@ -276,7 +538,7 @@ impl Differentiable for BaseFuncs {
 }
 
 /// Complex functions.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ComplexFuncs<F> {
     /// Basic functions
     Func(F),
@@ -290,6 +552,10 @@ pub enum ComplexFuncs<F> {
     Div(Box<ComplexFuncs<F>>, Box<ComplexFuncs<F>>),
     /// Composition
     Comp(Box<ComplexFuncs<F>>, Box<ComplexFuncs<F>>),
+    /// Power with a function exponent
+    Pow(Box<ComplexFuncs<F>>, Box<ComplexFuncs<F>>),
+    /// Natural logarithm
+    Ln(Box<ComplexFuncs<F>>),
 }
 
 impl<F: Differentiable> Differentiable for Box<F> {
@@ -317,121 +583,1317 @@ impl<F: Differentiable> Differentiable for ComplexFuncs<F> {
                 Box::new(Self::Mul(f2.clone(), f2.clone())),
             ),
             Self::Comp(f1, f2) => Self::Mul(f2.diff(), Box::new(Self::Comp(f1.diff(), f2.clone()))),
+            // HINT: Consult <https://en.wikipedia.org/wiki/Logarithmic_derivative>
+            Self::Pow(f1, f2) => Self::Mul(
+                Box::new(self.clone()),
+                Box::new(Self::Add(
+                    Box::new(Self::Mul(f2.diff(), Box::new(Self::Ln(f1.clone())))),
+                    Box::new(Self::Div(
+                        Box::new(Self::Mul(f2.clone(), f1.diff())),
+                        f1.clone(),
+                    )),
+                )),
+            ),
+            Self::Ln(f) => Self::Div(f.diff(), f.clone()),
         }
     }
 }
 
-/// Evaluate functions.
-pub trait Evaluate {
-    ///  Evaluate `self` at `x`.
-    fn evaluate(&self, x: f64) -> f64;
-}
-
-impl Evaluate for Rational {
-    fn evaluate(&self, x: f64) -> f64 {
-        self.numerator as f64 / self.denominator as f64
+impl BaseFuncs {
+    /// Returns the value of `self` if it is a constant function.
+    pub fn as_const(&self) -> Option<Rational> {
+        match self {
+            Self::Const(r) | Self::Poly(SingletonPolynomial::Const(r)) => Some(*r),
+            Self::Poly(SingletonPolynomial::Polynomial { coeff, .. })
+            | Self::Trig(Trignometric::Sine { coeff } | Trignometric::Cosine { coeff })
+            | Self::Exp(Exp { coeff, .. })
+                if *coeff == ZERO =>
+            {
+                Some(ZERO)
+            }
+            Self::Exp(Exp {
+                coeff,
+                rate,
+                log_power: 0,
+                ..
+            }) if *rate == ZERO => Some(*coeff),
+            _ => None,
+        }
     }
 }
 
-impl Evaluate for SingletonPolynomial {
-    fn evaluate(&self, x: f64) -> f64 {
+impl<F> ComplexFuncs<F> {
+    /// Returns the number of nodes in `self`.
+    pub fn size(&self) -> usize {
         match self {
-            Self::Const(r) => r.evaluate(x),
-            Self::Polynomial { coeff, power } => coeff.evaluate(x) * x.powf(power.evaluate(x)),
+            Self::Func(_) => 1,
+            Self::Ln(f) => 1 + f.size(),
+            Self::Add(f1, f2)
+            | Self::Sub(f1, f2)
+            | Self::Mul(f1, f2)
+            | Self::Div(f1, f2)
+            | Self::Comp(f1, f2)
+            | Self::Pow(f1, f2) => 1 + f1.size() + f2.size(),
         }
     }
-}
 
-impl Evaluate for Exp {
-    fn evaluate(&self, x: f64) -> f64 {
-        x.exp()
+    /// Returns the length of the longest path from the root of `self` to a basic function, where a
+    /// single basic function has depth 1.
+    pub fn depth(&self) -> usize {
+        match self {
+            Self::Func(_) => 1,
+            Self::Ln(f) => 1 + f.depth(),
+            Self::Add(f1, f2)
+            | Self::Sub(f1, f2)
+            | Self::Mul(f1, f2)
+            | Self::Div(f1, f2)
+            | Self::Comp(f1, f2)
+            | Self::Pow(f1, f2) => 1 + f1.depth().max(f2.depth()),
+        }
     }
 }
 
-impl Evaluate for Trignometric {
-    fn evaluate(&self, x: f64) -> f64 {
+impl<F: Clone> ComplexFuncs<F> {
+    /// Replaces the free variable of `self` by `replacement`.
+    ///
+    /// For example, substituting `x^2` into `sin(x) + x` results in `sin(x^2) + x^2`.
+    pub fn substitute(&self, replacement: &ComplexFuncs<F>) -> Self {
+        let sub = |f: &Self| Box::new(f.substitute(replacement));
         match self {
-            Self::Sine { coeff } => coeff.evaluate(x) * x.sin(),
-            Self::Cosine { coeff } => coeff.evaluate(x) * x.cos(),
+            Self::Func(_) => Self::Comp(Box::new(self.clone()), Box::new(replacement.clone())),
+            Self::Add(f1, f2) => Self::Add(sub(f1), sub(f2)),
+            Self::Sub(f1, f2) => Self::Sub(sub(f1), sub(f2)),
+            Self::Mul(f1, f2) => Self::Mul(sub(f1), sub(f2)),
+            Self::Div(f1, f2) => Self::Div(sub(f1), sub(f2)),
+            Self::Comp(f1, f2) => Self::Comp(f1.clone(), sub(f2)),
+            Self::Pow(f1, f2) => Self::Pow(sub(f1), sub(f2)),
+            Self::Ln(f) => Self::Ln(sub(f)),
         }
     }
 }
 
-impl Evaluate for BaseFuncs {
-    fn evaluate(&self, x: f64) -> f64 {
+/// Error type for parsing S-expressions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SexprError {
+    /// The input ended in the middle of an expression.
+    UnexpectedEnd,
+    /// The given token is not allowed at its position.
+    UnexpectedToken(String),
+    /// There are remaining tokens after a complete expression.
+    TrailingInput,
+    /// The given rational token has a zero denominator.
+    ZeroDenominator(String),
+}
+
+impl ComplexFuncs<BaseFuncs> {
+    /// Collapses every constant subtree of `self` into a single `Const`.
+    ///
+    /// Division by a constant zero is left as is, so that evaluating the result behaves the same
+    /// as evaluating `self`. Likewise, only integer powers and `ln(1)` are collapsed since the
+    /// others are not rational in general.
+    pub fn partial_eval(&self) -> Self {
+        let fold = |f1: &Self,
+                    f2: &Self,
+                    op: fn(Rational, Rational) -> Option<Rational>,
+                    ctor: fn(Box<Self>, Box<Self>) -> Self| {
+            let (f1, f2) = (f1.partial_eval(), f2.partial_eval());
+            if let (Some(r1), Some(r2)) = (f1.as_const(), f2.as_const()) {
+                if let Some(r) = op(r1, r2) {
+                    return Self::Func(BaseFuncs::Const(r));
+                }
+            }
+            ctor(Box::new(f1), Box::new(f2))
+        };
+
         match self {
-            Self::Const(r) => r.evaluate(x),
-            Self::Poly(p) => p.evaluate(x),
-            Self::Exp(e) => e.evaluate(x),
-            Self::Trig(t) => t.evaluate(x),
+            Self::Func(f) => Self::Func(f.as_const().map_or(*f, BaseFuncs::Const)),
+            Self::Add(f1, f2) => fold(f1, f2, Rational::checked_add, Self::Add),
+            Self::Sub(f1, f2) => fold(f1, f2, Rational::checked_sub, Self::Sub),
+            Self::Mul(f1, f2) => fold(f1, f2, Rational::checked_mul, Self::Mul),
+            Self::Div(f1, f2) => fold(
+                f1,
+                f2,
+                |r1, r2| (r2 != ZERO).then(|| r1.checked_mul(r2.reciprocal()))?,
+                Self::Div,
+            ),
+            Self::Comp(f1, f2) => {
+                let (f1, f2) = (f1.partial_eval(), f2.partial_eval());
+                match f1.as_const() {
+                    Some(r) => Self::Func(BaseFuncs::Const(r)),
+                    None => Self::Comp(Box::new(f1), Box::new(f2)),
+                }
+            }
+            Self::Pow(f1, f2) => fold(
+                f1,
+                f2,
+                |r1, r2| match r2.as_integer().and_then(|n| i32::try_from(n).ok()) {
+                    Some(n) if n >= 0 || r1 != ZERO => r1.checked_pow(n),
+                    _ => None,
+                },
+                Self::Pow,
+            ),
+            Self::Ln(f) => {
+                let f = f.partial_eval();
+                match f.as_const() {
+                    Some(ONE) => Self::Func(BaseFuncs::Const(ZERO)),
+                    _ => Self::Ln(Box::new(f)),
+                }
+            }
         }
     }
-}
 
-impl<F: Evaluate> Evaluate for ComplexFuncs<F> {
-    fn evaluate(&self, x: f64) -> f64 {
+    /// Serializes `self` into an S-expression.
+    ///
+    /// Rationals are written as in `Display`, basic functions as `(const r)`, `(poly-const r)`,
+    /// `(poly coeff power)`, `(exp)` for `e^x`, `(exp coeff base rate log_power)` for the other
+    /// exponentials where `base` is `e` or a rational, `(sin coeff)`, and `(cos coeff)`, and the
+    /// others as `(+ f g)`, `(- f g)`, `(* f g)`, `(/ f g)`, `(comp f g)`, `(^ f g)`, and `(ln f)`.
+    ///
+    /// For example, `sin(x^2) + 3` is `(+ (comp (sin 1) (poly 1 2)) (const 3))`.
+    pub fn to_sexpr(&self) -> String {
         match self {
-            Self::Func(f) => f.evaluate(x),
-            Self::Add(f1, f2) => f1.evaluate(x) + f2.evaluate(x),
-            Self::Sub(f1, f2) => f1.evaluate(x) - f2.evaluate(x),
-            Self::Mul(f1, f2) => f1.evaluate(x) * f2.evaluate(x),
-            Self::Div(f1, f2) => {
-                if f2.evaluate(x) == 0.0 {
-                    panic!("divide by zero");
-                } else {
-                    f1.evaluate(x) / f2.evaluate(x)
+            Self::Func(f) => match f {
+                BaseFuncs::Const(r) => format!("(const {r})"),
+                BaseFuncs::Poly(SingletonPolynomial::Const(r)) => format!("(poly-const {r})"),
+                BaseFuncs::Poly(SingletonPolynomial::Polynomial { coeff, power }) => {
+                    format!("(poly {coeff} {power})")
                 }
-            }
-            Self::Comp(f1, f2) => f1.evaluate(f2.evaluate(x)),
+                BaseFuncs::Exp(e) if *e == Exp::new() => "(exp)".to_string(),
+                BaseFuncs::Exp(Exp {
+                    coeff,
+                    base,
+                    rate,
+                    log_power,
+                }) => {
+                    let base = base.map_or("e".to_string(), |base| base.to_string());
+                    format!("(exp {coeff} {base} {rate} {log_power})")
+                }
+                BaseFuncs::Trig(Trignometric::Sine { coeff }) => format!("(sin {coeff})"),
+                BaseFuncs::Trig(Trignometric::Cosine { coeff }) => format!("(cos {coeff})"),
+            },
+            Self::Add(f1, f2) => format!("(+ {} {})", f1.to_sexpr(), f2.to_sexpr()),
+            Self::Sub(f1, f2) => format!("(- {} {})", f1.to_sexpr(), f2.to_sexpr()),
+            Self::Mul(f1, f2) => format!("(* {} {})", f1.to_sexpr(), f2.to_sexpr()),
+            Self::Div(f1, f2) => format!("(/ {} {})", f1.to_sexpr(), f2.to_sexpr()),
+            Self::Comp(f1, f2) => format!("(comp {} {})", f1.to_sexpr(), f2.to_sexpr()),
+            Self::Pow(f1, f2) => format!("(^ {} {})", f1.to_sexpr(), f2.to_sexpr()),
+            Self::Ln(f) => format!("(ln {})", f.to_sexpr()),
         }
     }
-}
 
-impl fmt::Display for Rational {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if *self == ZERO {
-            return write!(f, "0");
-        } else if self.denominator == 1 {
-            return write!(f, "{}", self.numerator);
+    /// Parses an S-expression generated by `to_sexpr`. Whitespaces between tokens are ignored.
+    pub fn from_sexpr(s: &str) -> Result<Self, SexprError> {
+        let spaced = s.replace('(', " ( ").replace(')', " ) ");
+        let mut tokens = spaced.split_whitespace();
+        let ret = Self::parse_sexpr(&mut tokens)?;
+        if tokens.next().is_some() {
+            return Err(SexprError::TrailingInput);
         }
-        write!(f, "{}/{}", self.numerator, self.denominator)
+        Ok(ret)
     }
-}
 
-impl fmt::Display for SingletonPolynomial {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::Const(r) => write!(f, "{r}"),
-            Self::Polynomial { coeff, power } => {
-                // coeff or power is zero
-                if *coeff == ZERO {
-                    return write!(f, "0");
-                } else if *power == ZERO {
-                    return write!(f, "{coeff}");
+    /// Parses a single S-expression from `tokens`.
+    fn parse_sexpr<'a, I: Iterator<Item = &'a str>>(tokens: &mut I) -> Result<Self, SexprError> {
+        let mut next = || tokens.next().ok_or(SexprError::UnexpectedEnd);
+        let rational = |token: &str| {
+            token.parse::<Rational>().map_err(|err| match err {
+                ParseRationalError::ZeroDenominator => {
+                    SexprError::ZeroDenominator(token.to_string())
                 }
+                _ => SexprError::UnexpectedToken(token.to_string()),
+            })
+        };
 
-                // Standard form of px^q
-                let coeff = if *coeff == ONE {
-                    "".to_string()
-                } else if *coeff == MINUS_ONE {
-                    "-".to_string()
-                } else {
-                    format!("({coeff})")
-                };
-                let var = if *power == ONE {
-                    "x".to_string()
-                } else {
-                    format!("x^({power})")
+        let token = next()?;
+        if token != "(" {
+            return Err(SexprError::UnexpectedToken(token.to_string()));
+        }
+
+        let head = next()?;
+        let ret = match head {
+            "const" => Self::Func(BaseFuncs::Const(rational(next()?)?)),
+            "poly-const" => Self::Func(BaseFuncs::Poly(SingletonPolynomial::new_c(rational(
+                next()?,
+            )?))),
+            "poly" => {
+                let coeff = rational(next()?)?;
+                let power = rational(next()?)?;
+                Self::Func(BaseFuncs::Poly(SingletonPolynomial::new_poly(coeff, power)))
+            }
+            "exp" => {
+                let token = next()?;
+                if token == ")" {
+                    return Ok(Self::Func(BaseFuncs::Exp(Exp::new())));
+                }
+                let coeff = rational(token)?;
+                let base = match next()? {
+                    "e" => None,
+                    token => Some(rational(token).and_then(|base| {
+                        if base > ZERO {
+                            Ok(base)
+                        } else {
+                            Err(SexprError::UnexpectedToken(token.to_string()))
+                        }
+                    })?),
                 };
-                write!(f, "{coeff}{var}")
+                let rate = rational(next()?)?;
+                let token = next()?;
+                let log_power = token
+                    .parse()
+                    .map_err(|_| SexprError::UnexpectedToken(token.to_string()))?;
+                Self::Func(BaseFuncs::Exp(Exp {
+                    coeff,
+                    base,
+                    rate,
+                    log_power,
+                }))
+            }
+            "sin" => Self::Func(BaseFuncs::Trig(Trignometric::new_sine(rational(next()?)?))),
+            "cos" => Self::Func(BaseFuncs::Trig(Trignometric::new_cosine(
+                rational(next()?)?,
+            ))),
+            "+" | "-" | "*" | "/" | "comp" | "^" => {
+                let f1 = Box::new(Self::parse_sexpr(tokens)?);
+                let f2 = Box::new(Self::parse_sexpr(tokens)?);
+                match head {
+                    "+" => Self::Add(f1, f2),
+                    "-" => Self::Sub(f1, f2),
+                    "*" => Self::Mul(f1, f2),
+                    "/" => Self::Div(f1, f2),
+                    "comp" => Self::Comp(f1, f2),
+                    _ => Self::Pow(f1, f2),
+                }
             }
+            "ln" => Self::Ln(Box::new(Self::parse_sexpr(tokens)?)),
+            _ => return Err(SexprError::UnexpectedToken(head.to_string())),
+        };
+
+        match tokens.next() {
+            Some(")") => Ok(ret),
+            Some(token) => Err(SexprError::UnexpectedToken(token.to_string())),
+            None => Err(SexprError::UnexpectedEnd),
+        }
+    }
+
+    /// Returns the value of `self` if it is a single constant function.
+    fn as_const(&self) -> Option<Rational> {
+        match self {
+            Self::Func(f) => f.as_const(),
+            _ => None,
         }
     }
 }
 
-impl fmt::Display for Exp {
+/// Builds a `ComplexFuncs<BaseFuncs>` from a mathematical expression.
+///
+/// For example, `func!(3 * x^2 + sin(x))` expands into the `Add` of `3 * x^(2)` and `sin(x)`.
+///
+/// Grammar:
+/// - `+` and `-` bind the weakest, then `*` and `/`, then `^`. All binary operators are left
+///   associative, and a leading `-` negates the first term.
+/// - If the exponent of `^` is a rational constant such as `2`, `(-1)`, or `(1/3)`, the base `x`
+///   becomes a `SingletonPolynomial`, and any other base is composed with one. If it is `x` and
+///   the base is an integer literal `a`, `a ^ x` becomes an exponential function. Any other
+///   exponent, which spans the rest of the term, becomes a `Pow`.
+/// - Atoms are `x`, integer literals, `sin(..)`, `cos(..)`, `exp(..)`, `ln(..)`, parenthesized
+///   expressions, and `{e}` for embedding an existing expression `e`.
+#[macro_export]
+macro_rules! func {
+    // Sums, whose current term is collected in `[$cur]`.
+    (@sum [] [] - $($rest:tt)+) => {
+        $crate::func!(@sum [$crate::func!(0), Sub] [] $($rest)+)
+    };
+    (@sum [$($lhs:tt)*] [$($cur:tt)+] + $($rest:tt)+) => {
+        $crate::func!(@sum [$crate::func!(@fold [$($lhs)*] $crate::func!(@prod [] [] $($cur)+)), Add] [] $($rest)+)
+    };
+    (@sum [$($lhs:tt)*] [$($cur:tt)+] - $($rest:tt)+) => {
+        $crate::func!(@sum [$crate::func!(@fold [$($lhs)*] $crate::func!(@prod [] [] $($cur)+)), Sub] [] $($rest)+)
+    };
+    (@sum [$($lhs:tt)*] [$($cur:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::func!(@sum [$($lhs)*] [$($cur)* $t] $($rest)*)
+    };
+    (@sum [$($lhs:tt)*] [$($cur:tt)+]) => {
+        $crate::func!(@fold [$($lhs)*] $crate::func!(@prod [] [] $($cur)+))
+    };
+
+    // Products, whose current factor is collected in `[$cur]`.
+    (@prod [$($lhs:tt)*] [$($cur:tt)+] * $($rest:tt)+) => {
+        $crate::func!(@prod [$crate::func!(@fold [$($lhs)*] $crate::func!(@pow [] $($cur)+)), Mul] [] $($rest)+)
+    };
+    (@prod [$($lhs:tt)*] [$($cur:tt)+] / $($rest:tt)+) => {
+        $crate::func!(@prod [$crate::func!(@fold [$($lhs)*] $crate::func!(@pow [] $($cur)+)), Div] [] $($rest)+)
+    };
+    (@prod [$($lhs:tt)*] [$($cur:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::func!(@prod [$($lhs)*] [$($cur)* $t] $($rest)*)
+    };
+    (@prod [$($lhs:tt)*] [$($cur:tt)+]) => {
+        $crate::func!(@fold [$($lhs)*] $crate::func!(@pow [] $($cur)+))
+    };
+
+    // Powers, whose base is collected in `[$base]`.
+    (@pow [$a:literal] ^ x) => {
+        $crate::assignments::assignment06::symbolic_differentiation::ComplexFuncs::Func(
+            $crate::assignments::assignment06::symbolic_differentiation::BaseFuncs::Exp(
+                $crate::assignments::assignment06::symbolic_differentiation::Exp::new_base(
+                    $crate::func!(@rat $a),
+                    $crate::assignments::assignment06::symbolic_differentiation::ONE,
+                ),
+            ),
+        )
+    };
+    (@pow [$($base:tt)+] ^ $n:literal) => { $crate::func!(@powrat [$($base)+] $n) };
+    (@pow [$($base:tt)+] ^ ($n:literal)) => { $crate::func!(@powrat [$($base)+] $n) };
+    (@pow [$($base:tt)+] ^ (- $n:literal)) => { $crate::func!(@powrat [$($base)+] - $n) };
+    (@pow [$($base:tt)+] ^ ($p:literal / $q:literal)) => {
+        $crate::func!(@powrat [$($base)+] $p / $q)
+    };
+    (@pow [$($base:tt)+] ^ (- $p:literal / $q:literal)) => {
+        $crate::func!(@powrat [$($base)+] - $p / $q)
+    };
+    (@pow [$($base:tt)+] ^ $($exp:tt)+) => {
+        $crate::assignments::assignment06::symbolic_differentiation::ComplexFuncs::Pow(
+            Box::new($crate::func!(@atom $($base)+)),
+            Box::new($crate::func!($($exp)+)),
+        )
+    };
+    (@pow [$($base:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::func!(@pow [$($base)* $t] $($rest)*)
+    };
+    (@pow [$($base:tt)+]) => {
+        $crate::func!(@atom $($base)+)
+    };
+
+    // Powers with a rational exponent.
+    (@powrat [x] $($exp:tt)+) => {
+        $crate::assignments::assignment06::symbolic_differentiation::ComplexFuncs::Func(
+            $crate::assignments::assignment06::symbolic_differentiation::BaseFuncs::Poly(
+                $crate::assignments::assignment06::symbolic_differentiation::SingletonPolynomial::new_poly(
+                    $crate::assignments::assignment06::symbolic_differentiation::ONE,
+                    $crate::func!(@rat $($exp)+),
+                ),
+            ),
+        )
+    };
+    (@powrat [$($base:tt)+] $($exp:tt)+) => {
+        $crate::assignments::assignment06::symbolic_differentiation::ComplexFuncs::Comp(
+            Box::new($crate::func!(@powrat [x] $($exp)+)),
+            Box::new($crate::func!(@atom $($base)+)),
+        )
+    };
+
+    // Rational exponents.
+    (@rat ($($t:tt)+)) => { $crate::func!(@rat $($t)+) };
+    (@rat $n:literal) => {
+        $crate::assignments::assignment06::symbolic_differentiation::Rational::new($n, 1)
+    };
+    (@rat - $n:literal) => {
+        $crate::assignments::assignment06::symbolic_differentiation::Rational::new(-$n, 1)
+    };
+    (@rat $p:literal / $q:literal) => {
+        $crate::assignments::assignment06::symbolic_differentiation::Rational::new($p, $q)
+    };
+    (@rat - $p:literal / $q:literal) => {
+        $crate::assignments::assignment06::symbolic_differentiation::Rational::new(-$p, $q)
+    };
+
+    // Atoms.
+    (@atom x) => { $crate::func!(@powrat [x] 1) };
+    (@atom ln($($arg:tt)+)) => {
+        $crate::assignments::assignment06::symbolic_differentiation::ComplexFuncs::Ln(Box::new($crate::func!($($arg)+)))
+    };
+    (@atom $f:ident(x)) => { $crate::func!(@base $f) };
+    (@atom $f:ident($($arg:tt)+)) => {
+        $crate::assignments::assignment06::symbolic_differentiation::ComplexFuncs::Comp(
+            Box::new($crate::func!(@base $f)),
+            Box::new($crate::func!($($arg)+)),
+        )
+    };
+    (@atom ($($t:tt)+)) => { $crate::func!($($t)+) };
+    (@atom {$e:expr}) => { $e };
+    (@atom $n:literal) => {
+        $crate::assignments::assignment06::symbolic_differentiation::ComplexFuncs::Func(
+            $crate::assignments::assignment06::symbolic_differentiation::BaseFuncs::Const(
+                $crate::func!(@rat $n),
+            ),
+        )
+    };
+
+    // Basic functions of `x`.
+    (@base sin) => {
+        $crate::assignments::assignment06::symbolic_differentiation::ComplexFuncs::Func(
+            $crate::assignments::assignment06::symbolic_differentiation::BaseFuncs::Trig(
+                $crate::assignments::assignment06::symbolic_differentiation::Trignometric::new_sine(
+                    $crate::assignments::assignment06::symbolic_differentiation::ONE,
+                ),
+            ),
+        )
+    };
+    (@base cos) => {
+        $crate::assignments::assignment06::symbolic_differentiation::ComplexFuncs::Func(
+            $crate::assignments::assignment06::symbolic_differentiation::BaseFuncs::Trig(
+                $crate::assignments::assignment06::symbolic_differentiation::Trignometric::new_cosine(
+                    $crate::assignments::assignment06::symbolic_differentiation::ONE,
+                ),
+            ),
+        )
+    };
+    (@base exp) => {
+        $crate::assignments::assignment06::symbolic_differentiation::ComplexFuncs::Func(
+            $crate::assignments::assignment06::symbolic_differentiation::BaseFuncs::Exp(
+                $crate::assignments::assignment06::symbolic_differentiation::Exp::new(),
+            ),
+        )
+    };
+
+    // Applies the pending binary operator, if any.
+    (@fold [] $rhs:expr) => { $rhs };
+    (@fold [$lhs:expr, $op:ident] $rhs:expr) => {
+        $crate::assignments::assignment06::symbolic_differentiation::ComplexFuncs::$op(
+            Box::new($lhs),
+            Box::new($rhs),
+        )
+    };
+
+    ($($t:tt)+) => { $crate::func!(@sum [] [] $($t)+) };
+}
+
+/// Error type for evaluation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvalError {
+    /// Division by zero, including negative powers of zero.
+    DivisionByZero,
+    /// The argument is outside of the domain, e.g., a fractional power of a negative number.
+    Domain,
+    /// The result is too large to be represented as `f64`.
+    Overflow,
+    /// A variable of a multivariable function is not given a value.
+    UnboundVariable,
+}
+
+impl fmt::Display for EvalError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "exp(x)")
+        match self {
+            Self::DivisionByZero => write!(f, "divide by zero"),
+            Self::Domain => write!(f, "argument out of domain"),
+            Self::Overflow => write!(f, "overflow"),
+            Self::UnboundVariable => write!(f, "unbound variable"),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+/// Checks that an evaluation result is a finite number.
+pub(crate) fn check(value: f64) -> Result<f64, EvalError> {
+    if value.is_nan() {
+        Err(EvalError::Domain)
+    } else if value.is_infinite() {
+        Err(EvalError::Overflow)
+    } else {
+        Ok(value)
+    }
+}
+
+/// Number of points `Evaluate::evaluate_many` evaluates at once.
+pub const EVAL_CHUNK: usize = 1024;
+
+/// Combines two vectors of values pointwise, stopping at the first error.
+fn zip_with(
+    lhs: Vec<f64>,
+    rhs: Vec<f64>,
+    op: impl Fn(f64, f64) -> Result<f64, EvalError>,
+) -> Result<Vec<f64>, EvalError> {
+    lhs.into_iter().zip(rhs).map(|(l, r)| op(l, r)).collect()
+}
+
+/// Evaluate functions.
+pub trait Evaluate {
+    /// Evaluate `self` at `x`, reporting division by zero, domain errors, and overflows.
+    fn try_evaluate(&self, x: f64) -> Result<f64, EvalError>;
+
+    ///  Evaluate `self` at `x`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `try_evaluate` fails.
+    fn evaluate(&self, x: f64) -> f64 {
+        match self.try_evaluate(x) {
+            Ok(value) => value,
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    /// Evaluate `self` at every point of `xs`.
+    ///
+    /// Implementors may override this to walk `self` once for all points instead of once per
+    /// point. If several points fail, the error reported need not be that of the first one.
+    fn try_evaluate_many(&self, xs: &[f64]) -> Result<Vec<f64>, EvalError> {
+        xs.iter().map(|x| self.try_evaluate(*x)).collect()
+    }
+
+    /// Evaluate `self` at every point of `xs`, in chunks of `EVAL_CHUNK` points.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `try_evaluate_many` fails on a chunk.
+    fn evaluate_many(&self, xs: &[f64]) -> Vec<f64> {
+        let mut values = Vec::with_capacity(xs.len());
+        for chunk in xs.chunks(EVAL_CHUNK) {
+            match self.try_evaluate_many(chunk) {
+                Ok(chunk) => values.extend(chunk),
+                Err(e) => panic!("{e}"),
+            }
+        }
+        values
+    }
+
+    /// Samples `self` at `n` evenly spaced points from `a` to `b`, both inclusive, and returns the
+    /// pairs of each point and its value. If `n` is 1, only `a` is sampled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `evaluate_many` panics.
+    fn sample(&self, a: f64, b: f64, n: usize) -> Vec<(f64, f64)> {
+        let h = if n > 1 { (b - a) / (n - 1) as f64 } else { 0.0 };
+        let xs = (0..n)
+            .map(|i| {
+                if i + 1 == n && n > 1 {
+                    b
+                } else {
+                    a + h * i as f64
+                }
+            })
+            .collect::<Vec<_>>();
+        let values = self.evaluate_many(&xs);
+        xs.into_iter().zip(values).collect()
+    }
+
+    /// Approximates the definite integral of `self` from `a` to `b` with the composite Simpson's
+    /// rule over `n` subintervals. Odd `n` is rounded up to the next even number.
+    ///
+    /// HINT: Consult <https://en.wikipedia.org/wiki/Simpson%27s_rule#Composite_Simpson's_1/3_rule>
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero, or if `evaluate` panics on a sample point.
+    fn integrate_numeric(&self, a: f64, b: f64, n: usize) -> f64 {
+        assert!(n > 0, "number of subintervals must be positive");
+        let n = n + n % 2;
+        let h = (b - a) / n as f64;
+
+        let inner = (1..n)
+            .map(|i| {
+                let weight = if i % 2 == 1 { 4.0 } else { 2.0 };
+                weight * self.evaluate(a + h * i as f64)
+            })
+            .sum::<f64>();
+        (self.evaluate(a) + inner + self.evaluate(b)) * h / 3.0
+    }
+}
+
+impl Evaluate for Rational {
+    fn try_evaluate(&self, x: f64) -> Result<f64, EvalError> {
+        if *self == ZERO {
+            return Ok(0.0);
+        }
+        Ok(self.numerator as f64 / self.denominator as f64)
+    }
+}
+
+impl Evaluate for SingletonPolynomial {
+    fn try_evaluate(&self, x: f64) -> Result<f64, EvalError> {
+        match self {
+            Self::Const(r) => r.try_evaluate(x),
+            Self::Polynomial { coeff, power } => {
+                if x == 0.0 && *power < ZERO {
+                    return Err(EvalError::DivisionByZero);
+                }
+                check(coeff.try_evaluate(x)? * x.powf(power.try_evaluate(x)?))
+            }
+        }
+    }
+}
+
+impl Evaluate for Exp {
+    fn try_evaluate(&self, x: f64) -> Result<f64, EvalError> {
+        check(self.scale() * (self.rate.evaluate(x) * self.ln_base() * x).exp())
+    }
+}
+
+impl Evaluate for Trignometric {
+    fn try_evaluate(&self, x: f64) -> Result<f64, EvalError> {
+        match self {
+            Self::Sine { coeff } => check(coeff.try_evaluate(x)? * x.sin()),
+            Self::Cosine { coeff } => check(coeff.try_evaluate(x)? * x.cos()),
+        }
+    }
+}
+
+impl Evaluate for BaseFuncs {
+    fn try_evaluate(&self, x: f64) -> Result<f64, EvalError> {
+        match self {
+            Self::Const(r) => r.try_evaluate(x),
+            Self::Poly(p) => p.try_evaluate(x),
+            Self::Exp(e) => e.try_evaluate(x),
+            Self::Trig(t) => t.try_evaluate(x),
+        }
+    }
+}
+
+impl<F: Evaluate> Evaluate for ComplexFuncs<F> {
+    fn try_evaluate(&self, x: f64) -> Result<f64, EvalError> {
+        match self {
+            Self::Func(f) => f.try_evaluate(x),
+            Self::Add(f1, f2) => check(f1.try_evaluate(x)? + f2.try_evaluate(x)?),
+            Self::Sub(f1, f2) => check(f1.try_evaluate(x)? - f2.try_evaluate(x)?),
+            Self::Mul(f1, f2) => check(f1.try_evaluate(x)? * f2.try_evaluate(x)?),
+            Self::Div(f1, f2) => {
+                let denominator = f2.try_evaluate(x)?;
+                if denominator == 0.0 {
+                    Err(EvalError::DivisionByZero)
+                } else {
+                    check(f1.try_evaluate(x)? / denominator)
+                }
+            }
+            Self::Comp(f1, f2) => f1.try_evaluate(f2.try_evaluate(x)?),
+            Self::Pow(f1, f2) => {
+                let (base, exponent) = (f1.try_evaluate(x)?, f2.try_evaluate(x)?);
+                if base == 0.0 && exponent < 0.0 {
+                    Err(EvalError::DivisionByZero)
+                } else {
+                    check(base.powf(exponent))
+                }
+            }
+            Self::Ln(f) => {
+                let value = f.try_evaluate(x)?;
+                if value <= 0.0 {
+                    Err(EvalError::Domain)
+                } else {
+                    check(value.ln())
+                }
+            }
+        }
+    }
+    fn try_evaluate_many(&self, xs: &[f64]) -> Result<Vec<f64>, EvalError> {
+        let many = |f: &Self| f.try_evaluate_many(xs);
+        match self {
+            Self::Func(f) => f.try_evaluate_many(xs),
+            Self::Add(f1, f2) => zip_with(many(f1)?, many(f2)?, |l, r| check(l + r)),
+            Self::Sub(f1, f2) => zip_with(many(f1)?, many(f2)?, |l, r| check(l - r)),
+            Self::Mul(f1, f2) => zip_with(many(f1)?, many(f2)?, |l, r| check(l * r)),
+            Self::Div(f1, f2) => zip_with(many(f1)?, many(f2)?, |l, r| {
+                if r == 0.0 {
+                    Err(EvalError::DivisionByZero)
+                } else {
+                    check(l / r)
+                }
+            }),
+            Self::Comp(f1, f2) => f1.try_evaluate_many(&many(f2)?),
+            Self::Pow(f1, f2) => zip_with(many(f1)?, many(f2)?, |base, exponent| {
+                if base == 0.0 && exponent < 0.0 {
+                    Err(EvalError::DivisionByZero)
+                } else {
+                    check(base.powf(exponent))
+                }
+            }),
+            Self::Ln(f) => many(f)?
+                .into_iter()
+                .map(|value| {
+                    if value <= 0.0 {
+                        Err(EvalError::Domain)
+                    } else {
+                        check(value.ln())
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Dual number `a + bε` where `ε^2 = 0`.
+///
+/// Evaluating a function `f` at `x + ε` results in `f(x) + f'(x)ε`, so the first component is the
+/// value and the second component is the derivative.
+///
+/// HINT: Consult <https://en.wikipedia.org/wiki/Automatic_differentiation#Automatic_differentiation_using_dual_numbers>
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dual(pub f64, pub f64);
+
+impl Dual {
+    /// Creates the dual number for the variable `x`, whose derivative is one.
+    pub fn var(x: f64) -> Self {
+        Self(x, 1.0)
+    }
+
+    /// Creates the dual number for the constant `c`, whose derivative is zero.
+    pub fn constant(c: f64) -> Self {
+        Self(c, 0.0)
+    }
+
+    /// Sine.
+    pub fn sin(self) -> Self {
+        Self(self.0.sin(), self.0.cos() * self.1)
+    }
+
+    /// Cosine.
+    pub fn cos(self) -> Self {
+        Self(self.0.cos(), -self.0.sin() * self.1)
+    }
+
+    /// Exponential.
+    pub fn exp(self) -> Self {
+        let exp = self.0.exp();
+        Self(exp, exp * self.1)
+    }
+
+    /// Raises `self` to a constant power.
+    pub fn powf(self, n: f64) -> Self {
+        Self(self.0.powf(n), n * self.0.powf(n - 1.0) * self.1)
+    }
+
+    /// Natural logarithm.
+    pub fn ln(self) -> Self {
+        Self(self.0.ln(), self.1 / self.0)
+    }
+
+    /// Raises `self` to the power of `rhs`.
+    pub fn pow(self, rhs: Self) -> Self {
+        let value = self.0.powf(rhs.0);
+        Self(
+            value,
+            value * (rhs.1 * self.0.ln() + rhs.0 * self.1 / self.0),
+        )
+    }
+}
+
+impl Add for Dual {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+
+impl Sub for Dual {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0, self.1 - rhs.1)
+    }
+}
+
+impl Mul for Dual {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self(self.0 * rhs.0, self.1 * rhs.0 + self.0 * rhs.1)
+    }
+}
+
+impl Div for Dual {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        Self(
+            self.0 / rhs.0,
+            (self.1 * rhs.0 - self.0 * rhs.1) / (rhs.0 * rhs.0),
+        )
+    }
+}
+
+/// Evaluate functions over dual numbers.
+pub trait EvaluateDual {
+    /// Evaluate `self` at the dual number `x`.
+    ///
+    /// With `x = Dual::var(v)`, the result is `Dual(f(v), f'(v))`.
+    fn evaluate_dual(&self, x: Dual) -> Dual;
+}
+
+impl EvaluateDual for Rational {
+    fn evaluate_dual(&self, x: Dual) -> Dual {
+        Dual::constant(self.evaluate(x.0))
+    }
+}
+
+impl EvaluateDual for SingletonPolynomial {
+    fn evaluate_dual(&self, x: Dual) -> Dual {
+        match self {
+            Self::Const(r) => r.evaluate_dual(x),
+            Self::Polynomial { coeff, power } => {
+                coeff.evaluate_dual(x) * x.powf(power.evaluate(x.0))
+            }
+        }
+    }
+}
+
+impl EvaluateDual for Exp {
+    fn evaluate_dual(&self, x: Dual) -> Dual {
+        let rate = Dual::constant(self.rate.evaluate(x.0) * self.ln_base());
+        Dual::constant(self.scale()) * (rate * x).exp()
+    }
+}
+
+impl EvaluateDual for Trignometric {
+    fn evaluate_dual(&self, x: Dual) -> Dual {
+        match self {
+            Self::Sine { coeff } => coeff.evaluate_dual(x) * x.sin(),
+            Self::Cosine { coeff } => coeff.evaluate_dual(x) * x.cos(),
+        }
+    }
+}
+
+impl EvaluateDual for BaseFuncs {
+    fn evaluate_dual(&self, x: Dual) -> Dual {
+        match self {
+            Self::Const(r) => r.evaluate_dual(x),
+            Self::Poly(p) => p.evaluate_dual(x),
+            Self::Exp(e) => e.evaluate_dual(x),
+            Self::Trig(t) => t.evaluate_dual(x),
+        }
+    }
+}
+
+impl<F: EvaluateDual> EvaluateDual for ComplexFuncs<F> {
+    fn evaluate_dual(&self, x: Dual) -> Dual {
+        match self {
+            Self::Func(f) => f.evaluate_dual(x),
+            Self::Add(f1, f2) => f1.evaluate_dual(x) + f2.evaluate_dual(x),
+            Self::Sub(f1, f2) => f1.evaluate_dual(x) - f2.evaluate_dual(x),
+            Self::Mul(f1, f2) => f1.evaluate_dual(x) * f2.evaluate_dual(x),
+            Self::Div(f1, f2) => f1.evaluate_dual(x) / f2.evaluate_dual(x),
+            Self::Comp(f1, f2) => f1.evaluate_dual(f2.evaluate_dual(x)),
+            Self::Pow(f1, f2) => f1.evaluate_dual(x).pow(f2.evaluate_dual(x)),
+            Self::Ln(f) => f.evaluate_dual(x).ln(),
+        }
+    }
+}
+
+/// Closed interval `[lo, hi]` of real numbers.
+///
+/// Evaluating a function over an interval results in an interval that contains every value of the
+/// function over the given interval, up to floating-point rounding. The bounds are not necessarily
+/// tight.
+///
+/// HINT: Consult <https://en.wikipedia.org/wiki/Interval_arithmetic>
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval(pub f64, pub f64);
+
+impl Interval {
+    /// Creates the interval `[x, x]`.
+    pub fn point(x: f64) -> Self {
+        Self(x, x)
+    }
+
+    /// Checks if `x` is in the interval.
+    pub fn contains(&self, x: f64) -> bool {
+        self.0 <= x && x <= self.1
+    }
+
+    /// Creates the smallest interval containing both `a` and `b`.
+    fn hull(a: f64, b: f64) -> Self {
+        Self(a.min(b), a.max(b))
+    }
+
+    /// Applies a `2π`-periodic function `f` that attains its maximum 1 at `peak` and its minimum
+    /// -1 at `trough`, and is monotone in between.
+    fn periodic(self, f: fn(f64) -> f64, peak: f64, trough: f64) -> Self {
+        use std::f64::consts::TAU;
+
+        if self.1 - self.0 >= TAU {
+            return Self(-1.0, 1.0);
+        }
+
+        let mut ret = Self::hull(f(self.0), f(self.1));
+        // The first maximum and minimum after `self.0`.
+        if peak + ((self.0 - peak) / TAU).ceil() * TAU <= self.1 {
+            ret.1 = 1.0;
+        }
+        if trough + ((self.0 - trough) / TAU).ceil() * TAU <= self.1 {
+            ret.0 = -1.0;
+        }
+        ret
+    }
+
+    /// Sine.
+    pub fn sin(self) -> Self {
+        use std::f64::consts::FRAC_PI_2;
+
+        self.periodic(f64::sin, FRAC_PI_2, -FRAC_PI_2)
+    }
+
+    /// Cosine.
+    pub fn cos(self) -> Self {
+        use std::f64::consts::PI;
+
+        self.periodic(f64::cos, 0.0, PI)
+    }
+
+    /// Exponential.
+    pub fn exp(self) -> Self {
+        Self(self.0.exp(), self.1.exp())
+    }
+
+    /// Natural logarithm.
+    ///
+    /// Only defined for the positive part of `self`. If there is no such part, the result is
+    /// `Interval(NaN, NaN)`.
+    pub fn ln(self) -> Self {
+        if self.1 <= 0.0 {
+            Self(f64::NAN, f64::NAN)
+        } else {
+            Self(self.0.max(0.0).ln(), self.1.ln())
+        }
+    }
+
+    /// Raises `self` to the power of `rhs`.
+    ///
+    /// If `rhs` is a single point, this is the same as `powf`. Otherwise, `self^rhs` is computed as
+    /// `exp(rhs * ln(self))`, which is only defined for the positive part of `self`.
+    pub fn pow(self, rhs: Self) -> Self {
+        if rhs.0 == rhs.1 {
+            self.powf(rhs.0)
+        } else {
+            (rhs * self.ln()).exp()
+        }
+    }
+
+    /// Raises `self` to a constant power.
+    ///
+    /// Non-integer powers are only defined for the nonnegative part of `self`. If there is no such
+    /// part, the result is `Interval(NaN, NaN)`.
+    pub fn powf(self, n: f64) -> Self {
+        if n == 0.0 {
+            Self::point(1.0)
+        } else if n < 0.0 {
+            Self::point(1.0) / self.powf(-n)
+        } else if n.fract() != 0.0 {
+            if self.1 < 0.0 {
+                Self(f64::NAN, f64::NAN)
+            } else {
+                Self(self.0.max(0.0).powf(n), self.1.powf(n))
+            }
+        } else if n % 2.0 == 0.0 && self.contains(0.0) {
+            Self(0.0, self.0.powf(n).max(self.1.powf(n)))
+        } else {
+            Self::hull(self.0.powf(n), self.1.powf(n))
+        }
+    }
+}
+
+impl Add for Interval {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+
+impl Sub for Interval {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.1, self.1 - rhs.0)
+    }
+}
+
+impl Mul for Interval {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let products = [
+            self.0 * rhs.0,
+            self.0 * rhs.1,
+            self.1 * rhs.0,
+            self.1 * rhs.1,
+        ];
+        Self(
+            products.iter().copied().fold(f64::INFINITY, f64::min),
+            products.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        )
+    }
+}
+
+impl Div for Interval {
+    type Output = Self;
+
+    /// If `rhs` contains zero, the quotient is unbounded.
+    fn div(self, rhs: Self) -> Self::Output {
+        if rhs.contains(0.0) {
+            Self(f64::NEG_INFINITY, f64::INFINITY)
+        } else {
+            self * Self(1.0 / rhs.1, 1.0 / rhs.0)
+        }
+    }
+}
+
+/// Evaluate functions over intervals.
+pub trait EvaluateInterval {
+    /// Evaluate `self` over the interval `x`, giving bounds of `self` over `x`.
+    fn evaluate_interval(&self, x: Interval) -> Interval;
+}
+
+impl EvaluateInterval for Rational {
+    fn evaluate_interval(&self, x: Interval) -> Interval {
+        Interval::point(self.evaluate(x.0))
+    }
+}
+
+impl EvaluateInterval for SingletonPolynomial {
+    fn evaluate_interval(&self, x: Interval) -> Interval {
+        match self {
+            Self::Const(r) => r.evaluate_interval(x),
+            Self::Polynomial { coeff, power } => {
+                coeff.evaluate_interval(x) * x.powf(power.evaluate(x.0))
+            }
+        }
+    }
+}
+
+impl EvaluateInterval for Exp {
+    fn evaluate_interval(&self, x: Interval) -> Interval {
+        let rate = Interval::point(self.rate.evaluate(x.0) * self.ln_base());
+        Interval::point(self.scale()) * (rate * x).exp()
+    }
+}
+
+impl EvaluateInterval for Trignometric {
+    fn evaluate_interval(&self, x: Interval) -> Interval {
+        match self {
+            Self::Sine { coeff } => coeff.evaluate_interval(x) * x.sin(),
+            Self::Cosine { coeff } => coeff.evaluate_interval(x) * x.cos(),
+        }
+    }
+}
+
+impl EvaluateInterval for BaseFuncs {
+    fn evaluate_interval(&self, x: Interval) -> Interval {
+        match self {
+            Self::Const(r) => r.evaluate_interval(x),
+            Self::Poly(p) => p.evaluate_interval(x),
+            Self::Exp(e) => e.evaluate_interval(x),
+            Self::Trig(t) => t.evaluate_interval(x),
+        }
+    }
+}
+
+impl<F: EvaluateInterval> EvaluateInterval for ComplexFuncs<F> {
+    fn evaluate_interval(&self, x: Interval) -> Interval {
+        match self {
+            Self::Func(f) => f.evaluate_interval(x),
+            Self::Add(f1, f2) => f1.evaluate_interval(x) + f2.evaluate_interval(x),
+            Self::Sub(f1, f2) => f1.evaluate_interval(x) - f2.evaluate_interval(x),
+            Self::Mul(f1, f2) => f1.evaluate_interval(x) * f2.evaluate_interval(x),
+            Self::Div(f1, f2) => f1.evaluate_interval(x) / f2.evaluate_interval(x),
+            Self::Comp(f1, f2) => f1.evaluate_interval(f2.evaluate_interval(x)),
+            Self::Pow(f1, f2) => f1.evaluate_interval(x).pow(f2.evaluate_interval(x)),
+            Self::Ln(f) => f.evaluate_interval(x).ln(),
+        }
+    }
+}
+
+/// Taylor expansion.
+///
+/// Every function that can be both differentiated and evaluated can be expanded into a truncated
+/// Taylor series.
+pub trait Taylor: Differentiable + Evaluate {
+    /// Expands `self` into the Taylor polynomial of degree `order` around `center`.
+    ///
+    /// The `k`-th coefficient of `(x - center)^k` is `f^(k)(center) / k!`, where `f^(k)` is
+    /// obtained by differentiating `self` `k` times.
+    ///
+    /// HINT: Consult <https://en.wikipedia.org/wiki/Taylor_series>
+    fn taylor(&self, center: f64, order: usize) -> Polynomial<f64>
+    where
+        Self: Sized,
+    {
+        // `x - center`
+        let shifted = Polynomial::x().add(&Polynomial::from(-center));
+
+        let mut ret = Polynomial::zero();
+        let mut func = self.clone();
+        let mut factorial = 1.0;
+        let mut power = Polynomial::one();
+        for k in 0..=order {
+            if k > 0 {
+                func = func.diff();
+                factorial *= k as f64;
+                power = power.mul(&shifted);
+            }
+            let coeff = func.evaluate(center) / factorial;
+            ret = ret.add(&power.mul(&Polynomial::from(coeff)));
+        }
+        ret
+    }
+}
+
+impl<T: Differentiable + Evaluate> Taylor for T {}
+
+/// Error type for root finding.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RootError {
+    /// The derivative vanished at the contained point, so the next step is undefined.
+    ZeroDerivative(f64),
+    /// The iteration did not converge within the iteration limit. Contains the last estimate.
+    NotConverged(f64),
+    /// The function or its derivative could not be evaluated at an intermediate estimate.
+    Eval(EvalError),
+}
+
+impl From<EvalError> for RootError {
+    fn from(e: EvalError) -> Self {
+        Self::Eval(e)
+    }
+}
+
+/// Root finding with Newton's method.
+///
+/// Every function that can be both differentiated and evaluated can use its symbolic derivative
+/// for the update step.
+pub trait FindRoot: Differentiable + Evaluate {
+    /// Finds a root of `self` starting from `x0`.
+    ///
+    /// The iteration stops successfully when either `|f(x)|` or the size of the update step drops
+    /// below `tol`. It fails if the derivative becomes zero, if `max_iter` steps are not enough, or
+    /// if an intermediate estimate cannot be evaluated.
+    ///
+    /// HINT: Consult <https://en.wikipedia.org/wiki/Newton%27s_method>
+    fn find_root(&self, x0: f64, tol: f64, max_iter: usize) -> Result<f64, RootError>
+    where
+        Self: Sized,
+    {
+        let deriv = self.diff();
+        let mut x = x0;
+        for _ in 0..max_iter {
+            let fx = self.try_evaluate(x)?;
+            if fx.abs() < tol {
+                return Ok(x);
+            }
+
+            let dfx = deriv.try_evaluate(x)?;
+            if dfx == 0.0 {
+                return Err(RootError::ZeroDerivative(x));
+            }
+
+            let next = x - fx / dfx;
+            if (next - x).abs() < tol {
+                return Ok(next);
+            }
+            x = next;
+        }
+        Err(RootError::NotConverged(x))
+    }
+}
+
+impl<T: Differentiable + Evaluate> FindRoot for T {}
+
+/// Returns the curvature of the graph of `f` at `x`, i.e., `|f''(x)| / (1 + f'(x)^2)^(3/2)`.
+///
+/// HINT: Consult <https://en.wikipedia.org/wiki/Curvature#Graph_of_a_function>
+///
+/// # Panics
+///
+/// Panics if `f'` or `f''` cannot be evaluated at `x`.
+pub fn curvature_at<F: Differentiable + Evaluate>(f: &F, x: f64) -> f64 {
+    let d1 = f.diff();
+    let slope = d1.evaluate(x);
+    d1.diff().evaluate(x).abs() / (1.0 + slope * slope).powf(1.5)
+}
+
+/// Integrand `sqrt(1 + f'(x)^2)` of the arc length of `f`.
+#[derive(Debug)]
+struct ArcLengthIntegrand<F>(F);
+
+impl<F: Evaluate> Evaluate for ArcLengthIntegrand<F> {
+    fn try_evaluate(&self, x: f64) -> Result<f64, EvalError> {
+        let slope = self.0.try_evaluate(x)?;
+        check((1.0 + slope * slope).sqrt())
+    }
+}
+
+/// Approximates the arc length of the graph of `f` from `a` to `b`, integrating
+/// `sqrt(1 + f'(x)^2)` with `integrate_numeric` over `n` subintervals.
+///
+/// HINT: Consult <https://en.wikipedia.org/wiki/Arc_length#Finding_arc_lengths_by_integration>
+///
+/// # Panics
+///
+/// Panics if `n` is zero, or if `f'` cannot be evaluated on a sample point.
+pub fn arc_length<F: Differentiable + Evaluate>(f: &F, a: f64, b: f64, n: usize) -> f64 {
+    ArcLengthIntegrand(f.diff()).integrate_numeric(a, b, n)
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if *self == ZERO {
+            return write!(f, "0");
+        } else if self.denominator == 1 {
+            return write!(f, "{}", self.numerator);
+        }
+        write!(f, "{}/{}", self.numerator, self.denominator)
+    }
+}
+
+impl fmt::Display for SingletonPolynomial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Const(r) => write!(f, "{r}"),
+            Self::Polynomial { coeff, power } => {
+                // coeff or power is zero
+                if *coeff == ZERO {
+                    return write!(f, "0");
+                } else if *power == ZERO {
+                    return write!(f, "{coeff}");
+                }
+
+                // Standard form of px^q
+                let coeff = if *coeff == ONE {
+                    "".to_string()
+                } else if *coeff == MINUS_ONE {
+                    "-".to_string()
+                } else {
+                    format!("({coeff})")
+                };
+                let var = if *power == ONE {
+                    "x".to_string()
+                } else {
+                    format!("x^({power})")
+                };
+                write!(f, "{coeff}{var}")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Exp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.coeff == ZERO {
+            return write!(f, "0");
+        } else if self.coeff == MINUS_ONE {
+            write!(f, "-")?;
+        } else if self.coeff != ONE {
+            write!(f, "({})", self.coeff)?;
+        }
+
+        let exponent = if self.rate == ONE {
+            "x".to_string()
+        } else {
+            format!("({})x", self.rate)
+        };
+        match self.base {
+            None => write!(f, "exp({exponent})"),
+            Some(base) => {
+                match self.log_power {
+                    0 => {}
+                    1 => write!(f, "ln({base})")?,
+                    m => write!(f, "ln({base})^({m})")?,
+                }
+                if base.denominator == 1 {
+                    write!(f, "{base}^({exponent})")
+                } else {
+                    write!(f, "({base})^({exponent})")
+                }
+            }
+        }
     }
 }
 
@@ -474,6 +1936,8 @@ impl<F: Differentiable + fmt::Display> fmt::Display for ComplexFuncs<F> {
             ComplexFuncs::Mul(l, r) => write!(f, "({l} * {r})"),
             ComplexFuncs::Div(l, r) => write!(f, "({l} / {r})"),
             ComplexFuncs::Comp(l, r) => write!(f, "({l} ∘ {r})"),
+            ComplexFuncs::Pow(l, r) => write!(f, "({l} ^ {r})"),
+            ComplexFuncs::Ln(func) => write!(f, "ln({func})"),
         }
     }
 }
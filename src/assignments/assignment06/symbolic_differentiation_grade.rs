@@ -3,6 +3,7 @@ mod test {
     use ntest::assert_about_eq;
 
     use crate::assignments::assignment06::symbolic_differentiation::*;
+    use crate::func;
 
     // Constant rationals to use
     const TWO: Rational = Rational::new(2, 1);
@@ -91,6 +92,77 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_rational_normalize() {
+        assert_eq!(Rational::new(2, -4), Rational::new(-1, 2));
+        assert_eq!(Rational::new(-6, -9), Rational::new(2, 3));
+        assert_eq!(Rational::new(0, 5), ZERO);
+        assert_eq!(format!("{}", Rational::new(12, -4)), "-3".to_string());
+        assert_eq!(Rational::new(1, 3) - Rational::new(1, 3), ZERO);
+        assert_eq!(Rational::new(1, 3) * ZERO, ZERO);
+    }
+
+    #[test]
+    fn test_rational_numeric() {
+        assert_eq!(-Rational::new(2, 3), Rational::new(-2, 3));
+        assert_eq!(-ZERO, ZERO);
+        assert_eq!(Rational::new(-2, 3).abs(), Rational::new(2, 3));
+        assert_eq!(Rational::new(-2, 3).reciprocal(), Rational::new(-3, 2));
+        assert_eq!(Rational::from(-7), Rational::new(-7, 1));
+
+        assert_eq!(Rational::new(2, 3).pow(3), Rational::new(8, 27));
+        assert_eq!(Rational::new(2, 3).pow(-2), Rational::new(9, 4));
+        assert_eq!(Rational::new(2, 3).pow(0), ONE);
+        assert_eq!(ZERO.pow(2), ZERO);
+        assert_eq!(MINUS_ONE.pow(i32::MAX), MINUS_ONE);
+        assert_eq!(Rational::new(-1, 2).pow(-61), Rational::new(-(1 << 61), 1));
+
+        assert!(Rational::new(1, 3) < Rational::new(1, 2));
+        assert!(Rational::new(-1, 2) < ZERO);
+        assert!(ZERO < Rational::new(1, 1000));
+        assert_eq!(
+            Rational::new(5, 3).max(Rational::new(7, 4)),
+            Rational::new(7, 4)
+        );
+
+        let mut rs = vec![FIVE_THIRD, ZERO, MINUS_ONE, THIRD, TWO_SEVENTH];
+        rs.sort();
+        assert_eq!(rs, vec![MINUS_ONE, ZERO, TWO_SEVENTH, THIRD, FIVE_THIRD]);
+    }
+
+    #[test]
+    fn test_rational_from_f64() {
+        use core::f64::consts::PI;
+
+        assert_eq!(Rational::from_f64(0.5, 100), Some(Rational::new(1, 2)));
+        assert_eq!(Rational::from_f64(-0.75, 10), Some(Rational::new(-3, 4)));
+        assert_eq!(Rational::from_f64(0.1, 1000), Some(Rational::new(1, 10)));
+        assert_eq!(Rational::from_f64(0.333333, 10), Some(THIRD));
+        assert_eq!(Rational::from_f64(0.0, 5), Some(ZERO));
+        assert_eq!(Rational::from_f64(42.0, 7), Some(Rational::new(42, 1)));
+        assert_eq!(Rational::from_f64(0.7, 1), Some(ONE));
+
+        assert_eq!(Rational::from_f64(PI, 10), Some(Rational::new(22, 7)));
+        assert_eq!(Rational::from_f64(PI, 100), Some(Rational::new(311, 99)));
+        assert_eq!(
+            Rational::from_f64(1e15, 1_000_000),
+            Some(Rational::new(1_000_000_000_000_000, 1))
+        );
+        assert_eq!(
+            Rational::from_f64(1e15 + 0.5, 1_000_000),
+            Some(Rational::new(2_000_000_000_000_001, 2))
+        );
+        assert_eq!(Rational::from_f64(PI, 1000), Some(Rational::new(355, 113)));
+        assert_eq!(
+            Rational::from_f64(-PI, 100000),
+            Some(Rational::new(-312689, 99532))
+        );
+
+        assert_eq!(Rational::from_f64(f64::NAN, 10), None);
+        assert_eq!(Rational::from_f64(f64::INFINITY, 10), None);
+        assert_eq!(Rational::from_f64(1e300, 10), None);
+    }
+
     #[test]
     fn test_differentiate_simple() {
         // Constant
@@ -214,4 +286,548 @@ mod test {
         assert_about_eq!(deriv.evaluate(2.714), -4.79392977);
         assert_about_eq!(deriv.evaluate(3.9), -3.72556973);
     }
+
+    #[test]
+    fn test_taylor() {
+        // exp(x) around 0
+        let poly = func!(exp(x)).taylor(0.0, 8);
+        assert_about_eq!(poly.eval(0.5), 0.5f64.exp());
+        assert_about_eq!(poly.eval(-0.3), (-0.3f64).exp());
+
+        // sin(x) around pi/2
+        let poly = func!(sin(x)).taylor(core::f64::consts::FRAC_PI_2, 10);
+        assert_about_eq!(poly.eval(1.0), 1.0f64.sin());
+        assert_about_eq!(poly.eval(2.0), 2.0f64.sin());
+
+        // 2x^4 is its own Taylor polynomial once the order reaches its degree.
+        let f = func!(2 * x ^ 4);
+        let poly = f.taylor(1.0, 6);
+        assert_about_eq!(poly.eval(3.0), 162.0);
+        assert_about_eq!(poly.eval(-1.5), 10.125);
+
+        // A first-order expansion is the tangent line.
+        let poly = f.taylor(1.0, 1);
+        assert_about_eq!(poly.eval(1.0), 2.0);
+        assert_about_eq!(poly.eval(2.0), 10.0);
+    }
+
+    #[test]
+    fn test_find_root() {
+        let f = func!(x ^ 2 - 2);
+        assert_about_eq!(f.find_root(1.0, 1e-12, 50).unwrap(), 2f64.sqrt());
+        assert_about_eq!(f.find_root(-3.0, 1e-12, 50).unwrap(), -(2f64.sqrt()));
+
+        let f = func!(cos(x));
+        assert_about_eq!(
+            f.find_root(1.0, 1e-12, 50).unwrap(),
+            core::f64::consts::FRAC_PI_2
+        );
+
+        // x^2 + 2 has no real root.
+        let f = func!(x ^ 2 + 2);
+        assert_eq!(
+            f.find_root(0.0, 1e-12, 50),
+            Err(RootError::ZeroDerivative(0.0))
+        );
+        assert!(matches!(
+            f.find_root(1.0, 1e-12, 50),
+            Err(RootError::NotConverged(_))
+        ));
+
+        // sqrt(x) + 1 is not defined for negative numbers.
+        let f = func!(x ^ (1 / 2) + 1);
+        assert_eq!(
+            f.find_root(-1.0, 1e-12, 50),
+            Err(RootError::Eval(EvalError::Domain))
+        );
+    }
+
+    #[test]
+    fn test_substitute() {
+        // sin(x) + x, with x := x^2
+        let h = func!(sin(x) + x).substitute(&func!(x ^ 2));
+        assert_about_eq!(h.evaluate(1.3), 1.69f64.sin() + 1.69);
+
+        // d/dx (sin(x^2) + x^2) = 2x * cos(x^2) + 2x
+        assert_about_eq!(h.diff().evaluate(1.3), 2.6 * 1.69f64.cos() + 2.6);
+
+        // cos(exp(x)), with x := 2x^4
+        let h = func!(cos(exp(x))).substitute(&func!(2 * x ^ 4));
+        assert_about_eq!(h.evaluate(0.8), (0.8192f64.exp()).cos());
+    }
+
+    #[test]
+    fn test_partial_eval() {
+        type BF = BaseFuncs;
+        type CF = ComplexFuncs<BF>;
+
+        let c = |r| Box::new(CF::Func(BF::Const(r)));
+        let exp = || Box::new(CF::Func(BF::Exp(Exp::new())));
+
+        // 1/3 + 2 * 4 = 25/3
+        let f = CF::Add(c(THIRD), Box::new(CF::Mul(c(TWO), c(FOUR))));
+        assert_eq!(f.partial_eval(), *c(Rational::new(25, 3)));
+
+        // (2 * 4) * exp(x) - 0sin(x)
+        let f = CF::Sub(
+            Box::new(CF::Mul(Box::new(CF::Mul(c(TWO), c(FOUR))), exp())),
+            Box::new(CF::Func(BF::Trig(Trignometric::new_sine(ZERO)))),
+        );
+        assert_eq!(
+            f.partial_eval(),
+            CF::Sub(Box::new(CF::Mul(c(Rational::new(8, 1)), exp())), c(ZERO))
+        );
+
+        // Constants composed with anything are constant.
+        let f = CF::Comp(c(FIVE_THIRD), exp());
+        assert_eq!(f.partial_eval(), *c(FIVE_THIRD));
+
+        // Division by a constant zero is kept.
+        let f = CF::Div(c(TWO), c(ZERO));
+        assert_eq!(f.partial_eval(), f);
+    }
+
+    #[test]
+    fn test_func_macro() {
+        type BF = BaseFuncs;
+        type CF = ComplexFuncs<BF>;
+
+        let f = func!(3 * x ^ 2 + sin(x));
+        assert_eq!(
+            f,
+            CF::Add(
+                Box::new(CF::Mul(
+                    Box::new(CF::Func(BF::Const(Rational::new(3, 1)))),
+                    Box::new(CF::Func(BF::Poly(SingletonPolynomial::new_poly(ONE, TWO)))),
+                )),
+                Box::new(CF::Func(BF::Trig(Trignometric::new_sine(ONE)))),
+            )
+        );
+        assert_eq!(format!("{f}"), "((3 * x^(2)) + sin(x))".to_string());
+
+        // Left associativity and precedence.
+        assert_about_eq!(func!(1 - x - x).evaluate(2.0), -3.0);
+        assert_about_eq!(func!(8 / x / 2).evaluate(2.0), 2.0);
+        assert_about_eq!(func!(1 + 2 * x ^ 3 * 2).evaluate(2.0), 33.0);
+        assert_about_eq!(func!(-x ^ 2 + 1).evaluate(3.0), -8.0);
+        assert_about_eq!(func!((1 + x) * 2).evaluate(1.0), 4.0);
+
+        // Rational exponents and powers of functions.
+        assert_about_eq!(func!(x ^ (1 / 3)).evaluate(8.0), 2.0);
+        assert_about_eq!(func!(x ^ (-2)).evaluate(2.0), 0.25);
+        assert_about_eq!(func!(sin(x) ^ 2 + cos(x) ^ 2).evaluate(0.7), 1.0);
+
+        // Compositions and embedded expressions.
+        let g = func!(exp(x));
+        assert_eq!(
+            func!(cos(x ^ 2)),
+            CF::Comp(
+                Box::new(CF::Func(BF::Trig(Trignometric::new_cosine(ONE)))),
+                Box::new(CF::Func(BF::Poly(SingletonPolynomial::new_poly(ONE, TWO)))),
+            )
+        );
+        assert_about_eq!(func!({ g.clone() } * x).evaluate(1.0), 1f64.exp());
+    }
+
+    #[test]
+    fn test_try_evaluate() {
+        assert_about_eq!(
+            func!(x ^ 2 * exp(x)).try_evaluate(2.0).unwrap(),
+            4.0 * 2f64.exp()
+        );
+        assert_about_eq!(func!(x ^ (1 / 2)).try_evaluate(4.0).unwrap(), 2.0);
+        assert_about_eq!(func!(x ^ (-2)).try_evaluate(-2.0).unwrap(), 0.25);
+
+        assert_eq!(
+            func!(1 / x).try_evaluate(0.0),
+            Err(EvalError::DivisionByZero)
+        );
+        assert_eq!(
+            func!(x ^ (-1)).try_evaluate(0.0),
+            Err(EvalError::DivisionByZero)
+        );
+        assert_eq!(
+            func!(x ^ (1 / 2)).try_evaluate(-1.0),
+            Err(EvalError::Domain)
+        );
+        assert_eq!(
+            func!(exp(x ^ 2)).try_evaluate(100.0),
+            Err(EvalError::Overflow)
+        );
+        assert_eq!(
+            func!(2 * exp(x)).try_evaluate(709.5),
+            Err(EvalError::Overflow)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "divide by zero")]
+    fn test_evaluate_division_by_zero() {
+        let _unused = func!(1 / sin(x)).evaluate(0.0);
+    }
+
+    #[test]
+    fn test_evaluate_dual() {
+        let funcs = [
+            func!(2 * x ^ 4 + exp(x)),
+            func!((5 / 3) * cos(x) - sin(x)),
+            func!(2 * x ^ 4 * cos(x) * exp(x)),
+            func!(sin(x) / cos(x)),
+            func!(cos(x ^ 2)),
+            func!(exp(sin(x) ^ 2) / (x ^ (1 / 2) + 1)),
+        ];
+
+        // Dual numbers agree with the symbolic derivative.
+        for f in funcs {
+            let deriv = f.diff();
+            for x in [0.3, 1.0, 2.2, 3.4] {
+                let Dual(value, slope) = f.evaluate_dual(Dual::var(x));
+                assert_about_eq!(value, f.evaluate(x));
+                assert_about_eq!(slope, deriv.evaluate(x));
+            }
+        }
+
+        assert_eq!(func!(x ^ 3).evaluate_dual(Dual::var(2.0)), Dual(8.0, 12.0));
+        assert_eq!(
+            func!(x ^ 3).evaluate_dual(Dual::constant(2.0)),
+            Dual(8.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_evaluate_interval() {
+        use core::f64::consts::PI;
+
+        assert_eq!(
+            func!(x ^ 2).evaluate_interval(Interval(-1.0, 2.0)),
+            Interval(0.0, 4.0)
+        );
+        assert_eq!(
+            func!(x ^ 3).evaluate_interval(Interval(-1.0, 2.0)),
+            Interval(-1.0, 8.0)
+        );
+        assert_eq!(
+            func!(1 / x).evaluate_interval(Interval(-1.0, 2.0)),
+            Interval(f64::NEG_INFINITY, f64::INFINITY)
+        );
+        assert_eq!(
+            func!(x - x).evaluate_interval(Interval(0.0, 1.0)),
+            Interval(-1.0, 1.0)
+        );
+
+        let Interval(lo, hi) = func!(sin(x)).evaluate_interval(Interval(0.0, PI));
+        assert_about_eq!(lo, 0.0);
+        assert_about_eq!(hi, 1.0);
+        let Interval(lo, hi) = func!(cos(x)).evaluate_interval(Interval(0.1, 3.0));
+        assert_about_eq!(lo, 3f64.cos());
+        assert_about_eq!(hi, 0.1f64.cos());
+
+        // The bounds contain every sampled value.
+        let funcs = [
+            func!(2 * x ^ 4 * cos(x) * exp(x)),
+            func!(sin(x) / (x ^ 2 + 1)),
+            func!(cos(x ^ 2) - x ^ (1 / 2)),
+            func!(exp(sin(x) * 3)),
+        ];
+        for f in funcs {
+            for (lo, hi) in [(0.0, 0.5), (0.2, 3.0), (1.5, 7.0)] {
+                let bounds = f.evaluate_interval(Interval(lo, hi));
+                for i in 0..=100 {
+                    let x = lo + (hi - lo) * i as f64 / 100.0;
+                    assert!(
+                        bounds.contains(f.evaluate(x)),
+                        "{f} {x} {bounds:?} {}",
+                        f.evaluate(x)
+                    );
+                }
+            }
+        }
+
+        // x^3 + x is increasing since its derivative is positive everywhere on [-2, 2].
+        let deriv = func!(x ^ 3 + x).diff();
+        assert!(deriv.evaluate_interval(Interval(-2.0, 2.0)).0 > 0.0);
+    }
+
+    #[test]
+    fn test_sexpr() {
+        type BF = BaseFuncs;
+        type CF = ComplexFuncs<BF>;
+
+        let f = func!(sin(x ^ 2) + 3);
+        assert_eq!(
+            f.to_sexpr(),
+            "(+ (comp (sin 1) (poly 1 2)) (const 3))".to_string()
+        );
+
+        let funcs = [
+            func!(2 * x ^ 4 * cos(x) * exp(x)),
+            func!(x ^ (-2 / 3) / (sin(x) - 1) - x),
+            CF::Func(BF::Poly(SingletonPolynomial::new_c(Rational::new(-5, 3)))),
+            CF::Func(BF::Trig(Trignometric::new_cosine(TWO_SEVENTH))),
+            func!(1 - x).diff(),
+        ];
+        for f in funcs {
+            assert_eq!(CF::from_sexpr(&f.to_sexpr()), Ok(f));
+        }
+
+        assert_eq!(
+            CF::from_sexpr(" ( *(exp)\n(poly 5/3 -1/2 ) ) "),
+            Ok(CF::Mul(
+                Box::new(CF::Func(BF::Exp(Exp::new()))),
+                Box::new(CF::Func(BF::Poly(SingletonPolynomial::new_poly(
+                    FIVE_THIRD,
+                    Rational::new(-1, 2)
+                )))),
+            ))
+        );
+
+        assert_eq!(CF::from_sexpr("(+ (exp)"), Err(SexprError::UnexpectedEnd));
+        assert_eq!(
+            CF::from_sexpr("(exp) (exp)"),
+            Err(SexprError::TrailingInput)
+        );
+        assert_eq!(
+            CF::from_sexpr("(tan 1)"),
+            Err(SexprError::UnexpectedToken("tan".to_string()))
+        );
+        assert_eq!(
+            CF::from_sexpr("(sin x)"),
+            Err(SexprError::UnexpectedToken("x".to_string()))
+        );
+        assert_eq!(
+            CF::from_sexpr("(exp 1)"),
+            Err(SexprError::UnexpectedToken(")".to_string()))
+        );
+        assert_eq!(
+            CF::from_sexpr("(const 1/0)"),
+            Err(SexprError::ZeroDenominator("1/0".to_string()))
+        );
+        assert_eq!(
+            CF::from_sexpr("(poly 0/0 1)"),
+            Err(SexprError::ZeroDenominator("0/0".to_string()))
+        );
+        assert_eq!(
+            CF::from_sexpr("(const -9223372036854775808)"),
+            Err(SexprError::UnexpectedToken(
+                "-9223372036854775808".to_string()
+            ))
+        );
+        assert_eq!(
+            "1/0".parse::<Rational>(),
+            Err(ParseRationalError::ZeroDenominator)
+        );
+        assert_eq!("-2/4".parse::<Rational>(), Ok(Rational::new(-1, 2)));
+    }
+
+    #[test]
+    fn test_integrate_numeric() {
+        use core::f64::consts::PI;
+
+        assert_about_eq!(func!(sin(x)).integrate_numeric(0.0, PI, 100), 2.0);
+        assert_about_eq!(
+            func!(exp(x)).integrate_numeric(0.0, 1.0, 100),
+            1f64.exp() - 1.0
+        );
+        assert_about_eq!(func!(1 / x).integrate_numeric(1.0, 2.0, 101), 2f64.ln());
+
+        // Simpson's rule is exact for cubics.
+        assert_about_eq!(func!(x ^ 3 - 2 * x).integrate_numeric(-1.0, 2.0, 2), 0.75);
+        assert_about_eq!(func!(x ^ 2).integrate_numeric(1.0, 0.0, 1), -1.0 / 3.0);
+
+        // Fundamental theorem of calculus
+        let f = func!(x ^ 2 * sin(x) + exp(cos(x)));
+        assert_about_eq!(
+            f.diff().integrate_numeric(0.5, 2.0, 200),
+            f.evaluate(2.0) - f.evaluate(0.5)
+        );
+    }
+
+    #[test]
+    fn test_general_exp() {
+        type BF = BaseFuncs;
+        type CF = ComplexFuncs<BF>;
+
+        let exp3 = Exp::new_natural(Rational::new(3, 1));
+        let pow2 = Exp::new_base(TWO, ONE);
+        let pow_half = Exp::new_base(Rational::new(1, 2), Rational::new(-2, 3));
+
+        assert_eq!(format!("{exp3}"), "exp((3)x)".to_string());
+        assert_eq!(format!("{}", exp3.diff()), "(3)exp((3)x)".to_string());
+        assert_eq!(format!("{pow2}"), "2^(x)".to_string());
+        assert_eq!(format!("{}", pow2.diff()), "ln(2)2^(x)".to_string());
+        assert_eq!(
+            format!("{}", pow2.diff().diff()),
+            "ln(2)^(2)2^(x)".to_string()
+        );
+        assert_eq!(
+            format!("{}", pow_half.diff()),
+            "(-2/3)ln(1/2)(1/2)^((-2/3)x)".to_string()
+        );
+        assert_eq!(func!(2 ^ x), CF::Func(BF::Exp(pow2)));
+
+        assert_about_eq!(exp3.evaluate(0.5), 1.5f64.exp());
+        assert_about_eq!(exp3.diff().evaluate(0.5), 3.0 * 1.5f64.exp());
+        assert_about_eq!(pow2.evaluate(10.0), 1024.0);
+        assert_about_eq!(pow2.diff().evaluate(3.0), 8.0 * 2f64.ln());
+        assert_about_eq!(
+            pow_half.diff().diff().evaluate(1.5),
+            (2.0 / 3.0 * 2f64.ln()).powi(2) * 2f64.powf(1.0)
+        );
+
+        // Dual numbers and intervals agree with the symbolic derivative.
+        for e in [exp3, pow2, pow_half, pow_half.diff()] {
+            let Dual(value, slope) = e.evaluate_dual(Dual::var(0.7));
+            assert_about_eq!(value, e.evaluate(0.7));
+            assert_about_eq!(slope, e.diff().evaluate(0.7));
+
+            let bounds = e.evaluate_interval(Interval(-1.0, 2.0));
+            for i in 0..=30 {
+                assert!(bounds.contains(e.evaluate(-1.0 + 0.1 * i as f64)));
+            }
+        }
+
+        let f = CF::Func(BF::Exp(pow_half.diff()));
+        assert_eq!(CF::from_sexpr(&f.to_sexpr()), Ok(f));
+        assert_eq!(func!(exp(x)).to_sexpr(), "(exp)".to_string());
+        assert_eq!(CF::from_sexpr("(exp 1 e 3 0)"), Ok(CF::Func(BF::Exp(exp3))));
+        assert_eq!(
+            CF::from_sexpr("(exp 1 -2 3 0)"),
+            Err(SexprError::UnexpectedToken("-2".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_general_power() {
+        type BF = BaseFuncs;
+        type CF = ComplexFuncs<BF>;
+
+        // x^x, whose derivative is x^x * (ln(x) + 1).
+        let x_x = func!(x ^ x);
+        assert_eq!(x_x, CF::Pow(Box::new(func!(x)), Box::new(func!(x))));
+        for x in [0.5, 1.0, 2.0, 3.5] {
+            assert_about_eq!(x_x.evaluate(x), x.powf(x));
+            assert_about_eq!(x_x.diff().evaluate(x), x.powf(x) * (x.ln() + 1.0));
+        }
+
+        // Rational exponents still become polynomials.
+        assert_eq!(
+            func!(x ^ (1 / 2)),
+            CF::Func(BF::Poly(SingletonPolynomial::new_poly(
+                ONE,
+                Rational::new(1, 2)
+            )))
+        );
+
+        // sin(x)^(x^2 + 1) and ln(x^2 + 1).
+        let f = func!(sin(x) ^ (x ^ 2 + 1));
+        let g = func!(ln(x ^ 2 + 1));
+        assert_eq!(format!("{g}"), "ln((x^(2) + 1))".to_string());
+        assert_about_eq!(f.evaluate(1.0), 1f64.sin().powf(2.0));
+        assert_about_eq!(g.diff().evaluate(2.0), 4.0 / 5.0);
+        for func in [&f, &g] {
+            for x in [0.3, 1.0, 1.4] {
+                let Dual(value, slope) = func.evaluate_dual(Dual::var(x));
+                assert_about_eq!(value, func.evaluate(x));
+                assert_about_eq!(slope, func.diff().evaluate(x));
+
+                let bounds = func.evaluate_interval(Interval(0.2, 1.5));
+                assert!(bounds.contains(func.evaluate(x)));
+            }
+        }
+
+        // Domain errors and constant folding.
+        assert_eq!(g.try_evaluate(0.0), Ok(0.0));
+        assert_eq!(func!(ln(x)).try_evaluate(-1.0), Err(EvalError::Domain));
+        assert_eq!(
+            func!(x ^ (x - 2)).try_evaluate(0.0),
+            Err(EvalError::DivisionByZero)
+        );
+        assert_eq!(func!(2 ^ (1 + 2)).partial_eval(), func!(8));
+        assert_eq!(func!(ln(1)).partial_eval(), func!(0));
+
+        // Constants that do not fit in `Rational` are left unfolded.
+        let f = CF::from_sexpr("(^ (const 2) (const 100))").unwrap();
+        assert_eq!(f.partial_eval(), f);
+        let f = CF::from_sexpr("(* (const 9223372036854775807) (const 2))").unwrap();
+        assert_eq!(f.partial_eval(), f);
+        let f = CF::from_sexpr("(^ (const 1) (const 2000000000))").unwrap();
+        assert_eq!(f.partial_eval(), func!(1));
+
+        // S-expressions.
+        assert_eq!(CF::from_sexpr(&f.to_sexpr()), Ok(f));
+        assert_eq!(CF::from_sexpr(&g.to_sexpr()), Ok(g));
+    }
+
+    #[test]
+    fn test_evaluate_many() {
+        let f = func!(sin(x) * exp(x) / (x ^ 2 + 1) + ln(x ^ 2 + 1));
+        let xs = (0..3000)
+            .map(|i| -5.0 + i as f64 * 0.01)
+            .collect::<Vec<_>>();
+
+        let values = f.evaluate_many(&xs);
+        assert_eq!(values.len(), xs.len());
+        for (x, value) in xs.iter().zip(values) {
+            assert_about_eq!(value, f.evaluate(*x));
+        }
+
+        let samples = func!(x ^ 2).sample(-1.0, 1.0, 5);
+        assert_eq!(
+            samples,
+            vec![
+                (-1.0, 1.0),
+                (-0.5, 0.25),
+                (0.0, 0.0),
+                (0.5, 0.25),
+                (1.0, 1.0)
+            ]
+        );
+        assert_eq!(func!(x).sample(2.0, 3.0, 1), vec![(2.0, 2.0)]);
+        assert!(func!(x).sample(2.0, 3.0, 0).is_empty());
+
+        assert_eq!(
+            func!(1 / x).try_evaluate_many(&[1.0, 0.0]),
+            Err(EvalError::DivisionByZero)
+        );
+        assert_eq!(
+            func!(ln(x)).try_evaluate_many(&[1.0, -1.0]),
+            Err(EvalError::Domain)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "divide by zero")]
+    fn test_evaluate_many_division_by_zero() {
+        let _unused = func!(1 / x).evaluate_many(&[1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_curvature_arc_length() {
+        // A line has no curvature, and its arc length is the distance between the endpoints.
+        let line = func!(3 * x + 1);
+        assert_about_eq!(curvature_at(&line, 2.0), 0.0);
+        assert_about_eq!(arc_length(&line, 0.0, 4.0, 10), 4.0 * 10f64.sqrt());
+
+        // y = x^2 has curvature 2 / (1 + 4x^2)^(3/2).
+        let parabola = func!(x ^ 2);
+        assert_about_eq!(curvature_at(&parabola, 0.0), 2.0);
+        assert_about_eq!(curvature_at(&parabola, 1.0), 2.0 / 5f64.powf(1.5));
+        let expected = 5f64.sqrt() / 2.0 + 2f64.asinh() / 4.0;
+        assert_about_eq!(arc_length(&parabola, 0.0, 1.0, 100), expected, 1e-8);
+
+        // The upper unit semicircle sqrt(1 - x^2) has curvature 1, and its arc over [0, 1/2]
+        // has length π / 6.
+        let circle = func!((1 - x ^ 2) ^ (1 / 2));
+        for x in [-0.5, 0.0, 0.3, 0.9] {
+            assert_about_eq!(curvature_at(&circle, x), 1.0);
+        }
+        assert_about_eq!(
+            arc_length(&circle, 0.0, 0.5, 100),
+            std::f64::consts::FRAC_PI_6,
+            1e-8
+        );
+
+        // Works with basic functions as well.
+        assert_about_eq!(curvature_at(&Exp::new(), 0.0), 1.0 / 2f64.powf(1.5));
+    }
 }
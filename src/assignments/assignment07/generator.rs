@@ -2,9 +2,15 @@
 //!
 //! HINT: Look at the `generator_grade.rs` file to see how the generator is used.
 
+use std::ops::Range;
+use std::vec;
+
 /// Yielded value. It can be either a value or a stop signal.
-enum Yielded<T> {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Yielded<T> {
+    /// A value.
     Value(T),
+    /// The stop signal.
     Stop,
 }
 
@@ -12,6 +18,9 @@ enum Yielded<T> {
 /// - You can call `next()` method to get the next value.
 /// - The generator should stop when it yields `Yielded::Stop`.
 ///
+/// Since a generator is an iterator, `into_iter()`, `collect()` and the adapters of
+/// `MyIterTools` can be used for it, and `Generator::from_iter` converts an iterator back.
+///
 /// Reference:
 /// - [Python generator](https://python-reference.readthedocs.io/en/latest/docs/generator/)
 #[derive(Debug)]
@@ -31,15 +40,78 @@ impl<T, S> Iterator for Generator<T, S> {
     }
 }
 
+impl<I: Iterator> Generator<I::Item, I> {
+    /// Returns a generator that yields the elements of `iter`, and then `Yielded::Stop`.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_iter(iter: I) -> Self {
+        let f = |iter: &mut I| match iter.next() {
+            Some(v) => Yielded::Value(v),
+            None => Yielded::Stop,
+        };
+        Generator { state: iter, f }
+    }
+}
+
+impl<T> Generator<T, vec::IntoIter<T>> {
+    /// Returns a generator that yields the elements of `v`, and then `Yielded::Stop`.
+    pub fn from_vec(v: Vec<T>) -> Self {
+        Self::from_iter(v.into_iter())
+    }
+}
+
+impl Generator<usize, Range<usize>> {
+    /// Returns a generator that yields the numbers from `a` to `b`, excluding `b`, and then
+    /// `Yielded::Stop`.
+    pub fn from_range(a: usize, b: usize) -> Self {
+        Self::from_iter(a..b)
+    }
+}
+
+/// Coroutine, i.e., a generator that receives a value each time it is resumed.
+/// - You can call `resume(value)` method to send a value and get the next value.
+/// - The coroutine should stop when it yields `Yielded::Stop`.
+///
+/// Reference:
+/// - [Python generator's `send()`](https://docs.python.org/3/reference/expressions.html#generator.send)
+#[derive(Debug)]
+pub struct Coroutine<T, U, S> {
+    state: S,
+    f: fn(&mut S, U) -> Yielded<T>,
+}
+
+impl<T, U, S> Coroutine<T, U, S> {
+    /// Creates a coroutine with the initial state and the step function, which receives the value
+    /// sent by `resume`.
+    pub fn new(state: S, f: fn(&mut S, U) -> Yielded<T>) -> Self {
+        Self { state, f }
+    }
+
+    /// Resumes the coroutine with `value`, and returns the yielded value, or `None` if it stops.
+    pub fn resume(&mut self, value: U) -> Option<T> {
+        match (self.f)(&mut self.state, value) {
+            Yielded::Value(v) => Some(v),
+            Yielded::Stop => None,
+        }
+    }
+}
+
 /// Returns a generator that yields fibonacci numbers.
 ///
+/// It stops when the next number would overflow `usize`.
+///
 /// HINT: Consult <https://en.wikipedia.org/wiki/Fibonacci_sequence>
-pub fn fib_generator(first: usize, second: usize) -> Generator<usize, (usize, usize)> {
-    let mut state = (first, second);
-    let f = |state: &mut (usize, usize)| {
-        let (a, b) = (state.0, state.1);
-        state.0 = b;
-        state.1 = a + b;
+pub fn fib_generator(
+    first: usize,
+    second: usize,
+) -> Generator<usize, (Option<usize>, Option<usize>)> {
+    // The next two numbers, where `None` means it overflows.
+    let state = (Some(first), Some(second));
+    let f = |state: &mut (Option<usize>, Option<usize>)| {
+        let Some(a) = state.0 else {
+            return Yielded::Stop;
+        };
+        state.0 = state.1;
+        state.1 = state.1.and_then(|b| a.checked_add(b));
         Yielded::Value(a)
     };
 
@@ -4,6 +4,7 @@ mod test {
     use ntest::assert_about_eq;
 
     use crate::assignments::assignment07::generator::*;
+    use crate::assignments::assignment07::my_itertools::MyIterTools;
 
     #[test]
     fn test_generator() {
@@ -35,4 +36,101 @@ mod test {
             ]
         );
     }
+
+    #[test]
+    fn test_coroutine() {
+        // Yields the running average of the values sent so far.
+        let mut average = Coroutine::new((0.0, 0), |(sum, count): &mut (f64, i32), value: f64| {
+            *sum += value;
+            *count += 1;
+            Yielded::Value(*sum / f64::from(*count))
+        });
+        assert_about_eq!(average.resume(10.0).unwrap(), 10.0);
+        assert_about_eq!(average.resume(20.0).unwrap(), 15.0);
+        assert_about_eq!(average.resume(0.0).unwrap(), 10.0);
+
+        // Ping-pong: answers each ball, until it is out.
+        let mut pong = Coroutine::new(0, |hits: &mut usize, ball: &str| match ball {
+            "ping" => {
+                *hits += 1;
+                Yielded::Value(format!("pong {}", hits))
+            }
+            _ => Yielded::Stop,
+        });
+        assert_eq!(pong.resume("ping"), Some("pong 1".to_string()));
+        assert_eq!(pong.resume("ping"), Some("pong 2".to_string()));
+        assert_eq!(pong.resume("out"), None);
+
+        // Two coroutines driving each other, without threads.
+        let mut countdown = Coroutine::new((), |_: &mut (), n: u32| {
+            if n == 0 {
+                Yielded::Stop
+            } else {
+                Yielded::Value(n - 1)
+            }
+        });
+        let mut echo = Coroutine::new(Vec::new(), |seen: &mut Vec<u32>, n: u32| {
+            seen.push(n);
+            Yielded::Value(n)
+        });
+        let mut n = 5;
+        while let Some(next) = countdown.resume(n) {
+            n = echo.resume(next).unwrap();
+        }
+        assert_eq!(n, 0);
+        assert_eq!(echo.resume(100), Some(100));
+    }
+
+    #[test]
+    fn test_from_iter() {
+        assert_eq!(
+            Generator::from_iter([1, 2, 3].into_iter()).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(Generator::from_iter(std::iter::empty::<()>()).next(), None);
+
+        // Interoperates with the adapters of `my_itertools`.
+        assert_eq!(
+            Generator::from_iter(collatz_conjecture(6).my_step_by(2))
+                .my_chain(fib_generator(0, 1).my_take_while(|n| *n < 5))
+                .my_join(" "),
+            "6 10 16 4 1 0 1 1 2 3"
+        );
+        let mut sum = 0;
+        for n in Generator::from_iter(1..=10) {
+            sum += n;
+        }
+        assert_eq!(sum, 55);
+    }
+
+    #[test]
+    fn test_finite_generators() {
+        assert_eq!(
+            Generator::from_vec(vec!["a", "b"]).collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        assert_eq!(Generator::from_vec(Vec::<i32>::new()).next(), None);
+        assert_eq!(
+            Generator::from_range(3, 7).collect::<Vec<_>>(),
+            vec![3, 4, 5, 6]
+        );
+        assert_eq!(Generator::from_range(7, 3).next(), None);
+
+        // Stops before overflowing.
+        let fib = fib_generator(0, 1).collect::<Vec<_>>();
+        assert_eq!(fib.len(), if usize::BITS == 64 { 94 } else { 48 });
+        assert!(fib.windows(3).all(|w| w[0] + w[1] == w[2]));
+        assert!(fib[fib.len() - 2].checked_add(fib[fib.len() - 1]).is_none());
+
+        let mut fib = fib_generator(usize::MAX - 1, 1);
+        assert_eq!(fib.next(), Some(usize::MAX - 1));
+        assert_eq!(fib.next(), Some(1));
+        assert_eq!(fib.next(), Some(usize::MAX));
+        assert_eq!(fib.next(), None);
+        assert_eq!(fib.next(), None);
+        assert_eq!(
+            fib_generator(usize::MAX, usize::MAX).collect::<Vec<_>>(),
+            vec![usize::MAX, usize::MAX]
+        );
+    }
 }
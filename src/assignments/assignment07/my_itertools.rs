@@ -1,7 +1,10 @@
 //! Implement your own minimal `itertools` crate.
 
-use std::collections::HashSet;
+use std::cell::RefCell;
+use std::collections::{HashSet, VecDeque};
+use std::fmt::{Display, Write};
 use std::hash::Hash;
+use std::rc::Rc;
 
 /// Iterator that iterates over the given iterator and returns only unique elements.
 #[derive(Debug)]
@@ -98,6 +101,251 @@ impl<I1: Iterator, I2: Iterator> Iterator for Zip<I1, I2> {
     }
 }
 
+/// Iterator that alternates the elements of two iterators, starting from the first one.
+///
+/// Once one iterator runs out, the remaining elements of the other one follow.
+#[derive(Debug)]
+pub struct Interleave<I1, I2> {
+    iter1: I1,
+    iter2: I2,
+    /// Whether the next element is taken from `iter1`.
+    is_first: bool,
+}
+
+impl<T, I1: Iterator<Item = T>, I2: Iterator<Item = T>> Iterator for Interleave<I1, I2> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let is_first = self.is_first;
+        self.is_first = !is_first;
+        if is_first {
+            self.iter1.next().or_else(|| self.iter2.next())
+        } else {
+            self.iter2.next().or_else(|| self.iter1.next())
+        }
+    }
+}
+
+/// Iterator that maps each element of the given iterator to an iterator, and flattens them.
+#[derive(Debug)]
+pub struct FlatMap<I, U: IntoIterator, F> {
+    iter: I,
+    f: F,
+    /// Iterator for the current element, if any.
+    inner: Option<U::IntoIter>,
+}
+
+impl<I: Iterator, U: IntoIterator, F: FnMut(I::Item) -> U> Iterator for FlatMap<I, U, F> {
+    type Item = U::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.inner.as_mut().and_then(Iterator::next) {
+                return Some(item);
+            }
+            self.inner = Some((self.f)(self.iter.next()?).into_iter());
+        }
+    }
+}
+
+/// Iterator that iterates over the given iterator while the predicate holds.
+///
+/// Once the predicate fails, it returns `None` forever, even if the predicate would hold for the
+/// later elements.
+#[derive(Debug)]
+pub struct TakeWhile<I, P> {
+    iter: I,
+    predicate: P,
+    done: bool,
+}
+
+impl<I: Iterator, P: FnMut(&I::Item) -> bool> Iterator for TakeWhile<I, P> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let item = self.iter.next()?;
+        if (self.predicate)(&item) {
+            Some(item)
+        } else {
+            self.done = true;
+            None
+        }
+    }
+}
+
+/// Iterator that skips the elements of the given iterator while the predicate holds, and then
+/// iterates over the rest.
+///
+/// Only the prefix is skipped, i.e., the predicate is not called anymore once it fails.
+#[derive(Debug)]
+pub struct SkipWhile<I, P> {
+    iter: I,
+    predicate: P,
+    skipped: bool,
+}
+
+impl<I: Iterator, P: FnMut(&I::Item) -> bool> Iterator for SkipWhile<I, P> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.skipped {
+            return self.iter.next();
+        }
+        self.skipped = true;
+        self.iter.by_ref().find(|item| !(self.predicate)(item))
+    }
+}
+
+/// Iterator that iterates over every `step`-th element of the given iterator, starting from the
+/// first one.
+#[derive(Debug)]
+pub struct StepBy<I> {
+    iter: I,
+    step: usize,
+    is_first: bool,
+}
+
+impl<I: Iterator> Iterator for StepBy<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_first {
+            self.is_first = false;
+            self.iter.next()
+        } else {
+            self.iter.nth(self.step - 1)
+        }
+    }
+}
+
+/// Iterator that groups the elements of the given iterator into chunks of the given size.
+///
+/// The last chunk may be shorter than the size.
+#[derive(Debug)]
+pub struct Chunks<I> {
+    iter: I,
+    size: usize,
+}
+
+impl<I: Iterator> Iterator for Chunks<I> {
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunk = self.iter.by_ref().take(self.size).collect::<Vec<_>>();
+        if chunk.is_empty() {
+            None
+        } else {
+            Some(chunk)
+        }
+    }
+}
+
+/// Iterator over the overlapping windows of the given size of the given iterator.
+///
+/// If the iterator is shorter than the size, there is no window.
+#[derive(Debug)]
+pub struct Windows<I: Iterator> {
+    iter: I,
+    size: usize,
+    /// Elements of the last window.
+    window: VecDeque<I::Item>,
+}
+
+impl<I: Iterator> Iterator for Windows<I>
+where
+    I::Item: Clone,
+{
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.window.len() == self.size {
+            let _unused = self.window.pop_front();
+        }
+        while self.window.len() < self.size {
+            self.window.push_back(self.iter.next()?);
+        }
+        Some(self.window.iter().cloned().collect())
+    }
+}
+
+/// Result of `MyIterTools::my_minmax`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MinMax<T> {
+    /// The iterator is empty.
+    NoElements,
+    /// The iterator has only one element.
+    OneElement(T),
+    /// The minimum and the maximum of the iterator.
+    MinMax(T, T),
+}
+
+/// Value of either of two types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Either<L, R> {
+    /// Value of the left type.
+    Left(L),
+    /// Value of the right type.
+    Right(R),
+}
+
+/// State shared by the iterators returned by `MyIterTools::my_partition_map`.
+#[derive(Debug)]
+struct PartitionState<I, F, L, R> {
+    iter: I,
+    f: F,
+    /// Left values not yet returned by `Lefts`.
+    lefts: VecDeque<L>,
+    /// Right values not yet returned by `Rights`.
+    rights: VecDeque<R>,
+}
+
+/// Iterator over the left values of `MyIterTools::my_partition_map`.
+#[derive(Debug)]
+pub struct Lefts<I, F, L, R>(Rc<RefCell<PartitionState<I, F, L, R>>>);
+
+/// Iterator over the right values of `MyIterTools::my_partition_map`.
+#[derive(Debug)]
+pub struct Rights<I, F, L, R>(Rc<RefCell<PartitionState<I, F, L, R>>>);
+
+impl<I: Iterator, F: FnMut(I::Item) -> Either<L, R>, L, R> Iterator for Lefts<I, F, L, R> {
+    type Item = L;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut state = self.0.borrow_mut();
+        if let Some(left) = state.lefts.pop_front() {
+            return Some(left);
+        }
+        loop {
+            let item = state.iter.next()?;
+            match (state.f)(item) {
+                Either::Left(left) => return Some(left),
+                Either::Right(right) => state.rights.push_back(right),
+            }
+        }
+    }
+}
+
+impl<I: Iterator, F: FnMut(I::Item) -> Either<L, R>, L, R> Iterator for Rights<I, F, L, R> {
+    type Item = R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut state = self.0.borrow_mut();
+        if let Some(right) = state.rights.pop_front() {
+            return Some(right);
+        }
+        loop {
+            let item = state.iter.next()?;
+            match (state.f)(item) {
+                Either::Left(left) => state.lefts.push_back(left),
+                Either::Right(right) => return Some(right),
+            }
+        }
+    }
+}
+
 /// My Itertools trait.
 pub trait MyIterTools: Iterator {
     /// Returns an iterator that iterates over the `self` and returns only unique elements.
@@ -145,6 +393,107 @@ pub trait MyIterTools: Iterator {
         }
     }
 
+    /// Returns an iterator that alternates the elements of `self` and `other`, followed by the
+    /// remaining elements of the longer one.
+    ///
+    /// Unlike `interleave3` of assignment 9, the iterators may have different lengths.
+    fn my_interleave<I: Iterator<Item = Self::Item>>(self, other: I) -> Interleave<Self, I>
+    where
+        Self: Sized,
+    {
+        Interleave {
+            iter1: self,
+            iter2: other,
+            is_first: true,
+        }
+    }
+
+    /// Returns an iterator that maps each element of `self` to an iterator with `f`, and flattens
+    /// them.
+    fn my_flat_map<U: IntoIterator, F: FnMut(Self::Item) -> U>(self, f: F) -> FlatMap<Self, U, F>
+    where
+        Self: Sized,
+    {
+        FlatMap {
+            iter: self,
+            f,
+            inner: None,
+        }
+    }
+
+    /// Returns an iterator that iterates over `self` while `predicate` holds.
+    fn my_take_while<P: FnMut(&Self::Item) -> bool>(self, predicate: P) -> TakeWhile<Self, P>
+    where
+        Self: Sized,
+    {
+        TakeWhile {
+            iter: self,
+            predicate,
+            done: false,
+        }
+    }
+
+    /// Returns an iterator that skips the elements of `self` while `predicate` holds.
+    fn my_skip_while<P: FnMut(&Self::Item) -> bool>(self, predicate: P) -> SkipWhile<Self, P>
+    where
+        Self: Sized,
+    {
+        SkipWhile {
+            iter: self,
+            predicate,
+            skipped: false,
+        }
+    }
+
+    /// Returns an iterator over every `step`-th element of `self`, starting from the first one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is zero.
+    fn my_step_by(self, step: usize) -> StepBy<Self>
+    where
+        Self: Sized,
+    {
+        assert!(step > 0, "step must be positive");
+        StepBy {
+            iter: self,
+            step,
+            is_first: true,
+        }
+    }
+
+    /// Returns an iterator over the chunks of `self` of the given size, where the last one may be
+    /// shorter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    fn my_chunks(self, size: usize) -> Chunks<Self>
+    where
+        Self: Sized,
+    {
+        assert!(size > 0, "chunk size must be positive");
+        Chunks { iter: self, size }
+    }
+
+    /// Returns an iterator over the overlapping windows of `self` of the given size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    fn my_windows(self, size: usize) -> Windows<Self>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        assert!(size > 0, "window size must be positive");
+        Windows {
+            iter: self,
+            size,
+            window: VecDeque::with_capacity(size),
+        }
+    }
+
     /// Foldleft for `MyIterTools`
     fn my_fold<T, F>(mut self, init: T, mut f: F) -> T
     where
@@ -157,6 +506,110 @@ pub trait MyIterTools: Iterator {
         }
         fold
     }
+
+    /// Returns the string of the elements of `self` separated by `separator`, built in one pass.
+    fn my_join(self, separator: &str) -> String
+    where
+        Self: Sized,
+        Self::Item: Display,
+    {
+        let mut result = String::new();
+        for (i, item) in self.my_enumerate() {
+            if i > 0 {
+                result.push_str(separator);
+            }
+            write!(result, "{}", item).expect("writing to a string never fails");
+        }
+        result
+    }
+
+    /// Splits the elements of `self` into those for which `predicate` holds and the others, in
+    /// order.
+    fn my_partition<P: FnMut(&Self::Item) -> bool>(
+        self,
+        mut predicate: P,
+    ) -> (Vec<Self::Item>, Vec<Self::Item>)
+    where
+        Self: Sized,
+    {
+        self.my_fold((Vec::new(), Vec::new()), |item, (mut yes, mut no)| {
+            if predicate(&item) {
+                yes.push(item);
+            } else {
+                no.push(item);
+            }
+            (yes, no)
+        })
+    }
+
+    /// Maps the elements of `self` with `f`, and returns the iterators over the left and the
+    /// right values respectively.
+    ///
+    /// Unlike `my_partition`, the elements are mapped lazily, i.e., only when either iterator needs
+    /// the next value. The values for the other iterator are buffered meanwhile.
+    #[allow(clippy::type_complexity)]
+    fn my_partition_map<L, R, F: FnMut(Self::Item) -> Either<L, R>>(
+        self,
+        f: F,
+    ) -> (Lefts<Self, F, L, R>, Rights<Self, F, L, R>)
+    where
+        Self: Sized,
+    {
+        let state = Rc::new(RefCell::new(PartitionState {
+            iter: self,
+            f,
+            lefts: VecDeque::new(),
+            rights: VecDeque::new(),
+        }));
+        (Lefts(Rc::clone(&state)), Rights(state))
+    }
+
+    /// Returns the minimum and the maximum of `self` at once. Among equal elements, the first
+    /// minimum and the last maximum are returned.
+    ///
+    /// The elements are compared in pairs, i.e., with about 1.5 comparisons per element.
+    fn my_minmax(mut self) -> MinMax<Self::Item>
+    where
+        Self: Sized,
+        Self::Item: PartialOrd,
+    {
+        /// Orders `a` and `b`, where `a` comes first in the iterator.
+        fn order<T: PartialOrd>(a: T, b: T) -> (T, T) {
+            if b < a {
+                (b, a)
+            } else {
+                (a, b)
+            }
+        }
+
+        let Some(first) = self.next() else {
+            return MinMax::NoElements;
+        };
+        let Some(second) = self.next() else {
+            return MinMax::OneElement(first);
+        };
+
+        let (mut min, mut max) = order(first, second);
+        while let Some(a) = self.next() {
+            let Some(b) = self.next() else {
+                // Compares the last element with both ends instead.
+                if a < min {
+                    min = a;
+                } else if a >= max {
+                    max = a;
+                }
+                break;
+            };
+            let (lo, hi) = order(a, b);
+            if lo < min {
+                min = lo;
+            }
+            if hi >= max {
+                max = hi;
+            }
+        }
+        MinMax::MinMax(min, max)
+    }
 }
 
 impl<T: ?Sized> MyIterTools for T where T: Iterator {}
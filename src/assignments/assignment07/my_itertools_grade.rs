@@ -62,4 +62,324 @@ mod test {
             take15.iter().sum()
         );
     }
+
+    #[test]
+    fn test_flat_map() {
+        assert_eq!(
+            (1..=4).my_flat_map(|n| 0..n).collect::<Vec<_>>(),
+            (1..=4).flat_map(|n| 0..n).collect::<Vec<_>>()
+        );
+
+        // Empty inner iterators are skipped.
+        assert_eq!(
+            [0, 2, 0, 0, 1, 0]
+                .into_iter()
+                .my_flat_map(|n| vec![n; n])
+                .collect::<Vec<_>>(),
+            vec![2, 2, 1]
+        );
+        assert_eq!(
+            std::iter::empty::<Vec<i32>>().my_flat_map(|v| v).next(),
+            None
+        );
+
+        // Lazy, even for infinite iterators.
+        assert_eq!(
+            (0..)
+                .my_flat_map(|n| vec![n; n])
+                .take(6)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 2, 3, 3, 3]
+        );
+    }
+
+    #[test]
+    fn test_take_while_skip_while() {
+        let v = [1, 3, 5, 6, 7, 8, 9];
+        assert_eq!(
+            v.into_iter()
+                .my_take_while(|n| n % 2 == 1)
+                .collect::<Vec<_>>(),
+            vec![1, 3, 5]
+        );
+        assert_eq!(
+            v.into_iter()
+                .my_skip_while(|n| n % 2 == 1)
+                .collect::<Vec<_>>(),
+            vec![6, 7, 8, 9]
+        );
+        assert_eq!(v.into_iter().my_take_while(|_| false).next(), None);
+        assert_eq!(v.into_iter().my_skip_while(|_| true).next(), None);
+
+        // Stops permanently after the first failure, although the next element satisfies the
+        // predicate.
+        let mut iter = v.into_iter().my_take_while(|n| *n != 6);
+        assert_eq!(iter.by_ref().count(), 3);
+        assert_eq!(iter.next(), None);
+
+        // Calls the predicate only for the prefix and the first failing element.
+        let mut calls = 0;
+        assert_eq!(
+            v.into_iter()
+                .my_skip_while(|n| {
+                    calls += 1;
+                    *n < 5
+                })
+                .collect::<Vec<_>>(),
+            vec![5, 6, 7, 8, 9]
+        );
+        assert_eq!(calls, 3);
+
+        // Lazy, even for infinite iterators.
+        assert_eq!(
+            (0..)
+                .my_skip_while(|n| n * n < 50)
+                .my_take_while(|n| n * n < 200)
+                .collect::<Vec<_>>(),
+            (8..15).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_chunks_windows() {
+        assert_eq!(
+            (1..=7).my_chunks(3).collect::<Vec<_>>(),
+            vec![vec![1, 2, 3], vec![4, 5, 6], vec![7]]
+        );
+        assert_eq!(
+            (1..=6).my_chunks(3).collect::<Vec<_>>(),
+            vec![vec![1, 2, 3], vec![4, 5, 6]]
+        );
+        assert_eq!((0..0).my_chunks(3).next(), None);
+
+        let v = ["a", "b", "c", "d"];
+        assert_eq!(
+            v.into_iter().my_windows(2).collect::<Vec<_>>(),
+            v.windows(2).map(<[_]>::to_vec).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            v.into_iter().my_windows(4).collect::<Vec<_>>(),
+            vec![v.to_vec()]
+        );
+        assert_eq!(v.into_iter().my_windows(5).next(), None);
+
+        // Lazy, even for infinite iterators.
+        assert_eq!(
+            (0..)
+                .my_windows(3)
+                .map(|w| w.iter().sum())
+                .take(4)
+                .collect::<Vec<i32>>(),
+            vec![3, 6, 9, 12]
+        );
+        assert_eq!((0..).my_chunks(2).nth(10), Some(vec![20, 21]));
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk size must be positive")]
+    fn test_chunks_zero() {
+        let _unused = (0..10).my_chunks(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "window size must be positive")]
+    fn test_windows_zero() {
+        let _unused = (0..10).my_windows(0);
+    }
+
+    #[test]
+    fn test_interleave() {
+        assert_eq!(
+            [1, 2, 3]
+                .into_iter()
+                .my_interleave(4..=6)
+                .collect::<Vec<_>>(),
+            vec![1, 4, 2, 5, 3, 6]
+        );
+        assert_eq!(
+            (1..=5).my_interleave(10..=11).collect::<Vec<_>>(),
+            vec![1, 10, 2, 11, 3, 4, 5]
+        );
+        assert_eq!(
+            (1..=2).my_interleave(10..=14).collect::<Vec<_>>(),
+            vec![1, 10, 2, 11, 12, 13, 14]
+        );
+        assert_eq!((0..0).my_interleave(1..3).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(
+            "ace"
+                .chars()
+                .my_interleave("bdfgh".chars())
+                .collect::<String>(),
+            "abcdefgh"
+        );
+
+        // Lazy, even for infinite iterators.
+        assert_eq!(
+            std::iter::repeat(0)
+                .my_interleave(1..)
+                .take(6)
+                .collect::<Vec<_>>(),
+            vec![0, 1, 0, 2, 0, 3]
+        );
+    }
+
+    #[test]
+    fn test_step_by() {
+        assert_eq!((0..10).my_step_by(3).collect::<Vec<_>>(), vec![0, 3, 6, 9]);
+        assert_eq!((0..9).my_step_by(3).collect::<Vec<_>>(), vec![0, 3, 6]);
+        assert_eq!(
+            (0..5).my_step_by(1).collect::<Vec<_>>(),
+            (0..5).collect::<Vec<_>>()
+        );
+        assert_eq!((0..5).my_step_by(10).collect::<Vec<_>>(), vec![0]);
+        assert_eq!((0..0).my_step_by(2).next(), None);
+        assert_eq!("abcdefg".chars().my_step_by(2).collect::<String>(), "aceg");
+
+        // Lazy, even for infinite iterators.
+        assert_eq!(
+            (1..).my_step_by(5).take(3).collect::<Vec<_>>(),
+            vec![1, 6, 11]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "step must be positive")]
+    fn test_step_by_zero() {
+        let _unused = (0..10).my_step_by(0);
+    }
+
+    #[test]
+    fn test_minmax() {
+        assert_eq!((0..0).my_minmax(), MinMax::NoElements);
+        assert_eq!([3].into_iter().my_minmax(), MinMax::OneElement(3));
+        assert_eq!([4, 2].into_iter().my_minmax(), MinMax::MinMax(2, 4));
+        assert_eq!(
+            [5, 1, 9, 3, 7].into_iter().my_minmax(),
+            MinMax::MinMax(1, 9)
+        );
+        assert_eq!(
+            [5, 1, 9, 3, 7, 0].into_iter().my_minmax(),
+            MinMax::MinMax(0, 9)
+        );
+        assert_eq!(
+            [5, 6, 7, 10].into_iter().rev().my_minmax(),
+            MinMax::MinMax(5, 10)
+        );
+
+        // The first minimum and the last maximum, as `itertools`.
+        let v = [(1, 'a'), (0, 'b'), (2, 'c'), (0, 'd'), (2, 'e'), (1, 'f')];
+        let by_key = || v.iter().map(|&(k, c)| Key(k, c));
+        assert_eq!(
+            by_key().my_minmax(),
+            MinMax::MinMax(Key(0, 'b'), Key(2, 'e'))
+        );
+        assert_eq!(
+            by_key().take(5).my_minmax(),
+            MinMax::MinMax(Key(0, 'b'), Key(2, 'e'))
+        );
+        for n in 0..=v.len() {
+            let expected = match by_key().take(n).minmax() {
+                itertools::MinMaxResult::NoElements => MinMax::NoElements,
+                itertools::MinMaxResult::OneElement(a) => MinMax::OneElement(a),
+                itertools::MinMaxResult::MinMax(a, b) => MinMax::MinMax(a, b),
+            };
+            assert_eq!(by_key().take(n).my_minmax(), expected);
+        }
+
+        // Compares the elements about 1.5 times per element.
+        let count = std::cell::Cell::new(0);
+        let _unused = (0..100).map(|n| Counted(n * 37 % 101, &count)).my_minmax();
+        assert!(count.get() <= 150);
+
+        /// Element compared only by the first field.
+        #[derive(Debug, Clone, Copy)]
+        struct Key(i32, char);
+
+        impl PartialEq for Key {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+
+        impl PartialOrd for Key {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                self.0.partial_cmp(&other.0)
+            }
+        }
+
+        /// Element that counts the comparisons.
+        #[derive(Debug)]
+        struct Counted<'a>(i32, &'a std::cell::Cell<usize>);
+
+        impl PartialEq for Counted<'_> {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+
+        impl PartialOrd for Counted<'_> {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                self.1.set(self.1.get() + 1);
+                self.0.partial_cmp(&other.0)
+            }
+        }
+    }
+
+    #[test]
+    fn test_partition() {
+        assert_eq!(
+            (1..=10).my_partition(|n| n % 3 == 0),
+            (vec![3, 6, 9], vec![1, 2, 4, 5, 7, 8, 10])
+        );
+        assert_eq!((0..0).my_partition(|_| true), (Vec::new(), Vec::new()));
+        assert_eq!(
+            ["a", "bb", "cc", "d"]
+                .into_iter()
+                .my_partition(|s| s.len() == 1),
+            (vec!["a", "d"], vec!["bb", "cc"])
+        );
+
+        let parse = |s: &'static str| match s.parse::<i32>() {
+            Ok(n) => Either::Left(n),
+            Err(_) => Either::Right(s),
+        };
+        let (numbers, words) = ["1", "one", "2", "3", "two"]
+            .into_iter()
+            .my_partition_map(parse);
+        assert_eq!(words.collect::<Vec<_>>(), vec!["one", "two"]);
+        assert_eq!(numbers.collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        // Maps the elements only when needed, even for infinite iterators.
+        let calls = std::cell::Cell::new(0);
+        let (mut evens, mut odds) = (0..).my_partition_map(|n| {
+            calls.set(calls.get() + 1);
+            if n % 2 == 0 {
+                Either::Left(n)
+            } else {
+                Either::Right(n)
+            }
+        });
+        assert_eq!(odds.next(), Some(1));
+        assert_eq!(calls.get(), 2);
+        assert_eq!(evens.by_ref().take(3).collect::<Vec<_>>(), vec![0, 2, 4]);
+        assert_eq!(calls.get(), 5);
+        assert_eq!(odds.take(3).collect::<Vec<_>>(), vec![3, 5, 7]);
+        assert_eq!(evens.next(), Some(6));
+    }
+
+    #[test]
+    fn test_join() {
+        assert_eq!((1..=4).my_join(", "), "1, 2, 3, 4");
+        assert_eq!((1..=4).my_join(", "), (1..=4).join(", "));
+        assert_eq!([1.5].into_iter().my_join(", "), "1.5");
+        assert_eq!((0..0).my_join(", "), "");
+        assert_eq!(["a", "b", "c"].into_iter().my_join(""), "abc");
+        assert_eq!(
+            "hello world"
+                .split(' ')
+                .map(str::to_uppercase)
+                .my_join(" -> "),
+            "HELLO -> WORLD"
+        );
+    }
 }
@@ -4,6 +4,9 @@ struct FindIter<'s, T: Eq> {
     query: &'s [T],
     base: &'s [T],
     curr: usize,
+    /// Whether the matches may overlap, i.e., the search resumes right after the start of a match
+    /// rather than its end.
+    overlapping: bool,
 }
 
 impl<T: Eq> Iterator for FindIter<'_, T> {
@@ -13,7 +16,11 @@ impl<T: Eq> Iterator for FindIter<'_, T> {
         while self.curr + self.query.len() <= self.base.len() {
             if &self.base[self.curr..self.curr + self.query.len()] == self.query {
                 let ret = self.curr;
-                self.curr += 1;
+                self.curr += if self.overlapping {
+                    1
+                } else {
+                    self.query.len().max(1)
+                };
                 return Some(ret);
             }
             self.curr += 1;
@@ -28,6 +35,79 @@ pub fn find<'s, T: Eq>(query: &'s [T], base: &'s [T]) -> impl 's + Iterator<Item
         query,
         base,
         curr: 0,
+        overlapping: true,
+    }
+}
+
+/// Returns an iterator over substring query indexes in the base, where the matches do not overlap,
+/// i.e., the search resumes past the end of each match.
+pub fn find_non_overlapping<'s, T: Eq>(
+    query: &'s [T],
+    base: &'s [T],
+) -> impl 's + Iterator<Item = usize> {
+    FindIter {
+        query,
+        base,
+        curr: 0,
+        overlapping: false,
+    }
+}
+
+/// Returns `base` with the matches of `query` replaced by `replacement`.
+///
+/// Matches overlapping a previous one are not replaced, i.e., the matches of
+/// `find_non_overlapping` are replaced, as `str::replace`. For instance, replacing `aa` in `aaa`
+/// gives `Xa` rather than `XX`.
+pub fn replace<T: Clone + Eq>(base: &[T], query: &[T], replacement: &[T]) -> Vec<T> {
+    let mut result = Vec::with_capacity(base.len());
+    let mut last = 0;
+    for start in find_non_overlapping(query, base) {
+        result.extend_from_slice(&base[last..start]);
+        result.extend_from_slice(replacement);
+        last = start + query.len();
+    }
+    result.extend_from_slice(&base[last..]);
+    result
+}
+
+/// Returns `base` with the matches of `query` replaced by `replacement`, as `replace`.
+///
+/// The strings are compared by characters, so an empty query matches between characters.
+pub fn replace_str(base: &str, query: &str, replacement: &str) -> String {
+    let chars = |s: &str| s.chars().collect::<Vec<_>>();
+    replace(&chars(base), &chars(query), &chars(replacement))
+        .into_iter()
+        .collect()
+}
+
+struct RFindIter<'s, T: Eq> {
+    query: &'s [T],
+    base: &'s [T],
+    /// Number of the remaining candidate indexes, i.e., the next candidate is `end - 1`.
+    end: usize,
+}
+
+impl<T: Eq> Iterator for RFindIter<'_, T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.end > 0 {
+            self.end -= 1;
+            if &self.base[self.end..self.end + self.query.len()] == self.query {
+                return Some(self.end);
+            }
+        }
+        None
+    }
+}
+
+/// Returns an iterator over substring query indexes in the base from the end, i.e., those of
+/// `find` in the reverse order.
+pub fn rfind<'s, T: Eq>(query: &'s [T], base: &'s [T]) -> impl 's + Iterator<Item = usize> {
+    RFindIter {
+        query,
+        base,
+        end: (base.len() + 1).saturating_sub(query.len()),
     }
 }
 
@@ -159,15 +239,15 @@ impl Iterator for Divisors {
         while self.curr <= sqrt.floor() as u64 {
             if self.n % self.curr == 0 {
                 let ret = self.curr;
-                self.factor.push(ret);
+                // The square root pairs with itself.
+                if ret * ret != self.n {
+                    self.factor.push(ret);
+                }
                 self.curr += 1;
                 return Some(ret);
             }
             self.curr += 1;
         }
-        if sqrt == sqrt.trunc() {
-            _ = self.factor.pop();
-        }
         if let Some(x) = self.factor.pop() {
             Some(self.n / x)
         } else {
@@ -184,3 +264,168 @@ pub fn divisors(n: u64) -> impl Iterator<Item = u64> {
         factor: vec![], // TODO: you may define additional fields here
     }
 }
+
+/// Iterator over the prime factors of n with their exponents, in increasing order.
+/// Assume n > 0.
+///
+/// Like `Divisors`, it only tries the candidates up to sqrt(n), where n is what remains after
+/// dividing out the smaller factors. What remains in the end is 1 or a prime.
+struct Factors {
+    n: u64,
+    curr: u64,
+}
+
+impl Iterator for Factors {
+    type Item = (u64, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.curr <= self.n / self.curr {
+            let p = self.curr;
+            self.curr += if p == 2 { 1 } else { 2 };
+            let mut exponent = 0;
+            // `is_multiple_of` is newer than the pinned toolchain.
+            #[allow(unknown_lints)]
+            #[allow(clippy::manual_is_multiple_of)]
+            while self.n % p == 0 {
+                self.n /= p;
+                exponent += 1;
+            }
+            if exponent > 0 {
+                return Some((p, exponent));
+            }
+        }
+        if self.n > 1 {
+            let p = self.n;
+            self.n = 1;
+            return Some((p, 1));
+        }
+        None
+    }
+}
+
+/// Returns an iterator over the `(prime, exponent)` pairs of the prime factorization of n, in
+/// increasing order of the primes. Assume n > 0.
+pub fn factorize(n: u64) -> impl Iterator<Item = (u64, u32)> {
+    Factors { n, curr: 2 }
+}
+
+/// Returns the number of the divisors of n, i.e., `divisors(n).count()`. Assume n > 0.
+pub fn num_divisors(n: u64) -> u64 {
+    factorize(n).map(|(_, e)| u64::from(e) + 1).product()
+}
+
+/// Returns the sum of the divisors of n, i.e., `divisors(n).sum()`, which may not fit in `u64`.
+/// Assume n > 0.
+pub fn sum_divisors(n: u64) -> u128 {
+    factorize(n)
+        .map(|(p, e)| (0..=e).map(|i| u128::from(p).pow(i)).sum::<u128>())
+        .product()
+}
+
+/// Number of the numbers sieved at once by `Primes`.
+const SEGMENT: u64 = 1 << 16;
+
+/// Iterator over the primes below n, in increasing order.
+///
+/// The numbers are sieved by segments of `SEGMENT` numbers, only when the previous segment is
+/// exhausted. The primes sieving a segment are up to the square root of its end, which are in the
+/// previous segments except for the first one.
+struct Primes {
+    n: u64,
+    /// Primes found so far whose squares are below n.
+    base: Vec<u64>,
+    /// Start of the current segment.
+    low: u64,
+    /// Number of the numbers in the current segment.
+    len: u64,
+    /// Bits of the current segment, where the `i`-th one is set iff `low + i` is not a prime.
+    composite: Vec<u64>,
+    /// Offset of the next number to check in the current segment.
+    offset: u64,
+}
+
+impl Primes {
+    fn is_composite(&self, i: u64) -> bool {
+        self.composite[(i / 64) as usize] & (1 << (i % 64)) != 0
+    }
+
+    fn set_composite(&mut self, i: u64) {
+        self.composite[(i / 64) as usize] |= 1 << (i % 64);
+    }
+
+    /// Sieves the segment starting from `low`.
+    fn sieve(&mut self, low: u64) {
+        self.low = low;
+        self.len = SEGMENT.min(self.n - low);
+        self.composite = vec![0; self.len.div_ceil(64) as usize];
+        self.offset = 0;
+        let high = low + self.len;
+
+        for k in 0..self.base.len() {
+            let p = self.base[k];
+            if p > (high - 1) / p {
+                break;
+            }
+            let start = (p * p).max(low.div_ceil(p) * p);
+            for m in (start..high).step_by(p as usize) {
+                self.set_composite(m - low);
+            }
+        }
+
+        // The first segment sieves itself.
+        if low == 0 {
+            for i in 0..self.len.min(2) {
+                self.set_composite(i);
+            }
+            let mut i = 2;
+            while i * i < self.len {
+                if !self.is_composite(i) {
+                    for m in (i * i..self.len).step_by(i as usize) {
+                        self.set_composite(m);
+                    }
+                }
+                i += 1;
+            }
+        }
+    }
+}
+
+impl Iterator for Primes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.offset == self.len {
+                let next = self.low + self.len;
+                if next >= self.n {
+                    return None;
+                }
+                self.sieve(next);
+            }
+
+            let i = self.offset;
+            self.offset += 1;
+            if !self.is_composite(i) {
+                let p = self.low + i;
+                if p <= (self.n - 1) / p {
+                    self.base.push(p);
+                }
+                return Some(p);
+            }
+        }
+    }
+}
+
+/// Returns an iterator over the primes below n, in increasing order.
+///
+/// It is backed by the sieve of Eratosthenes, so it is much faster than checking each number.
+pub fn primes_below(n: u64) -> impl Iterator<Item = u64> {
+    Primes {
+        n,
+        base: Vec::new(),
+        low: 0,
+        len: 0,
+        composite: Vec::new(),
+        offset: 0,
+    }
+}
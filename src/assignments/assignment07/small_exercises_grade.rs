@@ -146,6 +146,8 @@ mod test {
 
         assert_eq!(divisors(49).collect::<Vec<u64>>(), vec![1, 7, 49]);
 
+        assert_eq!(divisors(16).collect::<Vec<u64>>(), vec![1, 2, 4, 8, 16]);
+
         assert_eq!(
             divisors(120).collect::<Vec<u64>>(),
             vec![1, 2, 3, 4, 5, 6, 8, 10, 12, 15, 20, 24, 30, 40, 60, 120]
@@ -186,4 +188,157 @@ mod test {
 
         assert_eq!(divisors(97_821_761_637_600).count(), 17280);
     }
+
+    #[test]
+    fn test_find_non_overlapping_rfind() {
+        assert_eq!(
+            find("aa".as_bytes(), "aaaaa".as_bytes()).collect::<Vec<usize>>(),
+            vec![0, 1, 2, 3]
+        );
+        assert_eq!(
+            find_non_overlapping("aa".as_bytes(), "aaaaa".as_bytes()).collect::<Vec<usize>>(),
+            vec![0, 2]
+        );
+        assert_eq!(
+            find_non_overlapping("aba".as_bytes(), "ababababa".as_bytes()).collect::<Vec<usize>>(),
+            vec![0, 4]
+        );
+        assert_eq!(
+            find_non_overlapping(&[1, 2, 3], &[1, 2, 3, 4, 1, 2, 3, 4]).collect::<Vec<usize>>(),
+            vec![0, 4]
+        );
+        assert_eq!(
+            find_non_overlapping("".as_bytes(), "ab".as_bytes()).collect::<Vec<usize>>(),
+            vec![0, 1, 2]
+        );
+
+        assert_eq!(
+            rfind("aa".as_bytes(), "aaaaa".as_bytes()).collect::<Vec<usize>>(),
+            vec![3, 2, 1, 0]
+        );
+        assert_eq!(
+            rfind("aaba".as_bytes(), "aabaacaadaabaaba".as_bytes()).collect::<Vec<usize>>(),
+            vec![12, 9, 0]
+        );
+        assert_eq!(
+            rfind("ababc".as_bytes(), "abc".as_bytes()).collect::<Vec<usize>>(),
+            vec![]
+        );
+        assert_eq!(
+            rfind("abc".as_bytes(), "abc".as_bytes()).collect::<Vec<usize>>(),
+            vec![0]
+        );
+        assert_eq!(
+            rfind("".as_bytes(), "ab".as_bytes()).collect::<Vec<usize>>(),
+            vec![2, 1, 0]
+        );
+
+        // Lazy, so the last match is found without scanning the rest.
+        let base = (0..1_000_000).map(|i| i % 7).collect::<Vec<_>>();
+        assert_eq!(rfind(&[3, 4], &base).next(), Some(999_995));
+    }
+
+    #[test]
+    fn test_replace() {
+        assert_eq!(
+            replace(&[1, 2, 3, 4, 1, 2, 3, 4], &[2, 3], &[0]),
+            vec![1, 0, 4, 1, 0, 4]
+        );
+        assert_eq!(replace(&[1, 2, 3], &[4], &[0]), vec![1, 2, 3]);
+        assert_eq!(replace(&[1, 2, 1], &[1], &[]), vec![2]);
+        assert_eq!(replace::<i32>(&[], &[1], &[0]), vec![]);
+
+        // Overlapping matches are replaced from the left only.
+        assert_eq!(replace(&[1, 1, 1], &[1, 1], &[0]), vec![0, 1]);
+        assert_eq!(replace(&[1, 1, 1, 1], &[1, 1], &[0]), vec![0, 0]);
+
+        for (base, query, replacement) in [
+            ("abcdabcd", "abc", "x"),
+            ("aaaaa", "aa", "b"),
+            ("ababababa", "aba", "<aba>"),
+            ("hello", "world", "!"),
+            ("héllo wörld", "ö", "o"),
+            ("héllo", "", "-"),
+            ("", "", "-"),
+        ] {
+            assert_eq!(
+                replace_str(base, query, replacement),
+                base.replace(query, replacement)
+            );
+        }
+    }
+
+    #[test]
+    fn test_factorize() {
+        assert_eq!(factorize(1).collect::<Vec<_>>(), vec![]);
+        assert_eq!(factorize(2).collect::<Vec<_>>(), vec![(2, 1)]);
+        assert_eq!(
+            factorize(360).collect::<Vec<_>>(),
+            vec![(2, 3), (3, 2), (5, 1)]
+        );
+        assert_eq!(factorize(49).collect::<Vec<_>>(), vec![(7, 2)]);
+        assert_eq!(
+            factorize(1_234_567_890_123).collect::<Vec<_>>(),
+            vec![(3, 1), (3_541, 1), (116_216_501, 1)]
+        );
+        assert_eq!(
+            factorize(99_999_820_000_081).collect::<Vec<_>>(),
+            vec![(9_999_991, 2)]
+        );
+        assert_eq!(factorize(1 << 63).collect::<Vec<_>>(), vec![(2, 63)]);
+        assert_eq!(
+            factorize(1_000_000_000_000_037).collect::<Vec<_>>(),
+            vec![(1_000_000_000_000_037, 1)]
+        );
+
+        // Lazy, so the small factors come first.
+        assert_eq!(factorize(2 * 1_000_000_000_000_037).next(), Some((2, 1)));
+
+        for n in (1..300).chain([97_821_761_637_600, 1_234_567_890_123]) {
+            assert_eq!(num_divisors(n), divisors(n).count() as u64);
+            assert_eq!(sum_divisors(n), divisors(n).map(u128::from).sum::<u128>());
+        }
+        assert_eq!(num_divisors(97_821_761_637_600), 17280);
+
+        // u64::MAX = 3 * 5 * 17 * 257 * 641 * 65537 * 6700417
+        assert_eq!(num_divisors(u64::MAX), 128);
+        assert_eq!(
+            sum_divisors(u64::MAX),
+            4 * 6 * 18 * 258 * 642 * 65538 * 6_700_418
+        );
+        assert!(sum_divisors(u64::MAX) > u128::from(u64::MAX));
+    }
+
+    #[test]
+    fn test_primes_below() {
+        assert_eq!(primes_below(0).next(), None);
+        assert_eq!(primes_below(2).next(), None);
+        assert_eq!(primes_below(3).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(
+            primes_below(30).collect::<Vec<_>>(),
+            vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]
+        );
+        assert_eq!(primes_below(31).last(), Some(29));
+        assert_eq!(primes_below(32).last(), Some(31));
+
+        // Agrees with the divisors across the segments.
+        let primes = primes_below(200_000).collect::<Vec<_>>();
+        assert_eq!(primes.len(), 17_984);
+        assert!(primes
+            .iter()
+            .step_by(97)
+            .all(|&p| divisors(p).collect::<Vec<_>>() == vec![1, p]));
+        assert!(primes
+            .windows(2)
+            .all(|w| (w[0] + 1..w[1]).step_by(7).all(|n| num_divisors(n) > 2)));
+        assert_eq!(primes_below(1 << 16).last(), Some(65_521));
+        assert_eq!(primes_below((1 << 16) + 2).last(), Some(65_537));
+
+        // Sieves lazily, even below a huge bound.
+        assert_eq!(
+            primes_below(u64::MAX).take(5).collect::<Vec<_>>(),
+            vec![2, 3, 5, 7, 11]
+        );
+        assert_eq!(primes_below(10_000_000).count(), 664_579);
+    }
 }
@@ -165,3 +165,245 @@ impl fmt::Display for BigInt {
         Ok(())
     }
 }
+
+// Magnitudes are little-endian `u32` digits without trailing zeros, where zero is empty.
+
+/// Removes the trailing zeros of `digits`.
+fn trim(mut digits: Vec<u32>) -> Vec<u32> {
+    while digits.last() == Some(&0) {
+        let _unused = digits.pop();
+    }
+    digits
+}
+
+/// Compares two magnitudes.
+fn cmp_magnitude(lhs: &[u32], rhs: &[u32]) -> std::cmp::Ordering {
+    lhs.len()
+        .cmp(&rhs.len())
+        .then_with(|| lhs.iter().rev().cmp(rhs.iter().rev()))
+}
+
+/// Subtracts `rhs` from `lhs`, where `lhs >= rhs`.
+fn sub_magnitude(lhs: &[u32], rhs: &[u32]) -> Vec<u32> {
+    let mut ret = Vec::with_capacity(lhs.len());
+    let mut borrow = 0i64;
+    for (i, &a) in lhs.iter().enumerate() {
+        let diff = a as i64 - rhs.get(i).copied().unwrap_or(0) as i64 - borrow;
+        borrow = (diff < 0) as i64;
+        ret.push((diff + (borrow << 32)) as u32);
+    }
+    trim(ret)
+}
+
+/// Multiplies two magnitudes.
+fn mul_magnitude(lhs: &[u32], rhs: &[u32]) -> Vec<u32> {
+    let mut ret = vec![0u32; lhs.len() + rhs.len()];
+    for (i, &a) in lhs.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &b) in rhs.iter().enumerate() {
+            let cur = ret[i + j] as u64 + a as u64 * b as u64 + carry;
+            ret[i + j] = cur as u32;
+            carry = cur >> 32;
+        }
+        ret[i + rhs.len()] = carry as u32;
+    }
+    trim(ret)
+}
+
+/// Divides `lhs` by nonzero `rhs` with the shift-and-subtract long division, and returns the
+/// quotient and the remainder.
+fn div_rem_magnitude(lhs: &[u32], rhs: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    let mut quotient = vec![0u32; lhs.len()];
+    let mut remainder = Vec::<u32>::new();
+    for i in (0..lhs.len() * 32).rev() {
+        // remainder = remainder * 2 + (i-th bit of lhs)
+        let mut carry = (lhs[i / 32] >> (i % 32)) & 1;
+        for digit in &mut remainder {
+            let next = *digit >> 31;
+            *digit = (*digit << 1) | carry;
+            carry = next;
+        }
+        if carry != 0 {
+            remainder.push(carry);
+        }
+
+        if cmp_magnitude(&remainder, rhs).is_ge() {
+            remainder = sub_magnitude(&remainder, rhs);
+            quotient[i / 32] |= 1 << (i % 32);
+        }
+    }
+    (trim(quotient), remainder)
+}
+
+impl BigInt {
+    /// Returns `true` if `self` is negative.
+    pub fn is_negative(&self) -> bool {
+        self.carrier[0] & SIGN_MASK != 0
+    }
+
+    /// Returns `true` if `self` is zero.
+    pub fn is_zero(&self) -> bool {
+        self.carrier.iter().all(|word| *word == 0)
+    }
+
+    /// Returns the magnitude of `self`.
+    fn magnitude(&self) -> Vec<u32> {
+        let abs = if self.is_negative() {
+            // Extended by a word, as `-i32::MIN` does not fit in `i32`.
+            self.sign_extension(self.carrier.len() + 1).two_complement()
+        } else {
+            self.clone()
+        };
+        trim(abs.carrier.into_iter().rev().collect())
+    }
+
+    /// Creates a `BigInt` with the given sign and magnitude.
+    fn from_magnitude(negative: bool, digits: &[u32]) -> Self {
+        let mut carrier = vec![0];
+        carrier.extend(digits.iter().rev());
+        let abs = BigInt { carrier };
+        if negative {
+            abs.two_complement().truncate()
+        } else {
+            abs.truncate()
+        }
+    }
+
+    /// Divides `self` by `rhs`, and returns the quotient rounded toward zero and the remainder,
+    /// which has the same sign as `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rhs` is zero.
+    pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+        assert!(!rhs.is_zero(), "divide by zero");
+        let (quotient, remainder) = div_rem_magnitude(&self.magnitude(), &rhs.magnitude());
+        (
+            Self::from_magnitude(self.is_negative() != rhs.is_negative(), &quotient),
+            Self::from_magnitude(self.is_negative(), &remainder),
+        )
+    }
+
+    /// Raises `self` to the power of `exp`, using exponentiation by squaring.
+    pub fn pow(&self, mut exp: u32) -> Self {
+        let mut base = self.clone();
+        let mut ret = BigInt::new(1);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                ret = ret * base.clone();
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.clone() * base;
+            }
+        }
+        ret
+    }
+
+    /// Returns the value of `self` if it fits in `i64`.
+    pub fn to_i64(&self) -> Option<i64> {
+        if self.carrier.len() > 2 {
+            return None;
+        }
+        let words = self.sign_extension(2).carrier;
+        Some((((words[0] as u64) << 32) | words[1] as u64) as i64)
+    }
+
+    /// Converts `self` into the closest `f64`.
+    pub fn to_f64(&self) -> f64 {
+        let abs = self
+            .magnitude()
+            .iter()
+            .rev()
+            .fold(0.0, |acc, digit| acc * 4_294_967_296.0 + *digit as f64);
+        if self.is_negative() {
+            -abs
+        } else {
+            abs
+        }
+    }
+
+    /// Returns the decimal representation of `self`, e.g., `-42`.
+    pub fn to_decimal(&self) -> String {
+        const BASE: u64 = 1_000_000_000;
+
+        // Splits the magnitude into base 10^9 digits.
+        let mut digits = self.magnitude();
+        let mut chunks = Vec::new();
+        while !digits.is_empty() {
+            let mut remainder = 0u64;
+            for digit in digits.iter_mut().rev() {
+                let cur = (remainder << 32) | *digit as u64;
+                *digit = (cur / BASE) as u32;
+                remainder = cur % BASE;
+            }
+            chunks.push(remainder);
+            digits = trim(digits);
+        }
+
+        let mut ret = if self.is_negative() { "-" } else { "" }.to_string();
+        match chunks.split_last() {
+            None => ret.push('0'),
+            Some((last, rest)) => {
+                ret.push_str(&last.to_string());
+                for chunk in rest.iter().rev() {
+                    ret.push_str(&format!("{chunk:09}"));
+                }
+            }
+        }
+        ret
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(n: i64) -> Self {
+        BigInt {
+            carrier: vec![(n >> 32) as u32, n as u32],
+        }
+        .truncate()
+    }
+}
+
+impl Neg for BigInt {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::from_magnitude(!self.is_negative(), &self.magnitude())
+    }
+}
+
+impl Mul for BigInt {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::from_magnitude(
+            self.is_negative() != rhs.is_negative(),
+            &mul_magnitude(&self.magnitude(), &rhs.magnitude()),
+        )
+    }
+}
+
+impl PartialEq for BigInt {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for BigInt {}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match (self.is_negative(), other.is_negative()) {
+            (false, true) => std::cmp::Ordering::Greater,
+            (true, false) => std::cmp::Ordering::Less,
+            (false, false) => cmp_magnitude(&self.magnitude(), &other.magnitude()),
+            (true, true) => cmp_magnitude(&other.magnitude(), &self.magnitude()),
+        }
+    }
+}
@@ -101,4 +101,31 @@ mod test {
 
         // TODO: add a test case testing sign extension.
     }
+
+    #[test]
+    fn test_inf_prec_mul_div() {
+        let big = |n: i64| BigInt::from(n);
+
+        assert_eq!(big(-6) * big(7), big(-42));
+        assert_eq!(big(i64::MIN) * big(-1), big(i64::MAX) + big(1));
+        assert_eq!(-big(i64::MIN), big(i64::MAX) + big(1));
+        assert_eq!(big(2).pow(64).to_decimal(), "18446744073709551616");
+        assert_eq!((-big(3).pow(41)).to_decimal(), "-36472996377170786403");
+        assert_eq!(big(0).to_decimal(), "0");
+        assert_eq!(big(1_000_000_000).to_decimal(), "1000000000");
+
+        let (q, r) = (big(10).pow(30) + big(7)).div_rem(&big(10).pow(15));
+        assert_eq!(q, big(10).pow(15));
+        assert_eq!(r, big(7));
+        assert_eq!(big(-7).div_rem(&big(2)), (big(-3), big(-1)));
+        assert_eq!(big(7).div_rem(&big(-2)), (big(-3), big(1)));
+
+        assert!(big(-1) < big(0));
+        assert!(big(2).pow(70) > big(i64::MAX));
+        assert!(-big(2).pow(70) < big(i64::MIN));
+        assert_eq!(big(2).pow(62).to_i64(), Some(1 << 62));
+        assert_eq!(big(2).pow(63).to_i64(), None);
+        assert_eq!(big(i64::MIN).to_i64(), Some(i64::MIN));
+        assert_eq!((-big(2).pow(80)).to_f64(), -(2f64.powi(80)));
+    }
 }
@@ -1,10 +1,17 @@
 //! A small graph library.
 //!
 //! A node has a i32 value and (directed) edges to other nodes. A node does not have multiple edges
-//! to the same node. Nodes are not associated with a particular domain, and users can freely
-//! create nodes however they like. However, after a node is created, it can be added to a
-//! `SubGraph`, which form a subgraph of the graph of all nodes. A node can be added to multiple
-//! subgraphs. `SubGraph` has a method to check if the it has a cycle.
+//! to the same node. Each edge carries `EdgeData`, i.e., a weight and an optional label. Nodes are
+//! not associated with a particular domain, and users can freely create nodes however they like.
+//! However, after a node is created, it can be added to a `SubGraph`, which form a subgraph of the
+//! graph of all nodes. A node can be added to multiple subgraphs. `SubGraph` has a method to check
+//! if the it has a cycle.
+//!
+//! Edges do not own their targets, so that nodes in a cycle are freed once nothing else owns them.
+//! Instead, nodes are owned by a `Graph`, which keeps them alive until they are removed from it or
+//! it is dropped. Handles, including those in subgraphs, also own their nodes, so a node created
+//! outside of a graph lives as long as its handles. An edge to a freed node is regarded as removed.
+//! Hence an edge cannot be added through the last handle to its target.
 //!
 //! The goal of this assignment is to learn how to deal with inherently shared mutable data in
 //! Rust. Design the types and fill in the `todo!()`s in methods. There are several possible
@@ -13,9 +20,10 @@
 //! Refer `graph_grade.rs` for test cases.
 
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::hash::{Hash, Hasher};
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 
 #[derive(PartialEq, Eq, Debug)]
 enum VisitStatus {
@@ -27,7 +35,47 @@ enum VisitStatus {
 #[derive(Debug, Clone)]
 pub struct Node {
     value: i32,
-    edges: RefCell<HashSet<NodeHandle>>,
+    /// Edges by the values of their targets.
+    edges: RefCell<HashMap<i32, Edge>>,
+}
+
+/// Edge to a node, which does not keep it alive.
+#[derive(Debug, Clone)]
+struct Edge {
+    to: Weak<Node>,
+    data: EdgeData,
+}
+
+impl Edge {
+    fn new(to: &NodeHandle, data: EdgeData) -> Self {
+        Self {
+            to: Rc::downgrade(&to.0),
+            data,
+        }
+    }
+
+    /// Returns the target if it is not freed.
+    fn target(&self) -> Option<NodeHandle> {
+        self.to.upgrade().map(NodeHandle)
+    }
+}
+
+/// Data attached to an edge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdgeData {
+    /// Weight of the edge, which is 1 by default.
+    pub weight: i32,
+    /// Label of the edge, if any.
+    pub label: Option<String>,
+}
+
+impl Default for EdgeData {
+    fn default() -> Self {
+        Self {
+            weight: 1,
+            label: None,
+        }
+    }
 }
 /// Handle to a graph node.
 ///
@@ -65,22 +113,101 @@ pub struct SubGraph {
     nodes: HashSet<NodeHandle>,
 }
 
+/// Owner of nodes.
+///
+/// A node in a graph stays alive even if it is only reachable through edges. Nodes in a graph have
+/// distinct values.
+#[derive(Debug)]
+pub struct Graph {
+    nodes: HashMap<i32, NodeHandle>,
+}
+
+impl Default for Graph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Graph {
+    /// Creates an empty graph.
+    pub fn new() -> Self {
+        Self {
+            nodes: HashMap::new(),
+        }
+    }
+
+    /// Creates a node owned by the graph and returns the handle to it.
+    /// Returns `Err(GraphError)` if the graph already has a node with `value`.
+    pub fn add_node(&mut self, value: i32) -> Result<NodeHandle, GraphError> {
+        let node = NodeHandle::new(value);
+        if !self.insert_node(node.clone()) {
+            return Err(GraphError);
+        }
+        Ok(node)
+    }
+
+    /// Makes the graph own `node`. Returns true iff the node is newly added, i.e., false if the
+    /// graph already has a node with the same value.
+    pub fn insert_node(&mut self, node: NodeHandle) -> bool {
+        match self.nodes.entry(node.0.value) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                let _unused = entry.insert(node);
+                true
+            }
+        }
+    }
+
+    /// Releases `node` from the graph. Returns true iff the node is successfully removed. The node
+    /// is freed if no handle to it remains, and then edges to it are removed.
+    pub fn remove_node(&mut self, node: &NodeHandle) -> bool {
+        match self.nodes.entry(node.0.value) {
+            Entry::Occupied(entry) if Rc::ptr_eq(&entry.get().0, &node.0) => {
+                let _unused = entry.remove();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the handle to the node with `value`, if any.
+    pub fn node(&self, value: i32) -> Option<NodeHandle> {
+        self.nodes.get(&value).cloned()
+    }
+
+    /// Returns the number of nodes of the graph.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns a subgraph with every node of the graph.
+    pub fn subgraph(&self) -> SubGraph {
+        SubGraph {
+            nodes: self.nodes.values().cloned().collect(),
+        }
+    }
+}
+
 impl NodeHandle {
-    /// Creates a node and returns the handle to it.
+    /// Creates a node and returns the handle to it. The node is owned only by its handles unless it
+    /// is inserted into a `Graph`.
     pub fn new(value: i32) -> Self {
         Self(Rc::new(Node {
             value,
-            edges: RefCell::new(HashSet::new()),
+            edges: RefCell::new(HashMap::new()),
         }))
     }
 
-    /// Adds an edge to `to`.
+    /// Adds an edge to `to` with the default `EdgeData`.
     /// If the modification cannot be done, e.g. because of aliasing issues, returns
     /// `Err(GraphError)`. Returns `Ok(true)` if the edge is successfully added.
-    /// Returns `Ok(false)` if an edge to `to` already exits.
+    /// Returns `Ok(false)` if an edge to `to` already exits, leaving its data unchanged.
+    /// Returns `Err(GraphError)` if `to` is the last handle to its node, since the edge would be
+    /// removed as soon as `to` is dropped. Insert the node into a `Graph` to keep it alive.
     pub fn add_edge(&self, to: NodeHandle) -> Result<bool, GraphError> {
+        to.check_owned()?;
         let mut edges = self.0.edges.try_borrow_mut().map_err(|_| GraphError)?;
-        Ok(edges.insert(to.clone()))
+        Ok(insert_edge(&mut edges, &to))
     }
 
     /// Removes the edge to `to`.
@@ -89,7 +216,70 @@ impl NodeHandle {
     /// Returns `Ok(false)` if an edge to `to` does not exist.
     pub fn remove_edge(&self, to: &NodeHandle) -> Result<bool, GraphError> {
         let mut edges = self.0.edges.try_borrow_mut().map_err(|_| GraphError)?;
-        Ok(edges.remove(to))
+        Ok(remove_edge(&mut edges, to))
+    }
+
+    /// Returns the data of the edge to `to`, or `None` if the edge does not exist.
+    /// If the edges cannot be read, e.g. because of aliasing issues, returns `Err(GraphError)`.
+    pub fn edge_data(&self, to: &NodeHandle) -> Result<Option<EdgeData>, GraphError> {
+        let edges = self.0.edges.try_borrow().map_err(|_| GraphError)?;
+        Ok(edges
+            .get(&to.0.value)
+            .filter(|edge| edge.target().is_some())
+            .map(|edge| edge.data.clone()))
+    }
+
+    /// Sets the data of the edge to `to`, adding the edge if it does not exist.
+    /// If the modification cannot be done, e.g. because of aliasing issues, returns
+    /// `Err(GraphError)`. Returns `Ok(true)` if the edge is newly added.
+    /// Returns `Ok(false)` if the data of an existing edge is replaced.
+    /// Returns `Err(GraphError)` if `to` is the last handle to its node, as `add_edge`.
+    pub fn set_edge_data(&self, to: NodeHandle, data: EdgeData) -> Result<bool, GraphError> {
+        to.check_owned()?;
+        let mut edges = self.0.edges.try_borrow_mut().map_err(|_| GraphError)?;
+        let old = edges.insert(to.0.value, Edge::new(&to, data));
+        Ok(old.and_then(|edge| edge.target()).is_none())
+    }
+
+    /// Returns an iterator over the edges as `(to, data)` pairs, in arbitrary order.
+    /// If the edges cannot be read, e.g. because of aliasing issues, returns `Err(GraphError)`.
+    pub fn iter_edges(&self) -> Result<impl Iterator<Item = (NodeHandle, EdgeData)>, GraphError> {
+        let edges = self.0.edges.try_borrow().map_err(|_| GraphError)?;
+        let edges = edges
+            .values()
+            .filter_map(|edge| Some((edge.target()?, edge.data.clone())))
+            .collect::<Vec<_>>();
+        Ok(edges.into_iter())
+    }
+
+    /// Adds edges to `to` and back, both or neither. This is how an undirected edge is represented.
+    /// If the modification cannot be done, e.g. because of aliasing issues, returns
+    /// `Err(GraphError)`. Returns `Ok(true)` if either edge is successfully added.
+    /// Returns `Ok(false)` if both edges already exist.
+    /// Returns `Err(GraphError)` if `to` is the last handle to its node, as `add_edge`.
+    pub fn add_undirected_edge(&self, to: NodeHandle) -> Result<bool, GraphError> {
+        if Rc::ptr_eq(&self.0, &to.0) {
+            return self.add_edge(to);
+        }
+        to.check_owned()?;
+        let mut edges = self.0.edges.try_borrow_mut().map_err(|_| GraphError)?;
+        let mut back_edges = to.0.edges.try_borrow_mut().map_err(|_| GraphError)?;
+        let added = insert_edge(&mut back_edges, self);
+        Ok(insert_edge(&mut edges, &to) | added)
+    }
+
+    /// Removes the edges to `to` and back, both or neither.
+    /// If the modification cannot be done, e.g. because of aliasing issues, returns
+    /// `Err(GraphError)`. Returns `Ok(true)` if either edge is successfully removed.
+    /// Returns `Ok(false)` if neither edge exists.
+    pub fn remove_undirected_edge(&self, to: &NodeHandle) -> Result<bool, GraphError> {
+        if Rc::ptr_eq(&self.0, &to.0) {
+            return self.remove_edge(to);
+        }
+        let mut edges = self.0.edges.try_borrow_mut().map_err(|_| GraphError)?;
+        let mut back_edges = to.0.edges.try_borrow_mut().map_err(|_| GraphError)?;
+        let removed = remove_edge(&mut back_edges, self);
+        Ok(remove_edge(&mut edges, to) | removed)
     }
 
     /// Removes all edges.
@@ -100,6 +290,45 @@ impl NodeHandle {
         edges.clear();
         Ok(())
     }
+
+    /// Returns the number of handles to the node, including those in subgraphs and graphs. Edges to
+    /// the node are not counted.
+    pub fn handle_count(&self) -> usize {
+        Rc::strong_count(&self.0)
+    }
+
+    /// Returns `Err(GraphError)` if `self` is the last handle to its node, i.e., an edge to it
+    /// would be dead once `self` is dropped.
+    fn check_owned(&self) -> Result<(), GraphError> {
+        if self.handle_count() == 1 {
+            return Err(GraphError);
+        }
+        Ok(())
+    }
+}
+
+/// Adds an edge to `to` with the default `EdgeData` to `edges` if it does not exist. Returns true
+/// iff the edge is newly added.
+fn insert_edge(edges: &mut HashMap<i32, Edge>, to: &NodeHandle) -> bool {
+    match edges.entry(to.0.value) {
+        Entry::Occupied(entry) if entry.get().target().is_some() => false,
+        Entry::Occupied(mut entry) => {
+            let _unused = entry.insert(Edge::new(to, EdgeData::default()));
+            true
+        }
+        Entry::Vacant(entry) => {
+            let _unused = entry.insert(Edge::new(to, EdgeData::default()));
+            true
+        }
+    }
+}
+
+/// Removes the edge to `to` from `edges`. Returns true iff the edge existed.
+fn remove_edge(edges: &mut HashMap<i32, Edge>, to: &NodeHandle) -> bool {
+    edges
+        .remove(&to.0.value)
+        .and_then(|edge| edge.target())
+        .is_some()
 }
 
 impl Default for SubGraph {
@@ -126,38 +355,589 @@ impl SubGraph {
         self.nodes.remove(node)
     }
 
+    /// Returns a new subgraph with the nodes that belong to either `self` or `other`.
+    pub fn union(&self, other: &SubGraph) -> SubGraph {
+        Self {
+            nodes: self.nodes.union(&other.nodes).cloned().collect(),
+        }
+    }
+
+    /// Returns a new subgraph with the nodes that belong to both `self` and `other`.
+    pub fn intersection(&self, other: &SubGraph) -> SubGraph {
+        Self {
+            nodes: self.nodes.intersection(&other.nodes).cloned().collect(),
+        }
+    }
+
+    /// Returns a new subgraph with the nodes that belong to `self` but not to `other`.
+    pub fn difference(&self, other: &SubGraph) -> SubGraph {
+        Self {
+            nodes: self.nodes.difference(&other.nodes).cloned().collect(),
+        }
+    }
+
+    /// Returns true iff every node of `self` belongs to `other`.
+    pub fn is_subset_of(&self, other: &SubGraph) -> bool {
+        self.nodes.is_subset(&other.nodes)
+    }
+
+    /// Returns an iterator over the nodes of the subgraph, in arbitrary order.
+    pub fn iter_nodes(&self) -> impl Iterator<Item = &NodeHandle> {
+        self.nodes.iter()
+    }
+
+    /// Returns an iterator over the edges of the subgraph as `(from, to)` pairs, in arbitrary
+    /// order. Edges from or to nodes that do not belong to this subgraph are ignored.
+    pub fn iter_edges(&self) -> impl Iterator<Item = (NodeHandle, NodeHandle)> + '_ {
+        self.nodes.iter().flat_map(move |node| {
+            self.neighbors(node)
+                .into_iter()
+                .map(move |neighbor| (node.clone(), neighbor))
+        })
+    }
+
+    /// Returns the condensation of the subgraph, which has a node for each strongly connected
+    /// component, and an edge between two components iff there is an edge between their members.
+    /// The condensation has no cycle. Nodes that do not belong to this subgraph are ignored.
+    ///
+    /// The components are returned together, in the order of `scc`. The node of the `i`-th
+    /// component is a new node with the value `i`.
+    pub fn condensation(&self) -> (SubGraph, Vec<Vec<NodeHandle>>) {
+        let components = self.scc();
+        #[allow(clippy::mutable_key_type)]
+        let component_of = components
+            .iter()
+            .enumerate()
+            .flat_map(|(i, component)| component.iter().map(move |node| (node.clone(), i)))
+            .collect::<HashMap<_, _>>();
+        let nodes = (0..components.len())
+            .map(|i| NodeHandle::new(i as i32))
+            .collect::<Vec<_>>();
+
+        for (from, to) in self.iter_edges() {
+            let (from, to) = (component_of[&from], component_of[&to]);
+            if from != to {
+                let _ = insert_edge(&mut nodes[from].0.edges.borrow_mut(), &nodes[to]);
+            }
+        }
+        let condensation = Self {
+            nodes: nodes.into_iter().collect(),
+        };
+        (condensation, components)
+    }
+
+    /// Returns the adjacency list of the subgraph as text. Each node is a line of its value, `:`,
+    /// and the values of its neighbors in the subgraph separated by spaces, e.g., `1: 2 3`. Nodes
+    /// and neighbors are sorted by value.
+    pub fn to_adjacency_list(&self) -> String {
+        let mut nodes = self.nodes.iter().collect::<Vec<_>>();
+        nodes.sort_by_key(|node| node.0.value);
+        nodes
+            .into_iter()
+            .map(|node| {
+                let mut neighbors = self
+                    .neighbors(node)
+                    .iter()
+                    .map(|neighbor| neighbor.0.value)
+                    .collect::<Vec<_>>();
+                neighbors.sort();
+                let mut line = format!("{}:", node.0.value);
+                for neighbor in neighbors {
+                    line.push_str(&format!(" {}", neighbor));
+                }
+                line.push('\n');
+                line
+            })
+            .collect()
+    }
+
+    /// Builds a subgraph from an adjacency list in the format of `to_adjacency_list`. Blank lines
+    /// are ignored. The nodes are newly created, so the result is isomorphic to the subgraph the
+    /// list came from.
+    ///
+    /// Returns `Err(GraphError)` if a line is malformed, a node appears twice, or a neighbor does
+    /// not have its own line.
+    pub fn from_adjacency_list(list: &str) -> Result<SubGraph, GraphError> {
+        let mut lines = Vec::new();
+        for line in list.lines().filter(|line| !line.trim().is_empty()) {
+            let (value, neighbors) = line.split_once(':').ok_or(GraphError)?;
+            let value = value.trim().parse::<i32>().map_err(|_| GraphError)?;
+            let neighbors = neighbors
+                .split_whitespace()
+                .map(|neighbor| neighbor.parse::<i32>().map_err(|_| GraphError))
+                .collect::<Result<Vec<_>, _>>()?;
+            lines.push((value, neighbors));
+        }
+
+        let mut nodes = HashMap::new();
+        for (value, _) in &lines {
+            if nodes.insert(*value, NodeHandle::new(*value)).is_some() {
+                return Err(GraphError);
+            }
+        }
+        for (value, neighbors) in &lines {
+            for neighbor in neighbors {
+                let neighbor = nodes.get(neighbor).ok_or(GraphError)?;
+                let _unused = nodes[value].add_edge(neighbor.clone())?;
+            }
+        }
+        Ok(Self {
+            nodes: nodes.into_values().collect(),
+        })
+    }
+
+    /// Returns the number of nodes of the subgraph.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the number of edges of the subgraph, as `iter_edges`.
+    pub fn edge_count(&self) -> usize {
+        self.nodes
+            .iter()
+            .map(|node| self.neighbors(node).len())
+            .sum()
+    }
+
+    /// Returns the number of edges from `node` to the nodes of the subgraph.
+    /// Returns `Err(GraphError)` if `node` does not belong to this subgraph.
+    pub fn out_degree(&self, node: &NodeHandle) -> Result<usize, GraphError> {
+        if !self.nodes.contains(node) {
+            return Err(GraphError);
+        }
+        Ok(self.neighbors(node).len())
+    }
+
+    /// Returns the number of edges to `node` from the nodes of the subgraph.
+    /// Returns `Err(GraphError)` if `node` does not belong to this subgraph.
+    ///
+    /// Since edges can be modified through any handle without the subgraph knowing, this scans the
+    /// edges of all nodes rather than keeping the reverse edges.
+    pub fn in_degree(&self, node: &NodeHandle) -> Result<usize, GraphError> {
+        if !self.nodes.contains(node) {
+            return Err(GraphError);
+        }
+        Ok(self.iter_edges().filter(|(_, to)| to == node).count())
+    }
+
+    /// Returns the histogram of the degrees of the nodes, i.e., the `d`-th element is the number of
+    /// nodes whose in-degree plus out-degree is `d`. A self-loop adds 2 to the degree. The last
+    /// element is nonzero unless the subgraph is empty, in which case the histogram is empty.
+    pub fn degree_histogram(&self) -> Vec<usize> {
+        #[allow(clippy::mutable_key_type)]
+        let mut degrees = self
+            .nodes
+            .iter()
+            .map(|node| (node.clone(), 0))
+            .collect::<HashMap<_, usize>>();
+        for (from, to) in self.iter_edges() {
+            *degrees.get_mut(&from).expect("member") += 1;
+            *degrees.get_mut(&to).expect("member") += 1;
+        }
+
+        let mut histogram = Vec::new();
+        for degree in degrees.into_values() {
+            if histogram.len() <= degree {
+                histogram.resize(degree + 1, 0);
+            }
+            histogram[degree] += 1;
+        }
+        histogram
+    }
+
     /// Returns true iff the subgraph contains a cycle. Nodes that do not belong to this subgraph
     /// are ignored. See <https://en.wikipedia.org/wiki/Cycle_(graph_theory)> for an algorithm.
     pub fn detect_cycle(&self) -> bool {
+        self.find_cycle().is_some()
+    }
+
+    /// Returns a cycle of the subgraph if any, as the sequence of its nodes. That is, each node has
+    /// an edge to the next one, and the last one has an edge to the first one, e.g., `[a]` for a
+    /// self-loop. Nodes that do not belong to this subgraph are ignored.
+    pub fn find_cycle(&self) -> Option<Vec<NodeHandle>> {
         #[allow(clippy::mutable_key_type)]
         let mut status = HashMap::new();
 
+        for root in &self.nodes {
+            if status.contains_key(root) {
+                continue;
+            }
+
+            // Depth-first search with an explicit stack, which does not overflow on long paths. The
+            // stack has the nodes being visited, i.e., the current path, with their neighbors left.
+            let _unused = status.insert(root.clone(), VisitStatus::Visiting);
+            let mut stack = vec![(root.clone(), self.neighbors(root).into_iter())];
+            while let Some((node, neighbors)) = stack.last_mut() {
+                let Some(neighbor) = neighbors.next() else {
+                    let _unused = status.insert(node.clone(), VisitStatus::Visited);
+                    let _unused = stack.pop();
+                    continue;
+                };
+                match status.get(&neighbor) {
+                    Some(VisitStatus::Visiting) => {
+                        let start = stack.iter().position(|(node, _)| *node == neighbor)?;
+                        return Some(stack.drain(start..).map(|(node, _)| node).collect());
+                    }
+                    Some(VisitStatus::Visited) => {}
+                    _ => {
+                        let _unused = status.insert(neighbor.clone(), VisitStatus::Visiting);
+                        let neighbors = self.neighbors(&neighbor).into_iter();
+                        stack.push((neighbor, neighbors));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns true iff the subgraph contains a cycle when its edges are regarded as undirected.
+    /// That is, a pair of edges between two nodes in both directions is not a cycle, but a self-loop
+    /// is. Nodes that do not belong to this subgraph are ignored.
+    pub fn detect_undirected_cycle(&self) -> bool {
         #[allow(clippy::mutable_key_type)]
-        fn dfs(
-            node: &NodeHandle,
-            subgraph: &SubGraph,
-            status: &mut HashMap<NodeHandle, VisitStatus>,
-        ) -> bool {
-            match status.get(node) {
-                Some(VisitStatus::Visiting) => return true,
-                Some(VisitStatus::Visited) => return false,
-                _ => {}
-            }
-            let _unused = status.insert(node.clone(), VisitStatus::Visiting);
-            for neighbor in node.0.edges.borrow().iter() {
-                if subgraph.nodes.contains(neighbor) && dfs(neighbor, subgraph, status) {
+        let neighbors = self.undirected_neighbors();
+        #[allow(clippy::mutable_key_type)]
+        let mut visited = HashSet::new();
+
+        for root in &self.nodes {
+            if !visited.insert(root.clone()) {
+                continue;
+            }
+
+            // Depth-first search with an explicit stack of the nodes being visited with their
+            // parents. Visiting a visited node other than the parent closes a cycle.
+            let mut stack = vec![(root.clone(), None, neighbors[root].iter())];
+            while let Some((node, parent, children)) = stack.last_mut() {
+                let Some(child) = children.next() else {
+                    let _unused = stack.pop();
+                    continue;
+                };
+                if Some(child) == parent.as_ref() {
+                    continue;
+                }
+                if !visited.insert(child.clone()) {
                     return true;
                 }
+                let node = node.clone();
+                stack.push((child.clone(), Some(node), neighbors[child].iter()));
             }
-            let unused = status.insert(node.clone(), VisitStatus::Visited);
-            false
         }
+        false
+    }
+
+    /// Returns the weakly connected components of the subgraph, i.e., the connected components
+    /// when its edges are regarded as undirected. They partition the nodes of the subgraph, and are
+    /// in arbitrary order. Nodes that do not belong to this subgraph are ignored.
+    pub fn connected_components(&self) -> Vec<Vec<NodeHandle>> {
+        #[allow(clippy::mutable_key_type)]
+        let neighbors = self.undirected_neighbors();
+        #[allow(clippy::mutable_key_type)]
+        let mut visited = HashSet::new();
+        let mut components = Vec::new();
 
-        for node in &self.nodes {
-            if !status.contains_key(node) && dfs(node, self, &mut status) {
-                return true;
+        for root in &self.nodes {
+            if !visited.insert(root.clone()) {
+                continue;
             }
+
+            // Depth-first search with an explicit stack of the nodes to visit.
+            let mut component = Vec::new();
+            let mut stack = vec![root.clone()];
+            while let Some(node) = stack.pop() {
+                for neighbor in &neighbors[&node] {
+                    if visited.insert(neighbor.clone()) {
+                        stack.push(neighbor.clone());
+                    }
+                }
+                component.push(node);
+            }
+            components.push(component);
         }
-        false
+        components
+    }
+
+    /// Returns the nodes of the subgraph reachable from `node` by following edges, including `node`
+    /// itself. Nodes that do not belong to this subgraph are ignored, and the result is empty if
+    /// `node` does not belong to it.
+    #[allow(clippy::mutable_key_type)]
+    pub fn reachable_from(&self, node: &NodeHandle) -> HashSet<NodeHandle> {
+        if !self.nodes.contains(node) {
+            return HashSet::new();
+        }
+        self.reachable(vec![node.clone()])
+    }
+
+    /// Returns a path from `from` to `to` with the fewest edges in the subgraph, as the sequence of
+    /// its nodes from `from` to `to`, e.g., `[from]` if they are the same. Returns `Ok(None)` if
+    /// `to` is not reachable from `from`. Returns `Err(GraphError)` if either of them does not
+    /// belong to this subgraph.
+    pub fn bfs_path(
+        &self,
+        from: &NodeHandle,
+        to: &NodeHandle,
+    ) -> Result<Option<Vec<NodeHandle>>, GraphError> {
+        if !self.nodes.contains(from) || !self.nodes.contains(to) {
+            return Err(GraphError);
+        }
+
+        // Breadth-first search, remembering the node each node is first reached from.
+        #[allow(clippy::mutable_key_type)]
+        let mut parents = HashMap::<_, Option<NodeHandle>>::new();
+        let _unused = parents.insert(from.clone(), None);
+        let mut queue = VecDeque::from([from.clone()]);
+        while let Some(node) = queue.pop_front() {
+            if node == *to {
+                let mut path = vec![node];
+                while let Some(Some(parent)) = path.last().and_then(|node| parents.get(node)) {
+                    path.push(parent.clone());
+                }
+                path.reverse();
+                return Ok(Some(path));
+            }
+            for neighbor in self.neighbors(&node) {
+                if !parents.contains_key(&neighbor) {
+                    let _unused = parents.insert(neighbor.clone(), Some(node.clone()));
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the transitive closure of the subgraph, i.e., a subgraph with an edge from `a` to
+    /// `b` iff there is a path from `a` to `b` of length at least one in this subgraph. Nodes that
+    /// do not belong to this subgraph are ignored.
+    ///
+    /// Since edges belong to nodes, the closure consists of new nodes with the same values as the
+    /// nodes of this subgraph, which are left unchanged.
+    pub fn transitive_closure(&self) -> SubGraph {
+        #[allow(clippy::mutable_key_type)]
+        let closure = self
+            .nodes
+            .iter()
+            .map(|node| (node.clone(), NodeHandle::new(node.0.value)))
+            .collect::<HashMap<_, _>>();
+        for (node, new_node) in &closure {
+            let mut edges = new_node.0.edges.borrow_mut();
+            for reachable in self.reachable(self.neighbors(node)) {
+                let _ = insert_edge(&mut edges, &closure[&reachable]);
+            }
+        }
+        Self {
+            nodes: closure.into_values().collect(),
+        }
+    }
+
+    /// Returns the nodes of the subgraph reachable from `roots`, which belong to it, including
+    /// themselves.
+    #[allow(clippy::mutable_key_type)]
+    fn reachable(&self, roots: Vec<NodeHandle>) -> HashSet<NodeHandle> {
+        let mut visited = roots.iter().cloned().collect::<HashSet<_>>();
+        let mut stack = roots;
+        while let Some(node) = stack.pop() {
+            for neighbor in self.neighbors(&node) {
+                if visited.insert(neighbor.clone()) {
+                    stack.push(neighbor);
+                }
+            }
+        }
+        visited
+    }
+
+    /// Returns the articulation points of the subgraph when its edges are regarded as undirected,
+    /// i.e., the nodes whose removal increases the number of connected components. They are in
+    /// arbitrary order. Nodes that do not belong to this subgraph are ignored.
+    pub fn articulation_points(&self) -> Vec<NodeHandle> {
+        self.cut_points_and_bridges().0
+    }
+
+    /// Returns the bridges of the subgraph when its edges are regarded as undirected, i.e., the
+    /// edges whose removal increases the number of connected components. Each bridge is a pair of
+    /// its ends in arbitrary order, and so are the bridges. Nodes that do not belong to this
+    /// subgraph are ignored.
+    pub fn bridges(&self) -> Vec<(NodeHandle, NodeHandle)> {
+        self.cut_points_and_bridges().1
+    }
+
+    /// Returns the articulation points and the bridges found with a single depth-first search
+    /// computing low-links. See <https://en.wikipedia.org/wiki/Biconnected_component> for an
+    /// algorithm.
+    #[allow(clippy::type_complexity)]
+    fn cut_points_and_bridges(&self) -> (Vec<NodeHandle>, Vec<(NodeHandle, NodeHandle)>) {
+        #[allow(clippy::mutable_key_type)]
+        let neighbors = self.undirected_neighbors();
+        // Index and low-link of each visited node, where the low-link is the smallest index
+        // reachable from the subtree of the node with at most one back edge.
+        #[allow(clippy::mutable_key_type)]
+        let mut links = HashMap::<_, (usize, usize)>::new();
+        #[allow(clippy::mutable_key_type)]
+        let mut cut_points = HashSet::new();
+        let mut bridges = Vec::new();
+
+        for root in &self.nodes {
+            if links.contains_key(root) {
+                continue;
+            }
+
+            // Depth-first search with an explicit stack of the nodes being visited with their
+            // parents.
+            let index = links.len();
+            let _unused = links.insert(root.clone(), (index, index));
+            let mut root_children = 0;
+            let mut stack = vec![(root, None, neighbors[root].iter())];
+            while let Some((node, parent, children)) = stack.last_mut() {
+                let (node, parent) = (*node, *parent);
+                let Some(child) = children.next() else {
+                    let _unused = stack.pop();
+                    let Some(parent) = parent else {
+                        continue;
+                    };
+                    let (_, low) = links[node];
+                    let (parent_index, parent_low) = links.get_mut(parent).expect("visited");
+                    *parent_low = (*parent_low).min(low);
+                    if low > *parent_index {
+                        bridges.push((parent.clone(), node.clone()));
+                    }
+                    if parent == root {
+                        root_children += 1;
+                    } else if low >= *parent_index {
+                        let _unused = cut_points.insert(parent.clone());
+                    }
+                    continue;
+                };
+                if child == node || Some(child) == parent {
+                    continue;
+                }
+                match links.get(child) {
+                    Some(&(index, _)) => {
+                        let (_, low) = links.get_mut(node).expect("visited");
+                        *low = (*low).min(index);
+                    }
+                    None => {
+                        let index = links.len();
+                        let _unused = links.insert(child.clone(), (index, index));
+                        stack.push((child, Some(node), neighbors[child].iter()));
+                    }
+                }
+            }
+            if root_children > 1 {
+                let _unused = cut_points.insert(root.clone());
+            }
+        }
+        (cut_points.into_iter().collect(), bridges)
+    }
+
+    /// Returns the neighbors of each node of the subgraph when its edges are regarded as
+    /// undirected.
+    #[allow(clippy::mutable_key_type)]
+    fn undirected_neighbors(&self) -> HashMap<NodeHandle, HashSet<NodeHandle>> {
+        let mut neighbors = self
+            .nodes
+            .iter()
+            .map(|node| (node.clone(), HashSet::new()))
+            .collect::<HashMap<_, _>>();
+        for (from, to) in self.iter_edges() {
+            let _unused = neighbors.get_mut(&to).map(|set| set.insert(from.clone()));
+            let _unused = neighbors.get_mut(&from).map(|set| set.insert(to));
+        }
+        neighbors
+    }
+
+    /// Returns the neighbors of `node` that belong to this subgraph.
+    fn neighbors(&self, node: &NodeHandle) -> Vec<NodeHandle> {
+        node.0
+            .edges
+            .borrow()
+            .values()
+            .filter_map(Edge::target)
+            .filter(|neighbor| self.nodes.contains(neighbor))
+            .collect()
+    }
+
+    /// Returns the strongly connected components of the subgraph in reverse topological order,
+    /// i.e., there is no edge from a component to a later one. Nodes that do not belong to this
+    /// subgraph are ignored. See <https://en.wikipedia.org/wiki/Tarjan%27s_strongly_connected_components_algorithm>
+    /// for an algorithm.
+    pub fn scc(&self) -> Vec<Vec<NodeHandle>> {
+        #[derive(Debug, Default)]
+        struct Tarjan {
+            /// Index and low-link of each visited node.
+            #[allow(clippy::mutable_key_type)]
+            links: HashMap<NodeHandle, (usize, usize)>,
+            stack: Vec<NodeHandle>,
+            #[allow(clippy::mutable_key_type)]
+            on_stack: HashSet<NodeHandle>,
+            components: Vec<Vec<NodeHandle>>,
+        }
+
+        impl Tarjan {
+            fn enter(&mut self, node: &NodeHandle) {
+                let index = self.links.len();
+                let _unused = self.links.insert(node.clone(), (index, index));
+                self.stack.push(node.clone());
+                let _unused = self.on_stack.insert(node.clone());
+            }
+
+            /// Lowers the low-link of `node` to `low`.
+            fn lower(&mut self, node: &NodeHandle, low: usize) {
+                if let Some((_, link)) = self.links.get_mut(node) {
+                    *link = (*link).min(low);
+                }
+            }
+
+            /// Leaves `node`, and returns its low-link.
+            fn leave(&mut self, node: &NodeHandle) -> usize {
+                let (index, low) = self.links[node];
+
+                // `node` is the root of a component, which is on the top of the stack.
+                if low == index {
+                    let mut component = Vec::new();
+                    while let Some(member) = self.stack.pop() {
+                        let _unused = self.on_stack.remove(&member);
+                        let done = member == *node;
+                        component.push(member);
+                        if done {
+                            break;
+                        }
+                    }
+                    self.components.push(component);
+                }
+                low
+            }
+        }
+
+        let mut tarjan = Tarjan::default();
+        for root in &self.nodes {
+            if tarjan.links.contains_key(root) {
+                continue;
+            }
+
+            // Depth-first search with an explicit stack of the nodes being visited.
+            tarjan.enter(root);
+            let mut calls = vec![(root.clone(), self.neighbors(root).into_iter())];
+            while let Some((node, neighbors)) = calls.last_mut() {
+                let Some(neighbor) = neighbors.next() else {
+                    let node = node.clone();
+                    let _unused = calls.pop();
+                    let low = tarjan.leave(&node);
+                    if let Some((parent, _)) = calls.last() {
+                        tarjan.lower(parent, low);
+                    }
+                    continue;
+                };
+                match tarjan.links.get(&neighbor) {
+                    None => {
+                        tarjan.enter(&neighbor);
+                        let neighbors = self.neighbors(&neighbor).into_iter();
+                        calls.push((neighbor, neighbors));
+                    }
+                    Some(&(index, _)) if tarjan.on_stack.contains(&neighbor) => {
+                        tarjan.lower(node, index);
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        tarjan.components
     }
 }
@@ -60,4 +60,820 @@ mod test_graph {
             n.clear_edges().unwrap();
         }
     }
+
+    #[test]
+    fn test_scc() {
+        use std::collections::HashSet;
+
+        let nodes = (0..8).map(NodeHandle::new).collect::<Vec<_>>();
+        let edges = [
+            (0, 1),
+            (1, 2),
+            (2, 0),
+            (2, 3),
+            (3, 4),
+            (4, 3),
+            (5, 5),
+            (5, 4),
+            (6, 7),
+        ];
+        for (from, to) in edges {
+            assert!(nodes[from].add_edge(nodes[to].clone()).unwrap());
+        }
+
+        #[allow(clippy::mutable_key_type)]
+        let components = |graph: &SubGraph| {
+            graph
+                .scc()
+                .into_iter()
+                .map(|component| component.into_iter().collect::<HashSet<_>>())
+                .collect::<Vec<_>>()
+        };
+        #[allow(clippy::mutable_key_type)]
+        let set = |ns: &[usize]| ns.iter().map(|n| nodes[*n].clone()).collect::<HashSet<_>>();
+
+        let mut graph = SubGraph::new();
+        for node in &nodes {
+            assert!(graph.add_node(node.clone()));
+        }
+        let result = components(&graph);
+        assert_eq!(result.len(), 5);
+        for component in [&[0, 1, 2][..], &[3, 4], &[5], &[6], &[7]] {
+            assert!(result.contains(&set(component)));
+        }
+
+        // Components are in reverse topological order.
+        let position = |n: usize| result.iter().position(|c| c.contains(&nodes[n])).unwrap();
+        assert!(position(3) < position(0));
+        assert!(position(3) < position(5));
+        assert!(position(7) < position(6));
+
+        // Nodes outside of the subgraph break cycles.
+        assert!(graph.remove_node(&nodes[2]));
+        assert!(graph.remove_node(&nodes[4]));
+        let result = components(&graph);
+        assert_eq!(result.len(), 6);
+        for component in [&[0][..], &[1], &[3], &[5], &[6], &[7]] {
+            assert!(result.contains(&set(component)));
+        }
+
+        assert!(SubGraph::new().scc().is_empty());
+
+        for n in nodes {
+            n.clear_edges().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let nodes = (0..6).map(NodeHandle::new).collect::<Vec<_>>();
+        let edges = [(0, 1), (1, 2), (2, 3), (3, 1), (4, 4), (2, 5)];
+        for (from, to) in edges {
+            assert!(nodes[from].add_edge(nodes[to].clone()).unwrap());
+        }
+
+        // Each node of a cycle has an edge to the next one, i.e., the edge is not newly added.
+        let assert_cycle = |cycle: &[NodeHandle]| {
+            assert!(!cycle.is_empty());
+            for (i, node) in cycle.iter().enumerate() {
+                let next = cycle[(i + 1) % cycle.len()].clone();
+                assert!(!node.add_edge(next).unwrap());
+            }
+        };
+
+        let mut graph = SubGraph::new();
+        for n in [0, 1, 2, 3, 5] {
+            assert!(graph.add_node(nodes[n].clone()));
+        }
+        let cycle = graph.find_cycle().unwrap();
+        assert_eq!(cycle.len(), 3);
+        for n in [1, 2, 3] {
+            assert!(cycle.contains(&nodes[n]));
+        }
+        assert_cycle(&cycle);
+
+        // Self-loop.
+        assert!(graph.remove_node(&nodes[3]));
+        assert!(graph.find_cycle().is_none());
+        assert!(graph.add_node(nodes[4].clone()));
+        assert_eq!(graph.find_cycle(), Some(vec![nodes[4].clone()]));
+
+        assert!(nodes[4].remove_edge(&nodes[4]).unwrap());
+        assert!(graph.find_cycle().is_none());
+        assert!(nodes[5].add_edge(nodes[0].clone()).unwrap());
+        let cycle = graph.find_cycle().unwrap();
+        assert_eq!(cycle.len(), 4);
+        assert_cycle(&cycle);
+
+        for n in nodes {
+            n.clear_edges().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_deep_chain() {
+        const N: usize = 100_000;
+
+        let nodes = (0..N as i32).map(NodeHandle::new).collect::<Vec<_>>();
+        let mut graph = SubGraph::new();
+        for (i, node) in nodes.iter().enumerate() {
+            if i + 1 < N {
+                assert!(node.add_edge(nodes[i + 1].clone()).unwrap());
+            }
+            assert!(graph.add_node(node.clone()));
+        }
+        assert!(!graph.detect_cycle());
+        assert_eq!(graph.scc().len(), N);
+
+        assert!(nodes[N - 1].add_edge(nodes[0].clone()).unwrap());
+        assert_eq!(graph.find_cycle().unwrap().len(), N);
+        assert_eq!(graph.scc().len(), 1);
+
+        for n in nodes {
+            n.clear_edges().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_iter() {
+        use std::collections::HashSet;
+
+        let nodes = (0..5).map(NodeHandle::new).collect::<Vec<_>>();
+        let edges = [(0, 1), (1, 2), (2, 0), (2, 3), (3, 3), (4, 0)];
+        for (from, to) in edges {
+            assert!(nodes[from].add_edge(nodes[to].clone()).unwrap());
+        }
+
+        let mut graph = SubGraph::new();
+        assert_eq!(graph.node_count(), 0);
+        assert_eq!(graph.edge_count(), 0);
+        assert_eq!(graph.iter_edges().count(), 0);
+
+        for node in &nodes[..4] {
+            assert!(graph.add_node(node.clone()));
+        }
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 5);
+
+        #[allow(clippy::mutable_key_type)]
+        let node_set = graph.iter_nodes().cloned().collect::<HashSet<_>>();
+        #[allow(clippy::mutable_key_type)]
+        let expected = nodes[..4].iter().cloned().collect::<HashSet<_>>();
+        assert_eq!(node_set, expected);
+
+        #[allow(clippy::mutable_key_type)]
+        let edge_set = graph.iter_edges().collect::<HashSet<_>>();
+        assert_eq!(edge_set.len(), 5);
+        for (from, to) in &edges[..5] {
+            assert!(edge_set.contains(&(nodes[*from].clone(), nodes[*to].clone())));
+        }
+
+        // Edges from or to nodes outside of the subgraph are ignored.
+        assert!(graph.remove_node(&nodes[0]));
+        assert!(graph.add_node(nodes[4].clone()));
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.iter_edges().count(), 3);
+
+        for n in nodes {
+            n.clear_edges().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_undirected() {
+        let nodes = (0..5).map(NodeHandle::new).collect::<Vec<_>>();
+        let mut graph = SubGraph::new();
+        for node in &nodes {
+            assert!(graph.add_node(node.clone()));
+        }
+
+        // Undirected edges are pairs of directed edges.
+        for (from, to) in [(0, 1), (1, 2), (2, 3), (1, 4)] {
+            assert!(nodes[from].add_undirected_edge(nodes[to].clone()).unwrap());
+        }
+        assert!(!nodes[1].add_undirected_edge(nodes[0].clone()).unwrap());
+        assert_eq!(graph.edge_count(), 8);
+        assert!(!nodes[1].add_edge(nodes[0].clone()).unwrap());
+
+        // A tree has no undirected cycles, though it has directed ones.
+        assert!(graph.detect_cycle());
+        assert!(!graph.detect_undirected_cycle());
+
+        assert!(nodes[3].add_undirected_edge(nodes[0].clone()).unwrap());
+        assert!(graph.detect_undirected_cycle());
+        assert!(graph.remove_node(&nodes[2]));
+        assert!(!graph.detect_undirected_cycle());
+        assert!(graph.add_node(nodes[2].clone()));
+        assert!(nodes[0].remove_undirected_edge(&nodes[3]).unwrap());
+        assert!(!nodes[0].remove_undirected_edge(&nodes[3]).unwrap());
+        assert!(!graph.detect_undirected_cycle());
+
+        // A single directed edge is also an undirected edge.
+        assert!(nodes[4].add_edge(nodes[3].clone()).unwrap());
+        assert!(nodes[4].remove_edge(&nodes[1]).unwrap());
+        assert!(graph.detect_undirected_cycle());
+        assert!(nodes[4].remove_edge(&nodes[3]).unwrap());
+
+        // Self-loops are cycles.
+        assert!(!graph.detect_undirected_cycle());
+        assert!(nodes[4].add_undirected_edge(nodes[4].clone()).unwrap());
+        assert!(graph.detect_undirected_cycle());
+        assert!(nodes[4].remove_undirected_edge(&nodes[4]).unwrap());
+        assert!(!graph.detect_undirected_cycle());
+
+        for n in nodes {
+            n.clear_edges().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_connected_components() {
+        use std::collections::HashSet;
+
+        let nodes = (0..8).map(NodeHandle::new).collect::<Vec<_>>();
+        let edges = [(0, 1), (2, 1), (3, 2), (4, 5), (5, 4), (6, 6)];
+        for (from, to) in edges {
+            assert!(nodes[from].add_edge(nodes[to].clone()).unwrap());
+        }
+
+        #[allow(clippy::mutable_key_type)]
+        let components = |graph: &SubGraph| {
+            graph
+                .connected_components()
+                .into_iter()
+                .map(|component| component.into_iter().collect::<HashSet<_>>())
+                .collect::<Vec<_>>()
+        };
+        #[allow(clippy::mutable_key_type)]
+        let set = |ns: &[usize]| ns.iter().map(|n| nodes[*n].clone()).collect::<HashSet<_>>();
+
+        let mut graph = SubGraph::new();
+        for node in &nodes {
+            assert!(graph.add_node(node.clone()));
+        }
+        let result = components(&graph);
+        assert_eq!(result.len(), 4);
+        for component in [&[0, 1, 2, 3][..], &[4, 5], &[6], &[7]] {
+            assert!(result.contains(&set(component)));
+        }
+
+        // Nodes outside of the subgraph do not connect the others.
+        assert!(graph.remove_node(&nodes[1]));
+        let result = components(&graph);
+        assert_eq!(result.len(), 5);
+        for component in [&[0][..], &[2, 3], &[4, 5], &[6], &[7]] {
+            assert!(result.contains(&set(component)));
+        }
+        assert_eq!(
+            result.iter().map(HashSet::len).sum::<usize>(),
+            graph.node_count()
+        );
+
+        assert!(SubGraph::new().connected_components().is_empty());
+
+        for n in nodes {
+            n.clear_edges().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let nodes = (0..6).map(NodeHandle::new).collect::<Vec<_>>();
+        for (from, to) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5)] {
+            assert!(nodes[from].add_edge(nodes[to].clone()).unwrap());
+        }
+
+        let subgraph = |ns: &[usize]| {
+            let mut graph = SubGraph::new();
+            for n in ns {
+                assert!(graph.add_node(nodes[*n].clone()));
+            }
+            graph
+        };
+        let values = |graph: &SubGraph| {
+            let mut values = (0..6)
+                .filter(|n| graph.iter_nodes().any(|node| *node == nodes[*n]))
+                .collect::<Vec<_>>();
+            values.sort();
+            values
+        };
+
+        let graph1 = subgraph(&[0, 1, 3, 4]);
+        let graph2 = subgraph(&[1, 2, 4, 5]);
+        assert!(!graph1.detect_cycle());
+        assert!(!graph2.detect_cycle());
+
+        let union = graph1.union(&graph2);
+        assert_eq!(values(&union), [0, 1, 2, 3, 4, 5]);
+        assert!(union.detect_cycle());
+        assert_eq!(union.edge_count(), 5);
+
+        let intersection = graph1.intersection(&graph2);
+        assert_eq!(values(&intersection), [1, 4]);
+        assert_eq!(intersection.edge_count(), 0);
+
+        let difference = graph1.difference(&graph2);
+        assert_eq!(values(&difference), [0, 3]);
+        assert_eq!(values(&graph2.difference(&graph1)), [2, 5]);
+
+        assert!(graph1.is_subset_of(&union));
+        assert!(intersection.is_subset_of(&graph2));
+        assert!(!graph1.is_subset_of(&graph2));
+        assert!(SubGraph::new().is_subset_of(&difference));
+
+        // The results share the nodes with the operands.
+        assert!(nodes[1].add_edge(nodes[0].clone()).unwrap());
+        assert!(difference.union(&intersection).detect_cycle());
+        assert!(graph1.detect_cycle());
+        assert_eq!(graph1.edge_count(), 3);
+
+        for n in nodes {
+            n.clear_edges().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_reachability() {
+        use std::collections::HashSet;
+
+        let nodes = (0..6).map(NodeHandle::new).collect::<Vec<_>>();
+        for (from, to) in [(0, 1), (1, 2), (2, 1), (3, 0), (3, 4), (4, 5)] {
+            assert!(nodes[from].add_edge(nodes[to].clone()).unwrap());
+        }
+
+        let mut graph = SubGraph::new();
+        for node in &nodes[..5] {
+            assert!(graph.add_node(node.clone()));
+        }
+        #[allow(clippy::mutable_key_type)]
+        let set = |ns: &[usize]| ns.iter().map(|n| nodes[*n].clone()).collect::<HashSet<_>>();
+
+        assert_eq!(graph.reachable_from(&nodes[0]), set(&[0, 1, 2]));
+        assert_eq!(graph.reachable_from(&nodes[2]), set(&[1, 2]));
+        assert_eq!(graph.reachable_from(&nodes[3]), set(&[0, 1, 2, 3, 4]));
+        assert_eq!(graph.reachable_from(&nodes[4]), set(&[4]));
+        assert!(graph.reachable_from(&nodes[5]).is_empty());
+
+        let closure = graph.transitive_closure();
+        assert_eq!(closure.node_count(), 5);
+        #[allow(clippy::mutable_key_type)]
+        let edges = closure.iter_edges().collect::<HashSet<_>>();
+        let expected = [
+            (0, 1),
+            (0, 2),
+            (1, 1),
+            (1, 2),
+            (2, 1),
+            (2, 2),
+            (3, 0),
+            (3, 1),
+            (3, 2),
+            (3, 4),
+        ];
+        assert_eq!(edges.len(), expected.len());
+        for (from, to) in expected {
+            assert!(edges.contains(&(nodes[from].clone(), nodes[to].clone())));
+        }
+        assert!(closure.detect_cycle());
+
+        // The original nodes are left unchanged.
+        assert_eq!(graph.edge_count(), 5);
+        assert!(!nodes[0].remove_edge(&nodes[2]).unwrap());
+
+        for n in nodes {
+            n.clear_edges().unwrap();
+        }
+        for n in closure.iter_nodes() {
+            n.clear_edges().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_bfs_path() {
+        let nodes = (0..7).map(NodeHandle::new).collect::<Vec<_>>();
+        let edges = [
+            (0, 1),
+            (1, 2),
+            (2, 3),
+            (3, 4),
+            (0, 5),
+            (5, 3),
+            (4, 0),
+            (2, 6),
+        ];
+        for (from, to) in edges {
+            assert!(nodes[from].add_edge(nodes[to].clone()).unwrap());
+        }
+
+        let mut graph = SubGraph::new();
+        for node in &nodes[..6] {
+            assert!(graph.add_node(node.clone()));
+        }
+        let path = |ns: &[usize]| ns.iter().map(|n| nodes[*n].clone()).collect::<Vec<_>>();
+
+        assert_eq!(
+            graph.bfs_path(&nodes[0], &nodes[4]).unwrap(),
+            Some(path(&[0, 5, 3, 4]))
+        );
+        assert_eq!(
+            graph.bfs_path(&nodes[1], &nodes[5]).unwrap(),
+            Some(path(&[1, 2, 3, 4, 0, 5]))
+        );
+        assert_eq!(
+            graph.bfs_path(&nodes[2], &nodes[2]).unwrap(),
+            Some(path(&[2]))
+        );
+
+        // Paths only go through the nodes of the subgraph.
+        assert!(graph.remove_node(&nodes[5]));
+        assert_eq!(
+            graph.bfs_path(&nodes[0], &nodes[4]).unwrap(),
+            Some(path(&[0, 1, 2, 3, 4]))
+        );
+        assert!(graph.remove_node(&nodes[3]));
+        assert_eq!(graph.bfs_path(&nodes[0], &nodes[4]).unwrap(), None);
+
+        // Endpoints must belong to the subgraph.
+        assert!(graph.bfs_path(&nodes[0], &nodes[6]).is_err());
+        assert!(graph.bfs_path(&nodes[5], &nodes[0]).is_err());
+
+        for n in nodes {
+            n.clear_edges().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_adjacency_list() {
+        let nodes = (0..5).map(NodeHandle::new).collect::<Vec<_>>();
+        for (from, to) in [(0, 1), (0, 3), (1, 4), (3, 1), (4, 3), (2, 2), (4, 0)] {
+            assert!(nodes[from].add_edge(nodes[to].clone()).unwrap());
+        }
+        let mut graph = SubGraph::new();
+        for node in &nodes[1..] {
+            assert!(graph.add_node(node.clone()));
+        }
+
+        let list = graph.to_adjacency_list();
+        assert_eq!(list, "1: 4\n2: 2\n3: 1\n4: 3\n");
+
+        let copy = SubGraph::from_adjacency_list(&list).unwrap();
+        assert_eq!(copy.to_adjacency_list(), list);
+        assert_eq!(copy.node_count(), 4);
+        assert_eq!(copy.edge_count(), 4);
+        assert!(copy.detect_cycle());
+        assert_eq!(copy.scc().len(), graph.scc().len());
+
+        // Fixtures may be written by hand.
+        let fixture = SubGraph::from_adjacency_list("\n10: 20 30\n20:\n 30 :20\n\n").unwrap();
+        assert_eq!(fixture.to_adjacency_list(), "10: 20 30\n20:\n30: 20\n");
+        assert!(!fixture.detect_cycle());
+        assert_eq!(SubGraph::new().to_adjacency_list(), "");
+        assert_eq!(SubGraph::from_adjacency_list("").unwrap().node_count(), 0);
+
+        assert!(SubGraph::from_adjacency_list("1 2").is_err());
+        assert!(SubGraph::from_adjacency_list("1: x").is_err());
+        assert!(SubGraph::from_adjacency_list("1: 2").is_err());
+        assert!(SubGraph::from_adjacency_list("1:\n1:").is_err());
+
+        for n in nodes {
+            n.clear_edges().unwrap();
+        }
+        for graph in [copy, fixture] {
+            for n in graph.iter_nodes() {
+                n.clear_edges().unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_edge_data() {
+        let nodes = (0..3).map(NodeHandle::new).collect::<Vec<_>>();
+        let road = |weight, label: &str| EdgeData {
+            weight,
+            label: Some(label.to_string()),
+        };
+
+        assert!(nodes[0].add_edge(nodes[1].clone()).unwrap());
+        assert_eq!(
+            nodes[0].edge_data(&nodes[1]).unwrap(),
+            Some(EdgeData::default())
+        );
+        assert_eq!(EdgeData::default().weight, 1);
+        assert_eq!(nodes[0].edge_data(&nodes[2]).unwrap(), None);
+
+        // Setting data replaces that of an existing edge, or adds a new edge.
+        assert!(!nodes[0]
+            .set_edge_data(nodes[1].clone(), road(5, "a"))
+            .unwrap());
+        assert!(nodes[0]
+            .set_edge_data(nodes[2].clone(), road(7, "b"))
+            .unwrap());
+        assert_eq!(nodes[0].edge_data(&nodes[1]).unwrap(), Some(road(5, "a")));
+
+        // Adding an existing edge keeps its data.
+        assert!(!nodes[0].add_edge(nodes[1].clone()).unwrap());
+        assert_eq!(nodes[0].edge_data(&nodes[1]).unwrap(), Some(road(5, "a")));
+
+        let mut edges = nodes[0].iter_edges().unwrap().collect::<Vec<_>>();
+        edges.sort_by_key(|(_, data)| data.weight);
+        assert_eq!(
+            edges,
+            [
+                (nodes[1].clone(), road(5, "a")),
+                (nodes[2].clone(), road(7, "b"))
+            ]
+        );
+        assert_eq!(nodes[1].iter_edges().unwrap().count(), 0);
+
+        // Undirected edges have the default data in both directions.
+        assert!(nodes[1].add_undirected_edge(nodes[2].clone()).unwrap());
+        assert_eq!(
+            nodes[2].edge_data(&nodes[1]).unwrap(),
+            Some(EdgeData::default())
+        );
+
+        let mut graph = SubGraph::new();
+        for node in &nodes {
+            assert!(graph.add_node(node.clone()));
+        }
+        assert_eq!(graph.edge_count(), 4);
+        assert!(nodes[0].remove_edge(&nodes[1]).unwrap());
+        assert_eq!(nodes[0].edge_data(&nodes[1]).unwrap(), None);
+
+        for n in nodes {
+            n.clear_edges().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_cycle_freed() {
+        let nodes = (0..3).map(NodeHandle::new).collect::<Vec<_>>();
+        for (from, to) in [(0, 1), (1, 2), (2, 0)] {
+            assert!(nodes[from].add_edge(nodes[to].clone()).unwrap());
+        }
+        assert!(nodes[1].add_undirected_edge(nodes[1].clone()).unwrap());
+
+        // Edges do not keep their targets alive, even in a cycle.
+        for node in &nodes {
+            assert_eq!(node.handle_count(), 1);
+        }
+
+        let mut graph = SubGraph::new();
+        for node in &nodes {
+            assert!(graph.add_node(node.clone()));
+        }
+        assert_eq!(nodes[0].handle_count(), 2);
+        assert!(graph.detect_cycle());
+        let closure = graph.transitive_closure();
+        for node in closure.iter_nodes() {
+            assert_eq!(node.handle_count(), 1);
+        }
+        drop(closure);
+        drop(graph);
+        assert_eq!(nodes[0].handle_count(), 1);
+
+        // An edge to a freed node is removed.
+        let mut nodes = nodes.into_iter();
+        let (node0, node1, node2) = (
+            nodes.next().unwrap(),
+            nodes.next().unwrap(),
+            nodes.next().unwrap(),
+        );
+        drop(node1);
+        assert_eq!(node0.iter_edges().unwrap().count(), 0);
+        assert_eq!(node0.edge_data(&NodeHandle::new(1)).unwrap(), None);
+        assert!(!node0.remove_edge(&NodeHandle::new(1)).unwrap());
+        assert_eq!(node2.iter_edges().unwrap().count(), 1);
+
+        let mut graph = SubGraph::new();
+        assert!(graph.add_node(node0.clone()));
+        assert!(graph.add_node(node2));
+        assert!(!graph.detect_cycle());
+
+        // A new node with the same value gets a new edge.
+        let node1 = NodeHandle::new(1);
+        assert!(node0.add_edge(node1.clone()).unwrap());
+        assert_eq!(node0.iter_edges().unwrap().count(), 1);
+        assert_eq!(node1.handle_count(), 1);
+
+        // An edge to a node owned only by the argument would be dead right away.
+        assert!(node0.add_edge(NodeHandle::new(3)).is_err());
+        assert!(node0
+            .set_edge_data(NodeHandle::new(3), EdgeData::default())
+            .is_err());
+        assert!(node0.add_undirected_edge(NodeHandle::new(3)).is_err());
+        assert_eq!(node0.iter_edges().unwrap().count(), 1);
+        assert!(node0.add_undirected_edge(node0.clone()).unwrap());
+    }
+
+    #[test]
+    fn test_degrees() {
+        let nodes = (0..6).map(NodeHandle::new).collect::<Vec<_>>();
+        let edges = [(0, 1), (0, 2), (0, 3), (1, 2), (2, 0), (3, 3), (4, 0)];
+        for (from, to) in edges {
+            assert!(nodes[from].add_edge(nodes[to].clone()).unwrap());
+        }
+
+        let mut graph = SubGraph::new();
+        for node in &nodes[..5] {
+            assert!(graph.add_node(node.clone()));
+        }
+
+        let out_degrees = nodes[..5]
+            .iter()
+            .map(|node| graph.out_degree(node).unwrap())
+            .collect::<Vec<_>>();
+        let in_degrees = nodes[..5]
+            .iter()
+            .map(|node| graph.in_degree(node).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(out_degrees, [3, 1, 1, 1, 1]);
+        assert_eq!(in_degrees, [2, 1, 2, 2, 0]);
+        assert_eq!(graph.degree_histogram(), [0, 1, 1, 2, 0, 1]);
+        assert!(graph.out_degree(&nodes[5]).is_err());
+        assert!(graph.in_degree(&nodes[5]).is_err());
+
+        // Degrees only count the edges of the subgraph, including those modified afterwards.
+        assert!(graph.remove_node(&nodes[4]));
+        assert!(nodes[5].add_edge(nodes[1].clone()).unwrap());
+        assert!(nodes[3].remove_edge(&nodes[3]).unwrap());
+        assert_eq!(graph.in_degree(&nodes[0]).unwrap(), 1);
+        assert_eq!(graph.in_degree(&nodes[1]).unwrap(), 1);
+        assert_eq!(graph.in_degree(&nodes[3]).unwrap(), 1);
+        assert_eq!(graph.degree_histogram(), [0, 1, 1, 1, 1]);
+
+        assert!(SubGraph::new().degree_histogram().is_empty());
+    }
+
+    #[test]
+    fn test_condensation() {
+        let nodes = (0..8).map(NodeHandle::new).collect::<Vec<_>>();
+        let edges = [
+            (0, 1),
+            (1, 2),
+            (2, 0),
+            (2, 3),
+            (3, 4),
+            (4, 3),
+            (5, 5),
+            (5, 4),
+            (1, 4),
+            (6, 7),
+        ];
+        for (from, to) in edges {
+            assert!(nodes[from].add_edge(nodes[to].clone()).unwrap());
+        }
+        let mut graph = SubGraph::new();
+        for node in &nodes[..7] {
+            assert!(graph.add_node(node.clone()));
+        }
+
+        let (condensation, components) = graph.condensation();
+        assert_eq!(condensation.node_count(), 4);
+        assert_eq!(components.len(), 4);
+        assert!(!condensation.detect_cycle());
+        assert_eq!(
+            components.iter().map(Vec::len).sum::<usize>(),
+            graph.node_count()
+        );
+
+        // Finds the component of each node by its value.
+        let component_of = |n: usize| {
+            components
+                .iter()
+                .position(|component| component.contains(&nodes[n]))
+                .unwrap()
+        };
+        assert_eq!(component_of(0), component_of(2));
+        assert_eq!(component_of(3), component_of(4));
+        assert_ne!(component_of(0), component_of(3));
+
+        assert_eq!(condensation.edge_count(), 2);
+        let component = |n: usize| {
+            condensation
+                .iter_nodes()
+                .find(|node| **node == NodeHandle::new(component_of(n) as i32))
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(
+            component(0).edge_data(&component(3)).unwrap(),
+            Some(EdgeData::default())
+        );
+        assert!(component(5).edge_data(&component(3)).unwrap().is_some());
+        assert_eq!(condensation.out_degree(&component(6)).unwrap(), 0);
+        assert_eq!(condensation.in_degree(&component(6)).unwrap(), 0);
+
+        assert_eq!(SubGraph::new().condensation().0.node_count(), 0);
+    }
+
+    #[test]
+    fn test_articulation_points_and_bridges() {
+        use std::collections::HashSet;
+
+        let nodes = (0..14).map(NodeHandle::new).collect::<Vec<_>>();
+        let edges = [
+            (0, 1),
+            (1, 0),
+            (1, 2),
+            (2, 0),
+            (2, 3),
+            (4, 3),
+            (4, 5),
+            (5, 6),
+            (6, 4),
+            (8, 8),
+            (9, 10),
+            (11, 12),
+            (13, 11),
+        ];
+        for (from, to) in edges {
+            assert!(nodes[from].add_edge(nodes[to].clone()).unwrap());
+        }
+        let mut graph = SubGraph::new();
+        for node in &nodes {
+            assert!(graph.add_node(node.clone()));
+        }
+
+        #[allow(clippy::mutable_key_type)]
+        let set = |ns: &[usize]| ns.iter().map(|n| nodes[*n].clone()).collect::<HashSet<_>>();
+        #[allow(clippy::mutable_key_type)]
+        let bridges = |graph: &SubGraph| {
+            graph
+                .bridges()
+                .into_iter()
+                .map(|(a, b)| [a, b].into_iter().collect::<HashSet<_>>())
+                .collect::<Vec<_>>()
+        };
+
+        let points = graph.articulation_points();
+        assert_eq!(points.len(), 4);
+        assert_eq!(
+            points.into_iter().collect::<HashSet<_>>(),
+            set(&[2, 3, 4, 11])
+        );
+        let result = bridges(&graph);
+        assert_eq!(result.len(), 5);
+        for bridge in [[2, 3], [3, 4], [9, 10], [11, 12], [11, 13]] {
+            assert!(result.contains(&set(&bridge)));
+        }
+
+        // Closing a cycle through the bridges removes them.
+        assert!(nodes[5].add_edge(nodes[0].clone()).unwrap());
+        assert_eq!(
+            graph
+                .articulation_points()
+                .into_iter()
+                .collect::<HashSet<_>>(),
+            set(&[11])
+        );
+        assert_eq!(bridges(&graph).len(), 3);
+
+        // Only the nodes of the subgraph are considered.
+        assert!(graph.remove_node(&nodes[11]));
+        assert!(graph.articulation_points().is_empty());
+        assert_eq!(bridges(&graph), [set(&[9, 10])]);
+    }
+
+    #[test]
+    fn test_graph_owns_nodes() {
+        let mut graph = Graph::new();
+        let a = graph.add_node(0).unwrap();
+        let b = graph.add_node(1).unwrap();
+        let c = graph.add_node(2).unwrap();
+        assert!(graph.add_node(1).is_err());
+        assert!(!graph.insert_node(NodeHandle::new(2)));
+        assert_eq!(graph.node_count(), 3);
+
+        // Nodes reachable only through edges are kept alive by the graph.
+        for (from, to) in [(&a, &b), (&b, &c), (&c, &a)] {
+            assert!(from.add_edge(to.clone()).unwrap());
+        }
+        drop(b);
+        drop(c);
+        let b = a.iter_edges().unwrap().next().unwrap().0;
+        assert_eq!(b, graph.node(1).unwrap());
+        assert_eq!(b.iter_edges().unwrap().count(), 1);
+        assert!(graph.subgraph().detect_cycle());
+        drop(b);
+
+        // A node removed from the graph is freed with its edges.
+        let c = graph.node(2).unwrap();
+        assert!(graph.remove_node(&c));
+        assert!(!graph.remove_node(&c));
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(c.handle_count(), 1);
+        drop(c);
+        assert!(!graph.subgraph().detect_cycle());
+        assert!(!graph.remove_node(&NodeHandle::new(0)));
+
+        // Dropping the graph frees the nodes it owns, even in a cycle.
+        assert!(!a.add_edge(graph.node(1).unwrap()).unwrap());
+        assert!(graph.node(1).unwrap().add_edge(a.clone()).unwrap());
+        assert_eq!(a.handle_count(), 2);
+        drop(graph);
+        assert_eq!(a.handle_count(), 1);
+        assert_eq!(a.iter_edges().unwrap().count(), 0);
+    }
 }
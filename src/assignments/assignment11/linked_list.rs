@@ -2,7 +2,9 @@
 //!
 //! Consult <https://doc.rust-lang.org/book/ch15-01-box.html>.
 
-use std::fmt::Debug;
+use std::fmt::{self, Debug, Display};
+use std::mem;
+use std::ops::{Index, IndexMut};
 
 /// Node of the list.
 #[derive(Debug)]
@@ -26,6 +28,9 @@ impl<T: Debug> Node<T> {
 pub struct SinglyLinkedList<T: Debug> {
     /// Head node of the list. If it is `None`, the list is empty.
     head: Option<Box<Node<T>>>,
+
+    /// Number of nodes of the list.
+    len: usize,
 }
 
 impl<T: Debug> Default for SinglyLinkedList<T> {
@@ -37,7 +42,7 @@ impl<T: Debug> Default for SinglyLinkedList<T> {
 impl<T: Debug> SinglyLinkedList<T> {
     /// Creates a new list.
     pub fn new() -> Self {
-        Self { head: None }
+        Self { head: None, len: 0 }
     }
 
     /// Adds the given node to the front of the list.
@@ -47,6 +52,7 @@ impl<T: Debug> SinglyLinkedList<T> {
             new_node.next = Some(Box::new(*node));
         }
         self.head = Some(Box::new(new_node));
+        self.len += 1;
     }
 
     /// Adds the given node to the back of the list.
@@ -57,12 +63,14 @@ impl<T: Debug> SinglyLinkedList<T> {
             curr_node = &mut node.next;
         }
         *curr_node = Some(Box::new(new_node));
+        self.len += 1;
     }
 
     /// Removes and returns the node at the front of the list.
     pub fn pop_front(&mut self) -> Option<T> {
         self.head.take().map(|node| {
             self.head = node.next;
+            self.len -= 1;
             node.value
         })
     }
@@ -75,12 +83,14 @@ impl<T: Debug> SinglyLinkedList<T> {
         }
 
         if curr_node.as_ref().unwrap().next.is_none() {
+            self.len -= 1;
             return self.head.take().map(|node| node.value);
         }
 
         while let Some(ref mut node) = curr_node {
             if node.next.as_ref().unwrap().next.is_none() {
                 let last = node.next.take().unwrap();
+                self.len -= 1;
                 return Some(last.value);
             }
             curr_node = &mut node.next;
@@ -88,19 +98,125 @@ impl<T: Debug> SinglyLinkedList<T> {
         None
     }
 
+    /// Returns the element at the front of the list, if any.
+    pub fn front(&self) -> Option<&T> {
+        self.head.as_ref().map(|node| &node.value)
+    }
+
+    /// Returns a mutable reference to the element at the front of the list, if any.
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.head.as_mut().map(|node| &mut node.value)
+    }
+
+    /// Returns the element at the back of the list, if any.
+    ///
+    /// It takes O(n) time since the list has no pointer to its last node.
+    pub fn back(&self) -> Option<&T> {
+        self.values().last()
+    }
+
+    /// Returns a mutable reference to the element at the back of the list, if any.
+    ///
+    /// It takes O(n) time since the list has no pointer to its last node.
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        let mut curr_node = self.head.as_mut()?;
+        while let Some(ref mut node) = curr_node.next {
+            curr_node = node;
+        }
+        Some(&mut curr_node.value)
+    }
+
+    /// Returns the element at `index` counting from the front, or `None` if it is out of range.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.values().nth(index)
+    }
+
+    /// Returns a mutable reference to the element at `index` counting from the front, or `None` if
+    /// it is out of range.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        let mut curr_node = self.head.as_mut()?;
+        for _ in 0..index {
+            curr_node = curr_node.next.as_mut()?;
+        }
+        Some(&mut curr_node.value)
+    }
+
+    /// Moves all elements of `other` to the back of the list, leaving `other` empty. The nodes are
+    /// relinked rather than reallocated.
+    pub fn append(&mut self, other: &mut Self) {
+        let mut tail = &mut self.head;
+        while let Some(ref mut node) = tail {
+            tail = &mut node.next;
+        }
+        let _ = Self::splice(tail, other);
+        self.len += mem::take(&mut other.len);
+    }
+
+    /// Rotates the list to the left by `k` elements, i.e., moves the first `k` elements to the back.
+    /// `k` may be larger than the length. The nodes are relinked in O(n) time and O(1) space.
+    ///
+    /// # Examples
+    ///
+    /// `self`: `[1, 2, 3, 4, 5]`, `k`: `2` ==> `[3, 4, 5, 1, 2]`
+    pub fn rotate_left(&mut self, k: usize) {
+        // `is_multiple_of` is newer than the pinned toolchain.
+        #[allow(unknown_lints)]
+        #[allow(clippy::manual_is_multiple_of)]
+        if self.len == 0 || k % self.len == 0 {
+            return;
+        }
+
+        // Splits the list after the `k`-th node, and links the first part after the rest.
+        let mut split = &mut self.head;
+        for _ in 0..k % self.len {
+            split = &mut split.as_mut().expect("shorter than the list").next;
+        }
+        let mut rest = split.take();
+        let mut tail = &mut rest;
+        while let Some(ref mut node) = tail {
+            tail = &mut node.next;
+        }
+        *tail = self.head.take();
+        self.head = rest;
+    }
+
+    /// Rotates the list to the right by `k` elements, i.e., moves the last `k` elements to the
+    /// front. `k` may be larger than the length. The nodes are relinked in O(n) time and O(1)
+    /// space.
+    ///
+    /// # Examples
+    ///
+    /// `self`: `[1, 2, 3, 4, 5]`, `k`: `2` ==> `[4, 5, 1, 2, 3]`
+    pub fn rotate_right(&mut self, k: usize) {
+        if self.len == 0 {
+            return;
+        }
+        self.rotate_left(self.len - k % self.len);
+    }
+
+    /// Moves the nodes of `other` to `tail`, which is the empty link after the last node of a list,
+    /// and returns the new one. The lengths are left to the caller.
+    fn splice<'a>(
+        tail: &'a mut Option<Box<Node<T>>>,
+        other: &mut Self,
+    ) -> &'a mut Option<Box<Node<T>>> {
+        *tail = other.head.take();
+        let mut tail = tail;
+        while let Some(ref mut node) = tail {
+            tail = &mut node.next;
+        }
+        tail
+    }
+
     /// Create a new list from the given vector `vec`.
     pub fn from_vec(vec: Vec<T>) -> Self {
-        let mut ret = Self::new();
-        for val in vec.into_iter().rev() {
-            ret.push_front(val);
-        }
-        ret
+        vec.into_iter().collect()
     }
 
     /// Convert the current list into a vector.
-    pub fn into_vec(self) -> Vec<T> {
+    pub fn into_vec(mut self) -> Vec<T> {
         let mut ret = Vec::new();
-        let mut curr_node = self.head;
+        let mut curr_node = self.head.take();
         while let Some(node) = curr_node {
             ret.push(node.value);
             curr_node = node.next;
@@ -110,14 +226,64 @@ impl<T: Debug> SinglyLinkedList<T> {
 
     /// Return the length (i.e., number of nodes) of the list.
     pub fn length(&self) -> usize {
-        let mut count = 0;
-        let mut curr_node = self.head.as_ref();
+        debug_assert_eq!(self.len, self.count_nodes());
+        self.len
+    }
+
+    /// Returns `true` if the list has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    /// Counts the nodes by traversing the list, which should agree with the cached length.
+    fn count_nodes(&self) -> usize {
+        self.values().count()
+    }
+
+    /// Returns an iterator over the values of the list from the front.
+    fn values(&self) -> impl Iterator<Item = &T> {
+        std::iter::successors(self.head.as_deref(), |node| node.next.as_deref())
+            .map(|node| &node.value)
+    }
+
+    /// Retains only the elements for which `f` returns `true`, unlinking the others in place.
+    ///
+    /// # Examples
+    ///
+    /// `self`: `[1, 2, 3, 4]`, `f`: `|x| x % 2 == 0` ==> `[2, 4]`
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        let mut curr_node = &mut self.head;
+        while let Some(node) = curr_node {
+            if f(&node.value) {
+                curr_node = &mut curr_node.as_mut().unwrap().next;
+            } else {
+                *curr_node = node.next.take();
+                self.len -= 1;
+            }
+        }
+    }
 
+    /// Removes consecutive duplicate elements, unlinking them in place.
+    ///
+    /// # Examples
+    ///
+    /// `self`: `[1, 1, 2, 1, 3, 3, 3]` ==> `[1, 2, 1, 3]`
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        let mut curr_node = self.head.as_mut();
         while let Some(node) = curr_node {
-            count += 1;
-            curr_node = node.next.as_ref();
+            while let Some(mut next) = node.next.take() {
+                if next.value != node.value {
+                    node.next = Some(next);
+                    break;
+                }
+                node.next = next.next.take();
+                self.len -= 1;
+            }
+            curr_node = node.next.as_mut();
         }
-        count
     }
 
     /// Apply function `f` on every element of the list.
@@ -140,19 +306,123 @@ impl<T: Debug> SinglyLinkedList<T> {
     /// // each adjacent pair of elements: `(1, 2)`, `(2, 3)`, `(3, 4)`
     /// // apply `f` to each pair: `f(1, 2) == 3`, `f(2, 3) == 5`, `f(3, 4) == 7`
     /// ==> `[3, 5, 7]`
-    pub fn pair_map<F: Fn(T, T) -> T>(self, f: F) -> Self
+    ///
+    /// Only the second element of each pair is cloned, since it is also the first one of the next.
+    pub fn pair_map<F: Fn(T, T) -> T>(mut self, f: F) -> Self
     where
         T: Clone,
     {
         if self.length() < 2 {
             return self;
         }
-        let vec = self.into_vec();
-        let mut ret = Vec::new();
-        for i in 0..vec.len().saturating_sub(1) {
-            ret.push(f(vec[i].clone(), vec[i + 1].clone()));
+        let mut prev = self.pop_front().expect("the list has at least 2 elements");
+        std::iter::from_fn(|| self.pop_front())
+            .map(|value| f(mem::replace(&mut prev, value.clone()), value))
+            .collect()
+    }
+}
+
+impl<T: Debug> Drop for SinglyLinkedList<T> {
+    /// Drops the nodes one by one, since dropping the boxed nodes recursively may overflow the
+    /// stack for a long list.
+    fn drop(&mut self) {
+        let mut curr_node = self.head.take();
+        while let Some(mut node) = curr_node {
+            curr_node = node.next.take();
         }
-        Self::from_vec(ret)
+    }
+}
+
+impl<T: Debug> Index<usize> for SinglyLinkedList<T> {
+    type Output = T;
+
+    /// Panics if `index` is out of range.
+    fn index(&self, index: usize) -> &T {
+        let len = self.len;
+        self.get(index).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                len, index
+            )
+        })
+    }
+}
+
+impl<T: Debug> IndexMut<usize> for SinglyLinkedList<T> {
+    /// Panics if `index` is out of range.
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        let len = self.len;
+        self.get_mut(index).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                len, index
+            )
+        })
+    }
+}
+
+impl<T: Debug> Extend<T> for SinglyLinkedList<T> {
+    /// Appends the values to the back of the list, traversing the list only once.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let mut curr_node = &mut self.head;
+        while let Some(ref mut node) = curr_node {
+            curr_node = &mut node.next;
+        }
+        for value in iter {
+            let node = curr_node.insert(Box::new(Node::new(value)));
+            curr_node = &mut node.next;
+            self.len += 1;
+        }
+    }
+}
+
+impl<T: Debug> FromIterator<T> for SinglyLinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T: Debug> From<Vec<T>> for SinglyLinkedList<T> {
+    fn from(vec: Vec<T>) -> Self {
+        Self::from_vec(vec)
+    }
+}
+
+impl<T: Debug> From<SinglyLinkedList<T>> for Vec<T> {
+    fn from(list: SinglyLinkedList<T>) -> Self {
+        list.into_vec()
+    }
+}
+
+impl<T: Debug + Clone> Clone for SinglyLinkedList<T> {
+    fn clone(&self) -> Self {
+        // Appends the nodes one by one, since cloning the boxed nodes recursively may overflow the
+        // stack for a long list.
+        self.values().cloned().collect()
+    }
+}
+
+impl<T: Debug + PartialEq> PartialEq for SinglyLinkedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.values().eq(other.values())
+    }
+}
+
+impl<T: Debug + Eq> Eq for SinglyLinkedList<T> {}
+
+/// Formats the list like `[1 -> 2 -> 3]`.
+impl<T: Debug + Display> Display for SinglyLinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, value) in self.values().enumerate() {
+            if i > 0 {
+                write!(f, " -> ")?;
+            }
+            write!(f, "{}", value)?;
+        }
+        write!(f, "]")
     }
 }
 
@@ -163,13 +433,15 @@ impl<T: Debug> SinglyLinkedList<SinglyLinkedList<T>> {
     /// # Examples
     /// `self`: `[[1, 2, 3], [4, 5, 6], [7, 8]]`
     /// ==> `[1, 2, 3, 4, 5, 6, 7, 8]`
-    pub fn flatten(self) -> SinglyLinkedList<T> {
-        let mut vec = Vec::new();
-        let mut list = self.into_vec();
-        for temp in list.into_iter() {
-            let values = temp.into_vec();
-            vec.extend(values);
-        }
-        SinglyLinkedList::from_vec(vec)
+    ///
+    /// The nodes of the inner lists are relinked rather than reallocated, in O(total nodes) time.
+    pub fn flatten(mut self) -> SinglyLinkedList<T> {
+        let mut ret = SinglyLinkedList::new();
+        let mut tail = &mut ret.head;
+        while let Some(mut list) = self.pop_front() {
+            tail = SinglyLinkedList::splice(tail, &mut list);
+            ret.len += list.len;
+        }
+        ret
     }
 }
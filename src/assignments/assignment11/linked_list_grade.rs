@@ -34,8 +34,24 @@ mod test_linked_list {
 
     #[test]
     fn test_length() {
-        let list = SinglyLinkedList::from_vec(vec![1, 2, 3]);
+        let mut list = SinglyLinkedList::from_vec(vec![1, 2, 3]);
+        assert_eq!(list.length(), 3);
+        assert!(!list.is_empty());
+
+        list.push_back(4);
+        list.push_front(0);
+        assert_eq!(list.length(), 5);
+        assert_eq!(list.pop_back(), Some(4));
+        assert_eq!(list.pop_front(), Some(0));
         assert_eq!(list.length(), 3);
+
+        while list.pop_back().is_some() {}
+        assert_eq!(list.length(), 0);
+        assert!(list.is_empty());
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.length(), 0);
+
+        assert!(SinglyLinkedList::<i32>::new().is_empty());
     }
 
     #[test]
@@ -80,4 +96,242 @@ mod test_linked_list {
             vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
         );
     }
+
+    #[test]
+    fn test_clone_eq_display() {
+        let list = SinglyLinkedList::from_vec(vec![1, 2, 3]);
+        let mut copy = list.clone();
+        assert_eq!(list, copy);
+        assert_eq!(copy.length(), 3);
+
+        assert_eq!(copy.pop_back(), Some(3));
+        assert_ne!(list, copy);
+        copy.push_back(4);
+        assert_ne!(list, copy);
+        assert_eq!(list, SinglyLinkedList::from_vec(vec![1, 2, 3]));
+        assert_eq!(SinglyLinkedList::<i32>::new(), SinglyLinkedList::new());
+
+        assert_eq!(list.to_string(), "[1 -> 2 -> 3]");
+        assert_eq!(copy.to_string(), "[1 -> 2 -> 4]");
+        assert_eq!(SinglyLinkedList::from_vec(vec!["a"]).to_string(), "[a]");
+        assert_eq!(SinglyLinkedList::<i32>::new().to_string(), "[]");
+    }
+
+    #[test]
+    fn test_from_iter_extend() {
+        let mut list = (1..=3).collect::<SinglyLinkedList<_>>();
+        assert_eq!(list.length(), 3);
+        list.extend(vec![4, 5]);
+        list.extend(None);
+        assert_eq!(list.length(), 5);
+        list.push_back(6);
+        assert_eq!(Vec::from(list), vec![1, 2, 3, 4, 5, 6]);
+
+        let mut list = SinglyLinkedList::new();
+        list.extend([V(1), V(2)]);
+        assert_eq!(list.pop_back(), Some(V(2)));
+        assert_eq!(list.length(), 1);
+
+        let list = SinglyLinkedList::from(vec![1, 2, 3]);
+        assert_eq!(list, SinglyLinkedList::from_vec(vec![1, 2, 3]));
+        let vec: Vec<_> = list.into();
+        assert_eq!(vec, vec![1, 2, 3]);
+        assert!(std::iter::empty::<i32>()
+            .collect::<SinglyLinkedList<_>>()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_drop_long_list() {
+        let list = (0..1_000_000).collect::<SinglyLinkedList<_>>();
+        assert_eq!(list.length(), 1_000_000);
+        drop(list);
+
+        // Clones and comparisons do not recurse either.
+        let list = (0..1_000_000)
+            .map(|n| n.to_string())
+            .collect::<SinglyLinkedList<_>>();
+        let copy = list.clone();
+        assert_eq!(list, copy);
+    }
+
+    #[test]
+    fn test_retain_dedup() {
+        let mut list = SinglyLinkedList::from_vec((1..=10).collect());
+        list.retain(|x| x % 3 != 0);
+        assert_eq!(list.length(), 7);
+        assert_eq!(list.to_string(), "[1 -> 2 -> 4 -> 5 -> 7 -> 8 -> 10]");
+        list.retain(|x| *x > 4);
+        assert_eq!(list.to_string(), "[5 -> 7 -> 8 -> 10]");
+        list.push_back(11);
+        assert_eq!(list.length(), 5);
+        list.retain(|_| false);
+        assert!(list.is_empty());
+        assert_eq!(list.length(), 0);
+
+        let mut list = SinglyLinkedList::from_vec(vec![1, 1, 2, 1, 3, 3, 3]);
+        list.dedup();
+        assert_eq!(list, SinglyLinkedList::from_vec(vec![1, 2, 1, 3]));
+        assert_eq!(list.length(), 4);
+        assert_eq!(list.pop_back(), Some(3));
+
+        let mut list = SinglyLinkedList::<V>::new();
+        list.dedup();
+        assert!(list.is_empty());
+        let mut list = SinglyLinkedList::from_vec((0..100).map(|_| V(7)).collect());
+        list.dedup();
+        assert_eq!(list.into_vec(), vec![V(7)]);
+    }
+
+    #[test]
+    fn test_front_back() {
+        let mut list = SinglyLinkedList::new();
+        assert_eq!(list.front(), None);
+        assert_eq!(list.back(), None);
+        assert_eq!(list.front_mut(), None);
+        assert_eq!(list.back_mut(), None);
+
+        list.push_back(V(1));
+        assert_eq!(list.front(), Some(&V(1)));
+        assert_eq!(list.back(), Some(&V(1)));
+
+        list.push_back(V(2));
+        list.push_back(V(3));
+        assert_eq!(list.front(), Some(&V(1)));
+        assert_eq!(list.back(), Some(&V(3)));
+
+        *list.front_mut().unwrap() = V(10);
+        list.back_mut().unwrap().0 += 20;
+        assert_eq!(list.length(), 3);
+        assert_eq!(list.into_vec(), vec![V(10), V(2), V(23)]);
+    }
+
+    #[test]
+    fn test_pair_map_clones() {
+        use std::cell::Cell;
+
+        // Counts the clones.
+        #[derive(Debug)]
+        struct C<'a>(i32, &'a Cell<usize>);
+
+        impl Clone for C<'_> {
+            fn clone(&self) -> Self {
+                self.1.set(self.1.get() + 1);
+                Self(self.0, self.1)
+            }
+        }
+
+        let clones = Cell::new(0);
+        let list = SinglyLinkedList::from_vec((1..=5).map(|n| C(n, &clones)).collect());
+        let list = list.pair_map(|x, y| C(x.0 * y.0, x.1));
+        assert_eq!(clones.get(), 4);
+        assert_eq!(list.length(), 4);
+        assert_eq!(
+            list.into_vec().iter().map(|c| c.0).collect::<Vec<_>>(),
+            vec![2, 6, 12, 20]
+        );
+
+        let list = SinglyLinkedList::from_vec(vec![1]).pair_map(|x, y| x + y);
+        assert_eq!(list.into_vec(), vec![1]);
+    }
+
+    #[test]
+    fn test_append() {
+        let mut list1 = SinglyLinkedList::from_vec(vec![1, 2]);
+        let mut list2 = SinglyLinkedList::from_vec(vec![3, 4, 5]);
+        list1.append(&mut list2);
+        assert_eq!(list1.length(), 5);
+        assert!(list2.is_empty());
+        assert_eq!(list2.length(), 0);
+
+        list1.append(&mut list2);
+        list2.append(&mut list1);
+        assert!(list1.is_empty());
+        list2.push_back(6);
+        assert_eq!(list2.into_vec(), vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_flatten_oracle() {
+        // The previous implementation, which goes through vectors.
+        fn flatten_oracle(lists: Vec<Vec<usize>>) -> Vec<usize> {
+            let mut vec = Vec::new();
+            for list in lists {
+                vec.extend(SinglyLinkedList::from_vec(list).into_vec());
+            }
+            SinglyLinkedList::from_vec(vec).into_vec()
+        }
+
+        for n in 0..20 {
+            let lists = (0..n)
+                .map(|i| (0..(i * 7) % 5).map(|j| i * 10 + j).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            let list_list = lists
+                .iter()
+                .cloned()
+                .map(SinglyLinkedList::from_vec)
+                .collect::<SinglyLinkedList<_>>();
+
+            let mut flattened = list_list.flatten();
+            assert_eq!(
+                flattened.length(),
+                lists.iter().map(Vec::len).sum::<usize>()
+            );
+            flattened.push_back(0);
+            assert_eq!(flattened.pop_back(), Some(0));
+            assert_eq!(flattened.into_vec(), flatten_oracle(lists));
+        }
+    }
+
+    #[test]
+    fn test_rotate() {
+        let mut list = SinglyLinkedList::from_vec(vec![1, 2, 3, 4, 5]);
+        list.rotate_left(2);
+        assert_eq!(list.to_string(), "[3 -> 4 -> 5 -> 1 -> 2]");
+        list.rotate_right(2);
+        assert_eq!(list.to_string(), "[1 -> 2 -> 3 -> 4 -> 5]");
+        list.rotate_right(7);
+        assert_eq!(list.to_string(), "[4 -> 5 -> 1 -> 2 -> 3]");
+        list.rotate_left(13);
+        assert_eq!(list.to_string(), "[2 -> 3 -> 4 -> 5 -> 1]");
+        list.rotate_left(5);
+        list.rotate_right(0);
+        assert_eq!(list.to_string(), "[2 -> 3 -> 4 -> 5 -> 1]");
+        assert_eq!(list.length(), 5);
+        list.push_back(6);
+        assert_eq!(list.back(), Some(&6));
+
+        let mut list = SinglyLinkedList::<i32>::new();
+        list.rotate_left(3);
+        list.rotate_right(3);
+        assert!(list.is_empty());
+
+        let mut list = SinglyLinkedList::from_vec(vec![V(1)]);
+        list.rotate_right(usize::MAX);
+        assert_eq!(list.into_vec(), vec![V(1)]);
+    }
+
+    #[test]
+    fn test_get_index() {
+        let mut list = SinglyLinkedList::from_vec(vec![V(1), V(2), V(3)]);
+        assert_eq!(list.get(0), Some(&V(1)));
+        assert_eq!(list.get(2), Some(&V(3)));
+        assert_eq!(list.get(3), None);
+        assert_eq!(list.get_mut(3), None);
+
+        list.get_mut(1).unwrap().0 = 20;
+        assert_eq!(list[1], V(20));
+        list[2] = V(30);
+        list[0].0 += 9;
+        assert_eq!(list.into_vec(), vec![V(10), V(20), V(30)]);
+
+        assert_eq!(SinglyLinkedList::<i32>::new().get(0), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds: the len is 3 but the index is 3")]
+    fn test_index_out_of_range() {
+        let list = SinglyLinkedList::from_vec(vec![1, 2, 3]);
+        let _ = list[3];
+    }
 }
@@ -4,21 +4,161 @@
 //!
 //! Refer `mock_storage_grade.rs` for test cases.
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
+use std::fmt;
+use std::mem;
+use std::time::Instant;
 
 /// Mock storage.
 #[derive(Debug)]
 pub struct MockStorage {
     /// Files stored in the storage.
     ///
-    /// Each entry of the hashmap represents the `(name, size)` of the file.
-    files: RefCell<HashMap<String, usize>>,
+    /// Each entry of the hashmap represents the name and the file.
+    files: RefCell<HashMap<String, File>>,
+
+    /// Buckets of users, which are namespaces separate from `files` and each other.
+    users: RefCell<HashMap<String, Bucket>>,
+
+    /// Logical clock, which ticks on each upload and download.
+    clock: Cell<u64>,
 
     /// Capacity of the storage.
     ///
     /// The total size of files stored on the storage cannot exceed the capacity.
-    capacity: usize,
+    capacity: Cell<usize>,
+
+    /// Whether to evict the least recently used files rather than rejecting uploads that exceed the
+    /// capacity.
+    eviction: bool,
+
+    /// Observers registered with `on_event`.
+    listeners: Listeners,
+
+    /// Events not yet delivered to the observers.
+    ///
+    /// They are delivered once the storage is no longer borrowed, so that observers may access it.
+    events: RefCell<Vec<StorageEvent>>,
+}
+
+/// Observer of a storage.
+type Listener = Box<dyn Fn(StorageEvent)>;
+
+/// Observers of a storage.
+struct Listeners(RefCell<Vec<Listener>>);
+
+impl fmt::Debug for Listeners {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Listeners({})", self.0.borrow().len())
+    }
+}
+
+/// Change of a storage, or a rejected attempt to change it.
+///
+/// `user` is the owner of the file for the files uploaded with `MockStorage::upload_as`, and `None`
+/// for the shared ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageEvent {
+    /// A new file is uploaded.
+    Upload {
+        /// Owner of the file.
+        user: Option<String>,
+        /// Name of the file.
+        name: String,
+        /// Size of the file.
+        size: usize,
+    },
+    /// An existing file is overwritten.
+    Overwrite {
+        /// Owner of the file.
+        user: Option<String>,
+        /// Name of the file.
+        name: String,
+        /// Size of the file before overwritten.
+        old_size: usize,
+        /// Size of the file after overwritten.
+        size: usize,
+    },
+    /// A file is deleted or evicted.
+    Delete {
+        /// Owner of the file.
+        user: Option<String>,
+        /// Name of the file.
+        name: String,
+        /// Size of the file.
+        size: usize,
+    },
+    /// An upload is rejected. If a batch is rejected, each file of it is rejected with the error
+    /// of the whole batch.
+    Rejection {
+        /// Owner of the file.
+        user: Option<String>,
+        /// Name of the file.
+        name: String,
+        /// Size of the file.
+        size: usize,
+        /// Reason of the rejection.
+        error: UploadError,
+    },
+}
+
+/// Metadata of a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Metadata {
+    /// Size of the file.
+    pub size: usize,
+    /// Time of the last upload, by the logical clock of the storage which starts from 1.
+    pub uploaded_at: u64,
+    /// Time of the last access, i.e., upload or download, by the logical clock of the storage.
+    pub accessed_at: u64,
+    /// Number of times the file has been uploaded, i.e., 1 for a new file.
+    pub version: usize,
+    /// Whether the file cannot be overwritten.
+    pub read_only: bool,
+}
+
+/// File stored in a storage.
+#[derive(Debug)]
+struct File {
+    metadata: Metadata,
+    /// Content of the file, whose length is the size of the file.
+    data: Vec<u8>,
+}
+
+/// Files and quota of a user.
+#[derive(Debug, Default)]
+struct Bucket {
+    files: HashMap<String, File>,
+    /// Limit on the total size of the files, if any.
+    quota: Option<usize>,
+}
+
+/// Error of uploading a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UploadError {
+    /// There is not enough free space, short by the given size.
+    NoSpace(usize),
+    /// The quota of the user is exceeded by the given size.
+    QuotaExceeded(usize),
+    /// The file to overwrite is read-only.
+    ReadOnly,
+    /// The upload is interrupted by a permanent failure, or too many transient failures.
+    Interrupted,
+}
+
+/// Error of uploading files at once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchError {
+    /// There is not enough free space for all files, short by the given size.
+    NoSpace(usize),
+    /// The file with the given name to overwrite is read-only.
+    ReadOnly(String),
+}
+
+/// Total size of `files`.
+fn total_size(files: &HashMap<String, File>) -> usize {
+    files.values().map(|file| file.metadata.size).sum()
 }
 
 impl MockStorage {
@@ -26,87 +166,666 @@ impl MockStorage {
     pub fn new(capacity: usize) -> Self {
         Self {
             files: RefCell::new(HashMap::new()),
-            capacity,
+            users: RefCell::new(HashMap::new()),
+            clock: Cell::new(0),
+            capacity: Cell::new(capacity),
+            eviction: false,
+            listeners: Listeners(RefCell::new(Vec::new())),
+            events: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Registers an observer, which is called with each event of the storage from now on.
+    ///
+    /// Observers are called after the storage is updated, in the order of registration. They may
+    /// access the storage, but must not register another observer.
+    pub fn on_event(&self, listener: Box<dyn Fn(StorageEvent)>) {
+        self.listeners.0.borrow_mut().push(listener);
+    }
+
+    /// Creates a new mock storage that evicts the least recently used shared files to make room
+    /// for uploads, rather than rejecting them. See `upload_evicting`.
+    pub fn with_eviction(capacity: usize) -> Self {
+        Self {
+            eviction: true,
+            ..Self::new(capacity)
+        }
+    }
+
+    /// Sets the capacity of the storage.
+    ///
+    /// Returns `Err` with the excess size if the files already exceed the new capacity, in which
+    /// case the capacity is not changed.
+    pub fn set_capacity(&self, capacity: usize) -> Result<(), usize> {
+        let used = self.used();
+        if used > capacity {
+            return Err(used - capacity);
+        }
+        self.capacity.set(capacity);
+        Ok(())
+    }
+
+    /// Uploads a shared file as `Storage::upload`, and returns the names of the evicted files from
+    /// the least recently used.
+    ///
+    /// If the storage is created with `with_eviction` and the file does not fit, the least recently
+    /// used shared files are evicted until it fits. Read-only files, the files of users and the
+    /// file being overwritten are never evicted. If the file does not fit even after evicting all
+    /// of them, nothing is evicted and `Err(UploadError::NoSpace)` is returned with the size it is
+    /// still short by.
+    pub fn upload_evicting(&self, name: &str, size: usize) -> Result<Vec<String>, UploadError> {
+        self.upload_bytes_evicting(name, &vec![0; size])
+    }
+
+    /// Uploads a shared file with the given content as `upload_evicting`.
+    pub fn upload_bytes_evicting(
+        &self,
+        name: &str,
+        data: &[u8],
+    ) -> Result<Vec<String>, UploadError> {
+        let result = self.evict_and_store(name, data);
+        self.notify();
+        result
+    }
+
+    /// Does `upload_bytes_evicting` without notifying the observers.
+    fn evict_and_store(&self, name: &str, data: &[u8]) -> Result<Vec<String>, UploadError> {
+        let size = data.len();
+        let used = self.used();
+        let mut files = self.files.borrow_mut();
+        let old = files.get(name).map(|file| file.metadata);
+        if !self.eviction || old.is_some_and(|old| old.read_only) {
+            return self
+                .store(&mut files, used, None, None, name, data)
+                .map(|()| Vec::new());
+        }
+
+        let needed = (used - old.map_or(0, |old| old.size) + size).saturating_sub(self.capacity());
+        let mut candidates = files
+            .iter()
+            .map(|(file, stored)| (file, stored.metadata))
+            .filter(|(file, metadata)| *file != name && !metadata.read_only)
+            .map(|(file, metadata)| (metadata.accessed_at, metadata.size, file.clone()))
+            .collect::<Vec<_>>();
+        candidates.sort();
+
+        let mut freed = 0;
+        let mut evicted = Vec::new();
+        for (_, size, file) in candidates {
+            if freed >= needed {
+                break;
+            }
+            freed += size;
+            evicted.push(file);
+        }
+        if freed < needed {
+            let error = UploadError::NoSpace(needed - freed);
+            self.record(StorageEvent::Rejection {
+                user: None,
+                name: name.to_string(),
+                size,
+                error,
+            });
+            return Err(error);
+        }
+        for file in &evicted {
+            if let Some(removed) = files.remove(file) {
+                self.record(StorageEvent::Delete {
+                    user: None,
+                    name: file.clone(),
+                    size: removed.metadata.size,
+                });
+            }
+        }
+        self.store(&mut files, used - freed, None, None, name, data)?;
+        Ok(evicted)
+    }
+
+    /// Returns the metadata of the file with the given name, if any.
+    pub fn metadata(&self, name: &str) -> Option<Metadata> {
+        self.files.borrow().get(name).map(|file| file.metadata)
+    }
+
+    /// Makes the file with the given name read-only or not. Returns `false` if there is no such
+    /// file.
+    pub fn set_read_only(&self, name: &str, read_only: bool) -> bool {
+        match self.files.borrow_mut().get_mut(name) {
+            Some(file) => {
+                file.metadata.read_only = read_only;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Uploads shared files as `Storage::upload`, either all or none of them. If a name appears
+    /// more than once, the last one wins.
+    ///
+    /// The files are validated up front: returns `Err(BatchError::ReadOnly)` if any of them would
+    /// overwrite a read-only file, and `Err(BatchError::NoSpace)` with the insufficient size if
+    /// they do not fit altogether. Nothing is evicted, even with `with_eviction`.
+    pub fn upload_batch(&self, files: &[(&str, usize)]) -> Result<(), BatchError> {
+        let result = self.store_batch(files);
+        if let Err(error) = &result {
+            let error = match error {
+                BatchError::NoSpace(size) => UploadError::NoSpace(*size),
+                BatchError::ReadOnly(_) => UploadError::ReadOnly,
+            };
+            for (name, size) in files {
+                self.record(StorageEvent::Rejection {
+                    user: None,
+                    name: name.to_string(),
+                    size: *size,
+                    error,
+                });
+            }
+        }
+        self.notify();
+        result
+    }
+
+    /// Does `upload_batch` without notifying the observers.
+    fn store_batch(&self, files: &[(&str, usize)]) -> Result<(), BatchError> {
+        let used = self.used();
+        let mut stored = self.files.borrow_mut();
+
+        let mut sizes = HashMap::new();
+        for (name, size) in files {
+            if stored.get(*name).is_some_and(|old| old.metadata.read_only) {
+                return Err(BatchError::ReadOnly(name.to_string()));
+            }
+            let _unused = sizes.insert(*name, *size);
+        }
+        let new = sizes.iter().fold(used, |new, (name, size)| {
+            new - stored.get(*name).map_or(0, |old| old.metadata.size) + size
+        });
+        if new > self.capacity() {
+            return Err(BatchError::NoSpace(new - self.capacity()));
+        }
+
+        for (name, size) in files {
+            self.insert(&mut stored, None, name, &vec![0; *size]);
+        }
+        Ok(())
+    }
+
+    /// Uploads a file to the bucket of `user`, as `Storage::upload`. The files of a user are
+    /// separate from those of the others and the shared ones uploaded with `Storage::upload`, even
+    /// with the same name.
+    ///
+    /// Returns `Err(UploadError::QuotaExceeded)` with the excess size if the quota of the user
+    /// would be exceeded. The global capacity is enforced as well.
+    pub fn upload_as(&self, user: &str, name: &str, size: usize) -> Result<(), UploadError> {
+        let used = self.used();
+        let result = {
+            let mut users = self.users.borrow_mut();
+            let bucket = users.entry(user.to_string()).or_default();
+            self.store(
+                &mut bucket.files,
+                used,
+                bucket.quota,
+                Some(user),
+                name,
+                &vec![0; size],
+            )
+        };
+        self.notify();
+        result
+    }
+
+    /// Returns the total size of the files of `user`.
+    pub fn used_by(&self, user: &str) -> usize {
+        self.users
+            .borrow()
+            .get(user)
+            .map_or(0, |bucket| total_size(&bucket.files))
+    }
+
+    /// Returns the quota of `user`, if any.
+    pub fn quota(&self, user: &str) -> Option<usize> {
+        self.users.borrow().get(user)?.quota
+    }
+
+    /// Sets the quota of `user`.
+    ///
+    /// Returns `Err` with the excess size if the files of the user already exceed the quota.
+    pub fn set_quota(&self, user: &str, quota: usize) -> Result<(), usize> {
+        let mut users = self.users.borrow_mut();
+        let bucket = users.entry(user.to_string()).or_default();
+        let used = total_size(&bucket.files);
+        if used > quota {
+            return Err(used - quota);
+        }
+        bucket.quota = Some(quota);
+        Ok(())
+    }
+
+    /// Removes the quota of `user`.
+    pub fn remove_quota(&self, user: &str) {
+        if let Some(bucket) = self.users.borrow_mut().get_mut(user) {
+            bucket.quota = None;
+        }
+    }
+
+    /// Stores a file in `files`, which is the namespace the file belongs to. `used` is the used
+    /// size of the whole storage, `quota` is that of the namespace if any, and `user` is the owner
+    /// of the namespace if any.
+    fn store(
+        &self,
+        files: &mut HashMap<String, File>,
+        used: usize,
+        quota: Option<usize>,
+        user: Option<&str>,
+        name: &str,
+        data: &[u8],
+    ) -> Result<(), UploadError> {
+        let result = Self::check(files, used, quota, self.capacity(), name, data.len());
+        match result {
+            Ok(()) => self.insert(files, user, name, data),
+            Err(error) => self.record(StorageEvent::Rejection {
+                user: user.map(str::to_string),
+                name: name.to_string(),
+                size: data.len(),
+                error,
+            }),
         }
+        result
     }
+
+    /// Checks if a file can be stored in `files`, as `store`.
+    fn check(
+        files: &HashMap<String, File>,
+        used: usize,
+        quota: Option<usize>,
+        capacity: usize,
+        name: &str,
+        size: usize,
+    ) -> Result<(), UploadError> {
+        let old = files.get(name).map(|file| file.metadata);
+        if old.is_some_and(|old| old.read_only) {
+            return Err(UploadError::ReadOnly);
+        }
+        let old_size = old.map_or(0, |old| old.size);
+
+        if let Some(quota) = quota {
+            let new = total_size(files) - old_size + size;
+            if new > quota {
+                return Err(UploadError::QuotaExceeded(new - quota));
+            }
+        }
+        let new = used - old_size + size;
+        if new > capacity {
+            return Err(UploadError::NoSpace(new - capacity));
+        }
+        Ok(())
+    }
+
+    /// Inserts a file in `files` without any checks.
+    fn insert(
+        &self,
+        files: &mut HashMap<String, File>,
+        user: Option<&str>,
+        name: &str,
+        data: &[u8],
+    ) {
+        let size = data.len();
+        let old = files.get(name).map(|file| file.metadata);
+        let user = user.map(str::to_string);
+        self.record(match old {
+            Some(old) => StorageEvent::Overwrite {
+                user,
+                name: name.to_string(),
+                old_size: old.size,
+                size,
+            },
+            None => StorageEvent::Upload {
+                user,
+                name: name.to_string(),
+                size,
+            },
+        });
+        let now = self.tick();
+        let _unused = files.insert(
+            name.to_string(),
+            File {
+                metadata: Metadata {
+                    size,
+                    uploaded_at: now,
+                    accessed_at: now,
+                    version: old.map_or(0, |old| old.version) + 1,
+                    read_only: false,
+                },
+                data: data.to_vec(),
+            },
+        );
+    }
+
+    /// Advances the clock, and returns the new time.
+    fn tick(&self) -> u64 {
+        self.clock.set(self.clock.get() + 1);
+        self.clock.get()
+    }
+
+    /// Records an event to be delivered by `notify`.
+    fn record(&self, event: StorageEvent) {
+        self.events.borrow_mut().push(event);
+    }
+
+    /// Delivers the recorded events to the observers. The storage must not be borrowed.
+    fn notify(&self) {
+        let events = mem::take(&mut *self.events.borrow_mut());
+        let listeners = self.listeners.0.borrow();
+        for event in events {
+            for listener in listeners.iter() {
+                listener(event.clone());
+            }
+        }
+    }
+}
+
+/// Order of files listed by [`Storage::list`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortBy {
+    /// By name.
+    Name,
+    /// By size, and then by name for files of the same size, as `du_sort` of assignment 10.
+    Size,
 }
 
 /// Trait for storage object.
 pub trait Storage {
-    /// Uploads a file. If a file with the same name already exists in the storage, overwrite it.
+    /// Uploads a file with the given content, whose length is the size of the file. If a file with
+    /// the same name already exists in the storage, overwrite it.
     ///
-    /// Returns `Err` with insufficient memory size if there is no free space to upload a file.
-    fn upload(&self, name: &str, size: usize) -> Result<(), usize>;
+    /// Returns `Err(UploadError::NoSpace)` with insufficient memory size if there is no free space
+    /// to upload a file, and `Err(UploadError::ReadOnly)` if the file to overwrite is read-only.
+    fn upload_bytes(&self, name: &str, data: &[u8]) -> Result<(), UploadError>;
+
+    /// Uploads a file of the given size filled with zeros, as `upload_bytes`.
+    fn upload(&self, name: &str, size: usize) -> Result<(), UploadError> {
+        self.upload_bytes(name, &vec![0; size])
+    }
+
+    /// Returns the content of the file with the given name, if any.
+    fn download(&self, name: &str) -> Option<Vec<u8>>;
+
+    /// Deletes the file with the given name. Returns `false` if there is no such file or it cannot
+    /// be deleted.
+    fn delete(&self, name: &str) -> bool;
 
     /// Returns the used memory size of the storage.
     fn used(&self) -> usize;
 
     /// Returns the capacity of the storage.
     fn capacity(&self) -> usize;
+
+    /// Returns the `(name, size)` of the files in the storage in the given order.
+    fn list(&self, sort_by: SortBy) -> Vec<(String, usize)>;
+
+    /// Returns `true` if a file with the given name is in the storage.
+    fn contains(&self, name: &str) -> bool;
 }
 
 impl Storage for MockStorage {
-    fn upload(&self, name: &str, size: usize) -> Result<(), usize> {
-        let mut files = self.files.borrow_mut();
+    fn upload_bytes(&self, name: &str, data: &[u8]) -> Result<(), UploadError> {
+        self.upload_bytes_evicting(name, data).map(|_| ())
+    }
 
-        let len: usize = files.values().sum();
-        let old: usize = files.get(name).copied().unwrap_or(0);
-        let new = len - old + size;
+    /// Returns the content of the shared file with the given name, and updates the access time of
+    /// the file.
+    fn download(&self, name: &str) -> Option<Vec<u8>> {
+        let mut files = self.files.borrow_mut();
+        let file = files.get_mut(name)?;
+        file.metadata.accessed_at = self.tick();
+        Some(file.data.clone())
+    }
 
-        if new > self.capacity() {
-            Err(self.capacity - len)
-        } else {
-            let _unused = files.insert(name.to_string(), size);
-            Ok(())
-        }
+    /// Deletes the shared file with the given name. Read-only files cannot be deleted.
+    fn delete(&self, name: &str) -> bool {
+        let deleted = {
+            let mut files = self.files.borrow_mut();
+            match files.get(name) {
+                Some(file) if !file.metadata.read_only => {
+                    let size = file.metadata.size;
+                    let _unused = files.remove(name);
+                    self.record(StorageEvent::Delete {
+                        user: None,
+                        name: name.to_string(),
+                        size,
+                    });
+                    true
+                }
+                _ => false,
+            }
+        };
+        self.notify();
+        deleted
     }
 
     fn used(&self) -> usize {
-        self.files.borrow().values().sum()
+        let users = self.users.borrow();
+        total_size(&self.files.borrow())
+            + users
+                .values()
+                .map(|bucket| total_size(&bucket.files))
+                .sum::<usize>()
     }
 
     fn capacity(&self) -> usize {
-        self.capacity
+        self.capacity.get()
+    }
+
+    fn list(&self, sort_by: SortBy) -> Vec<(String, usize)> {
+        let mut files = self
+            .files
+            .borrow()
+            .iter()
+            .map(|(name, file)| (name.clone(), file.metadata.size))
+            .collect::<Vec<_>>();
+        match sort_by {
+            SortBy::Name => files.sort(),
+            SortBy::Size => files.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0))),
+        }
+        files
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.files.borrow().contains_key(name)
     }
 }
 
+/// Simulated failure of uploading a chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Failure {
+    /// The chunk may be uploaded if retried.
+    Transient,
+    /// The upload cannot proceed anymore.
+    Permanent,
+}
+
+/// Decides whether each attempt to upload a chunk fails, given the index of the chunk and the
+/// number of the previous attempts to upload it.
+pub type FailurePolicy<'a> = Box<dyn Fn(usize, usize) -> Option<Failure> + 'a>;
+
 /// File uploader.
 ///
 /// It uploads files to the internal storage.
-#[derive(Debug)]
 pub struct FileUploader<'a, T: Storage> {
     storage: &'a T,
+    failure_policy: FailurePolicy<'a>,
+    /// Maximum number of retries of a chunk after transient failures.
+    max_retries: usize,
+}
+
+impl<T: Storage + fmt::Debug> fmt::Debug for FileUploader<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileUploader")
+            .field("storage", &self.storage)
+            .field("max_retries", &self.max_retries)
+            .finish_non_exhaustive()
+    }
 }
 
 impl<'a, T: Storage> FileUploader<'a, T> {
     /// Creates a new file uploader with given internal storage.
     pub fn new(storage: &'a T) -> Self {
-        Self { storage }
+        Self {
+            storage,
+            failure_policy: Box::new(|_, _| None),
+            max_retries: 0,
+        }
+    }
+
+    /// Makes the chunks uploaded by `upload_chunked` fail as `failure_policy` decides, where each
+    /// chunk is retried up to `max_retries` times after transient failures.
+    pub fn with_failure_policy(
+        self,
+        failure_policy: impl Fn(usize, usize) -> Option<Failure> + 'a,
+        max_retries: usize,
+    ) -> Self {
+        Self {
+            failure_policy: Box::new(failure_policy),
+            max_retries,
+            ..self
+        }
     }
 
     /// Uploads a file to the internal storage.
-    pub fn upload(&self, name: &str, size: usize) -> Result<(), usize> {
+    pub fn upload(&self, name: &str, size: usize) -> Result<(), UploadError> {
         self.storage.upload(name, size)
     }
+
+    /// Uploads a file to the internal storage in chunks of the given size, which must be positive.
+    ///
+    /// A chunk that fails transiently is retried. If a chunk fails permanently or is rejected by
+    /// the storage, the upload is rolled back, i.e., the previous content of the file is restored
+    /// or the partially uploaded file is deleted, and the error is returned. Too many transient
+    /// failures of a chunk are regarded as a permanent one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk` is zero.
+    pub fn upload_chunked(&self, name: &str, size: usize, chunk: usize) -> Result<(), UploadError> {
+        assert!(chunk > 0, "chunk size must be positive");
+        let backup = self.storage.download(name);
+
+        // Uploads the file prefix by prefix, with at least one chunk even for an empty file.
+        let ends = (chunk..size).step_by(chunk).chain([size]);
+        for (index, end) in ends.enumerate() {
+            let mut attempt = 0;
+            let result = loop {
+                match (self.failure_policy)(index, attempt) {
+                    None => break self.storage.upload(name, end),
+                    Some(Failure::Transient) if attempt < self.max_retries => attempt += 1,
+                    Some(_) => break Err(UploadError::Interrupted),
+                }
+            };
+
+            if let Err(error) = result {
+                if index > 0 {
+                    self.roll_back(name, backup);
+                }
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    /// Restores the file to `backup`, i.e., its content before the upload if any.
+    fn roll_back(&self, name: &str, backup: Option<Vec<u8>>) {
+        match backup {
+            Some(data) => {
+                let _unused = self.storage.upload_bytes(name, &data);
+            }
+            None => {
+                let _unused = self.storage.delete(name);
+            }
+        }
+    }
+}
+
+/// Usage of a storage at some point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    /// When the sample is taken.
+    pub at: Instant,
+    /// Used size of the storage.
+    pub used: usize,
+    /// Capacity of the storage.
+    pub capacity: usize,
+}
+
+impl Sample {
+    /// Returns the ratio of the used size to the capacity.
+    pub fn usage(&self) -> f64 {
+        self.used as f64 / self.capacity as f64
+    }
 }
 
 /// Storage usage analyzer.
+///
+/// It records a sample of the usage each time the storage is queried, to report the trend.
 #[derive(Debug)]
 pub struct UsageAnalyzer<'a, T: Storage> {
     storage: &'a T,
     bound: f64,
+    samples: RefCell<Vec<Sample>>,
 }
 
 impl<'a, T: Storage> UsageAnalyzer<'a, T> {
     /// Creates a new usage analyzer.
     pub fn new(storage: &'a T, bound: f64) -> Self {
-        Self { storage, bound }
+        Self {
+            storage,
+            bound,
+            samples: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Records a sample of the current usage, and returns it.
+    pub fn sample(&self) -> Sample {
+        let sample = Sample {
+            at: Instant::now(),
+            used: self.storage.used(),
+            capacity: self.storage.capacity(),
+        };
+        self.samples.borrow_mut().push(sample);
+        sample
+    }
+
+    /// Returns the samples recorded so far, from the oldest.
+    pub fn samples(&self) -> Vec<Sample> {
+        self.samples.borrow().clone()
     }
 
     /// Returns `true` if the usage of the internal storage is under the bound.
     pub fn is_usage_under_bound(&self) -> bool {
-        let used = self.storage.used();
-        let capacity = self.storage.capacity();
-        used as f64 / capacity as f64 <= self.bound
+        self.sample().usage() <= self.bound
+    }
+
+    /// Returns the highest usage among the samples, or `None` if there is no sample.
+    pub fn peak_usage(&self) -> Option<f64> {
+        self.samples
+            .borrow()
+            .iter()
+            .map(Sample::usage)
+            .max_by(f64::total_cmp)
+    }
+
+    /// Returns the average usage of the samples, or `None` if there is no sample.
+    pub fn average_usage(&self) -> Option<f64> {
+        let samples = self.samples.borrow();
+        if samples.is_empty() {
+            return None;
+        }
+        Some(samples.iter().map(Sample::usage).sum::<f64>() / samples.len() as f64)
+    }
+
+    /// Returns `true` if the usage would exceed the bound after uploading `n_uploads` new files of
+    /// `avg_size` on average, assuming none of them overwrites an existing file.
+    pub fn will_exceed_bound_in(&self, n_uploads: usize, avg_size: usize) -> bool {
+        let sample = self.sample();
+        let used = sample.used + n_uploads * avg_size;
+        used as f64 / sample.capacity as f64 > self.bound
     }
 }
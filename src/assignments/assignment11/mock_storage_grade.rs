@@ -2,6 +2,9 @@
 
 #[cfg(test)]
 mod test_mock_storage {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
     use crate::assignments::assignment11::mock_storage::*;
 
     #[test]
@@ -22,10 +25,476 @@ mod test_mock_storage {
         assert!(uploader1.upload("file3.txt", 40).is_ok());
         assert!(!usage_analyzer.is_usage_under_bound());
 
-        assert_eq!(uploader2.upload("file4.txt", 50), Err(40));
+        assert_eq!(
+            uploader2.upload("file4.txt", 50),
+            Err(UploadError::NoSpace(40))
+        );
         assert!(!usage_analyzer.is_usage_under_bound());
 
         assert!(uploader1.upload("file3.txt", 10).is_ok());
         assert!(usage_analyzer.is_usage_under_bound());
     }
+
+    #[test]
+    fn test_list() {
+        let mock_storage = MockStorage::new(100);
+        let uploader = FileUploader::new(&mock_storage);
+        assert!(mock_storage.list(SortBy::Name).is_empty());
+
+        for (name, size) in [("c.txt", 10), ("a.txt", 30), ("d.txt", 10), ("b.txt", 20)] {
+            assert!(uploader.upload(name, size).is_ok());
+        }
+        assert!(uploader.upload("a.txt", 5).is_ok());
+        assert_eq!(uploader.upload("e.txt", 60), Err(UploadError::NoSpace(5)));
+
+        let files = |list: Vec<(String, usize)>| {
+            list.into_iter()
+                .map(|(name, size)| format!("{}:{}", name, size))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            files(mock_storage.list(SortBy::Name)),
+            ["a.txt:5", "b.txt:20", "c.txt:10", "d.txt:10"]
+        );
+        assert_eq!(
+            files(mock_storage.list(SortBy::Size)),
+            ["a.txt:5", "c.txt:10", "d.txt:10", "b.txt:20"]
+        );
+
+        assert!(mock_storage.contains("a.txt"));
+        assert!(!mock_storage.contains("e.txt"));
+        assert_eq!(
+            mock_storage
+                .list(SortBy::Size)
+                .iter()
+                .map(|(_, size)| size)
+                .sum::<usize>(),
+            mock_storage.used()
+        );
+    }
+
+    #[test]
+    fn test_metadata() {
+        let mock_storage = MockStorage::new(100);
+        let uploader = FileUploader::new(&mock_storage);
+        assert_eq!(mock_storage.metadata("a.txt"), None);
+
+        assert!(uploader.upload("a.txt", 10).is_ok());
+        assert!(uploader.upload("b.txt", 20).is_ok());
+        assert_eq!(
+            mock_storage.metadata("a.txt"),
+            Some(Metadata {
+                size: 10,
+                uploaded_at: 1,
+                accessed_at: 1,
+                version: 1,
+                read_only: false,
+            })
+        );
+
+        // Overwriting bumps the version, while failed uploads change nothing.
+        assert!(uploader.upload("a.txt", 30).is_ok());
+        assert!(uploader.upload("a.txt", 90).is_err());
+        let metadata = mock_storage.metadata("a.txt").unwrap();
+        assert_eq!(
+            (metadata.size, metadata.uploaded_at, metadata.version),
+            (30, 3, 2)
+        );
+
+        // Read-only files cannot be overwritten.
+        assert!(mock_storage.set_read_only("b.txt", true));
+        assert!(!mock_storage.set_read_only("c.txt", true));
+        assert_eq!(uploader.upload("b.txt", 5), Err(UploadError::ReadOnly));
+        assert_eq!(uploader.upload("b.txt", 500), Err(UploadError::ReadOnly));
+        assert_eq!(mock_storage.metadata("b.txt").unwrap().size, 20);
+        assert!(mock_storage.metadata("b.txt").unwrap().read_only);
+
+        assert!(mock_storage.set_read_only("b.txt", false));
+        assert!(uploader.upload("b.txt", 5).is_ok());
+        let metadata = mock_storage.metadata("b.txt").unwrap();
+        assert_eq!((metadata.version, metadata.read_only), (2, false));
+        assert_eq!(mock_storage.used(), 35);
+    }
+
+    #[test]
+    fn test_users() {
+        let mock_storage = MockStorage::new(100);
+        assert!(mock_storage.upload("a.txt", 10).is_ok());
+        assert!(mock_storage.upload_as("alice", "a.txt", 20).is_ok());
+        assert!(mock_storage.upload_as("bob", "a.txt", 30).is_ok());
+
+        // Namespaces are separate.
+        assert_eq!(mock_storage.used_by("alice"), 20);
+        assert_eq!(mock_storage.used_by("bob"), 30);
+        assert_eq!(mock_storage.used_by("carol"), 0);
+        assert_eq!(mock_storage.used(), 60);
+        assert_eq!(mock_storage.metadata("a.txt").unwrap().size, 10);
+        assert_eq!(mock_storage.list(SortBy::Name).len(), 1);
+
+        // Quotas are enforced per user, as well as the capacity.
+        assert_eq!(mock_storage.quota("alice"), None);
+        assert_eq!(mock_storage.set_quota("alice", 15), Err(5));
+        assert!(mock_storage.set_quota("alice", 25).is_ok());
+        assert_eq!(mock_storage.quota("alice"), Some(25));
+        assert_eq!(
+            mock_storage.upload_as("alice", "b.txt", 10),
+            Err(UploadError::QuotaExceeded(5))
+        );
+        assert!(mock_storage.upload_as("alice", "a.txt", 25).is_ok());
+        assert!(mock_storage.upload_as("alice", "a.txt", 5).is_ok());
+        assert!(mock_storage.upload_as("alice", "b.txt", 20).is_ok());
+        assert_eq!(
+            mock_storage.upload_as("bob", "b.txt", 40),
+            Err(UploadError::NoSpace(5))
+        );
+        assert_eq!(
+            mock_storage.upload("b.txt", 40),
+            Err(UploadError::NoSpace(5))
+        );
+
+        mock_storage.remove_quota("alice");
+        assert_eq!(mock_storage.quota("alice"), None);
+        assert!(mock_storage.upload_as("alice", "c.txt", 35).is_ok());
+        assert_eq!(mock_storage.used_by("alice"), 60);
+        assert_eq!(mock_storage.used(), mock_storage.capacity());
+
+        // Quotas may be set before the user uploads anything.
+        assert!(mock_storage.set_quota("carol", 0).is_ok());
+        assert_eq!(
+            mock_storage.upload_as("carol", "a.txt", 1),
+            Err(UploadError::QuotaExceeded(1))
+        );
+        assert!(mock_storage.upload_as("carol", "a.txt", 0).is_ok());
+    }
+
+    #[test]
+    fn test_eviction() {
+        let mock_storage = MockStorage::with_eviction(100);
+        for (name, size) in [("a", 30), ("b", 30), ("c", 30)] {
+            assert_eq!(mock_storage.upload_evicting(name, size), Ok(vec![]));
+        }
+
+        // Evicts the least recently used files, touched by uploads.
+        assert!(mock_storage.upload("a", 30).is_ok());
+        assert_eq!(
+            mock_storage.upload_evicting("d", 40),
+            Ok(vec!["b".to_string()])
+        );
+        assert_eq!(
+            mock_storage.upload_evicting("e", 50),
+            Ok(vec!["c".to_string(), "a".to_string()])
+        );
+        assert_eq!(mock_storage.list(SortBy::Name).len(), 2);
+        assert_eq!(mock_storage.used(), 90);
+
+        // Overwriting frees the old file first.
+        assert_eq!(mock_storage.upload_evicting("e", 60), Ok(vec![]));
+        assert_eq!(mock_storage.used(), 100);
+
+        // Read-only files and the files of users are kept.
+        assert!(mock_storage.set_read_only("d", true));
+        assert!(mock_storage.upload_as("alice", "a", 0).is_ok());
+        assert_eq!(
+            mock_storage.upload_evicting("f", 80),
+            Err(UploadError::NoSpace(20))
+        );
+        assert!(mock_storage.contains("e"));
+        assert_eq!(
+            mock_storage.upload_evicting("f", 60),
+            Ok(vec!["e".to_string()])
+        );
+        assert_eq!(
+            mock_storage.upload_as("alice", "b", 1),
+            Err(UploadError::NoSpace(1))
+        );
+        assert_eq!(mock_storage.upload("d", 1), Err(UploadError::ReadOnly));
+
+        // Without eviction, uploads that do not fit are rejected.
+        let mock_storage = MockStorage::new(100);
+        assert_eq!(mock_storage.upload_evicting("a", 60), Ok(vec![]));
+        assert_eq!(
+            mock_storage.upload_evicting("b", 60),
+            Err(UploadError::NoSpace(20))
+        );
+    }
+
+    #[test]
+    fn test_upload_batch() {
+        let mock_storage = MockStorage::new(100);
+        assert!(mock_storage.upload("a", 50).is_ok());
+        assert!(mock_storage.upload("b", 10).is_ok());
+
+        // Fails as a whole, even if some of the files fit.
+        assert_eq!(
+            mock_storage.upload_batch(&[("c", 20), ("d", 30)]),
+            Err(BatchError::NoSpace(10))
+        );
+        assert_eq!(mock_storage.used(), 60);
+        assert!(!mock_storage.contains("c"));
+
+        // Validates the final sizes rather than each step.
+        assert!(mock_storage
+            .upload_batch(&[("c", 40), ("a", 0), ("d", 30), ("d", 50)])
+            .is_ok());
+        assert_eq!(mock_storage.used(), 100);
+        assert_eq!(
+            mock_storage.list(SortBy::Name),
+            [
+                ("a".to_string(), 0),
+                ("b".to_string(), 10),
+                ("c".to_string(), 40),
+                ("d".to_string(), 50)
+            ]
+        );
+        assert_eq!(mock_storage.metadata("d").unwrap().version, 2);
+
+        assert!(mock_storage.set_read_only("b", true));
+        assert_eq!(
+            mock_storage.upload_batch(&[("a", 10), ("b", 0)]),
+            Err(BatchError::ReadOnly("b".to_string()))
+        );
+        assert_eq!(mock_storage.metadata("a").unwrap().size, 0);
+        assert!(mock_storage.upload_batch(&[]).is_ok());
+    }
+
+    #[test]
+    fn test_events() {
+        let mock_storage = Rc::new(MockStorage::with_eviction(100));
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let usages = Rc::new(RefCell::new(Vec::new()));
+        {
+            let events = Rc::clone(&events);
+            mock_storage.on_event(Box::new(move |event| events.borrow_mut().push(event)));
+        }
+        {
+            // Observers may access the storage.
+            let storage = Rc::downgrade(&mock_storage);
+            let usages = Rc::clone(&usages);
+            mock_storage.on_event(Box::new(move |_| {
+                usages.borrow_mut().push(storage.upgrade().unwrap().used())
+            }));
+        }
+        assert!(mock_storage.upload("a", 30).is_ok());
+        assert!(mock_storage.upload("a", 40).is_ok());
+        assert!(mock_storage.upload_as("u", "a", 10).is_ok());
+        assert!(mock_storage.set_read_only("a", true));
+        assert_eq!(mock_storage.upload("a", 0), Err(UploadError::ReadOnly));
+        assert!(mock_storage.set_read_only("a", false));
+        assert!(mock_storage.upload("b", 50).is_ok());
+        assert_eq!(
+            mock_storage.upload_evicting("c", 20),
+            Ok(vec!["a".to_string()])
+        );
+        assert!(!mock_storage.delete("a"));
+        assert!(mock_storage.delete("c"));
+        assert!(mock_storage.upload_batch(&[("d", 100)]).is_err());
+
+        assert_eq!(
+            *events.borrow(),
+            [
+                StorageEvent::Upload {
+                    user: None,
+                    name: "a".to_string(),
+                    size: 30
+                },
+                StorageEvent::Overwrite {
+                    user: None,
+                    name: "a".to_string(),
+                    old_size: 30,
+                    size: 40
+                },
+                StorageEvent::Upload {
+                    user: Some("u".to_string()),
+                    name: "a".to_string(),
+                    size: 10
+                },
+                StorageEvent::Rejection {
+                    user: None,
+                    name: "a".to_string(),
+                    size: 0,
+                    error: UploadError::ReadOnly
+                },
+                StorageEvent::Upload {
+                    user: None,
+                    name: "b".to_string(),
+                    size: 50
+                },
+                StorageEvent::Delete {
+                    user: None,
+                    name: "a".to_string(),
+                    size: 40
+                },
+                StorageEvent::Upload {
+                    user: None,
+                    name: "c".to_string(),
+                    size: 20
+                },
+                StorageEvent::Delete {
+                    user: None,
+                    name: "c".to_string(),
+                    size: 20
+                },
+                StorageEvent::Rejection {
+                    user: None,
+                    name: "d".to_string(),
+                    size: 100,
+                    error: UploadError::NoSpace(60)
+                },
+            ]
+        );
+        assert_eq!(*usages.borrow(), [30, 40, 50, 50, 100, 80, 80, 60, 60]);
+    }
+
+    #[test]
+    fn test_set_capacity() {
+        let mock_storage = MockStorage::new(100);
+        let analyzer = UsageAnalyzer::new(&mock_storage, 0.5);
+        assert!(mock_storage.upload("a", 60).is_ok());
+        assert!(mock_storage.upload_as("u", "a", 20).is_ok());
+        assert!(!analyzer.is_usage_under_bound());
+
+        assert_eq!(mock_storage.set_capacity(200), Ok(()));
+        assert_eq!(mock_storage.capacity(), 200);
+        assert!(analyzer.is_usage_under_bound());
+        assert!(mock_storage.upload("b", 120).is_ok());
+
+        // Cannot shrink below the used size.
+        assert_eq!(mock_storage.set_capacity(150), Err(50));
+        assert_eq!(mock_storage.capacity(), 200);
+        assert_eq!(mock_storage.set_capacity(200), Ok(()));
+
+        assert!(mock_storage.upload("b", 20).is_ok());
+        assert_eq!(mock_storage.set_capacity(100), Ok(()));
+        assert_eq!(mock_storage.upload("c", 1), Err(UploadError::NoSpace(1)));
+        assert!(!analyzer.is_usage_under_bound());
+    }
+
+    #[test]
+    fn test_usage_history() {
+        let mock_storage = MockStorage::new(100);
+        let analyzer = UsageAnalyzer::new(&mock_storage, 0.5);
+        assert_eq!(analyzer.peak_usage(), None);
+        assert_eq!(analyzer.average_usage(), None);
+
+        assert!(mock_storage.upload("a", 20).is_ok());
+        assert!(analyzer.is_usage_under_bound());
+        assert!(mock_storage.upload("b", 40).is_ok());
+        assert_eq!(analyzer.sample().used, 60);
+        assert!(mock_storage.upload("b", 0).is_ok());
+        assert!(!analyzer.will_exceed_bound_in(3, 10));
+        assert!(analyzer.will_exceed_bound_in(4, 10));
+
+        let samples = analyzer.samples();
+        assert_eq!(
+            samples.iter().map(|sample| sample.used).collect::<Vec<_>>(),
+            [20, 60, 20, 20]
+        );
+        assert!(samples.windows(2).all(|w| w[0].at <= w[1].at));
+        assert_eq!(analyzer.peak_usage(), Some(0.6));
+        assert_eq!(analyzer.average_usage(), Some(0.3));
+
+        // Usage is relative to the capacity at the time.
+        assert!(mock_storage.set_capacity(25).is_ok());
+        assert!(!analyzer.is_usage_under_bound());
+        assert_eq!(analyzer.samples().last().unwrap().capacity, 25);
+        assert_eq!(analyzer.peak_usage(), Some(0.8));
+    }
+
+    #[test]
+    fn test_download() {
+        let mock_storage = MockStorage::with_eviction(10);
+        assert!(mock_storage.upload_bytes("a", b"hello").is_ok());
+        assert!(mock_storage.upload("b", 3).is_ok());
+        assert_eq!(mock_storage.used(), 8);
+        assert_eq!(mock_storage.metadata("a").unwrap().size, 5);
+        assert_eq!(mock_storage.download("a"), Some(b"hello".to_vec()));
+        assert_eq!(mock_storage.download("b"), Some(vec![0; 3]));
+        assert_eq!(mock_storage.download("c"), None);
+
+        // Round-trips arbitrary bytes.
+        let data = (0..=255).collect::<Vec<u8>>();
+        assert_eq!(
+            mock_storage.upload_bytes("c", &data),
+            Err(UploadError::NoSpace(246))
+        );
+        assert!(mock_storage.set_capacity(300).is_ok());
+        assert!(mock_storage.upload_bytes("c", &data).is_ok());
+        let downloaded = mock_storage.download("c").unwrap();
+        assert_eq!(
+            downloaded.iter().map(|&b| b as usize).sum::<usize>(),
+            255 * 256 / 2
+        );
+        assert_eq!(downloaded, data);
+
+        // Downloading touches the file, so that it is evicted later.
+        let accessed_at = mock_storage.metadata("a").unwrap().accessed_at;
+        assert!(mock_storage.download("a").is_some());
+        let metadata = mock_storage.metadata("a").unwrap();
+        assert!(metadata.accessed_at > accessed_at);
+        assert_eq!(metadata.uploaded_at, 1);
+        assert_eq!(
+            mock_storage.upload_bytes_evicting("d", &[1; 40]),
+            Ok(vec!["b".to_string(), "c".to_string()])
+        );
+        assert_eq!(mock_storage.download("a"), Some(b"hello".to_vec()));
+    }
+
+    #[test]
+    fn test_upload_chunked() {
+        let mock_storage = MockStorage::new(100);
+        let uploader = FileUploader::new(&mock_storage);
+        assert!(uploader.upload_chunked("a", 10, 3).is_ok());
+        assert!(uploader.upload_chunked("b", 0, 3).is_ok());
+        assert_eq!(mock_storage.list(SortBy::Name).len(), 2);
+        assert_eq!(mock_storage.metadata("a").unwrap().size, 10);
+        assert_eq!(mock_storage.metadata("a").unwrap().version, 4);
+
+        // Retries the transient failures.
+        let attempts = RefCell::new(Vec::new());
+        let uploader = FileUploader::new(&mock_storage).with_failure_policy(
+            |chunk, attempt| {
+                attempts.borrow_mut().push((chunk, attempt));
+                (chunk == 1 && attempt < 2).then_some(Failure::Transient)
+            },
+            2,
+        );
+        assert!(uploader.upload_chunked("c", 20, 10).is_ok());
+        assert_eq!(*attempts.borrow(), [(0, 0), (1, 0), (1, 1), (1, 2)]);
+        assert_eq!(mock_storage.metadata("c").unwrap().size, 20);
+
+        // Too many transient failures roll back the new file.
+        let uploader = FileUploader::new(&mock_storage)
+            .with_failure_policy(|chunk, _| (chunk == 1).then_some(Failure::Transient), 3);
+        assert_eq!(
+            uploader.upload_chunked("d", 20, 5),
+            Err(UploadError::Interrupted)
+        );
+        assert!(!mock_storage.contains("d"));
+
+        // A permanent failure rolls back the overwritten file.
+        assert!(mock_storage.upload_bytes("e", b"old").is_ok());
+        let uploader = FileUploader::new(&mock_storage)
+            .with_failure_policy(|chunk, _| (chunk == 2).then_some(Failure::Permanent), 5);
+        assert_eq!(
+            uploader.upload_chunked("e", 30, 10),
+            Err(UploadError::Interrupted)
+        );
+        assert_eq!(mock_storage.download("e"), Some(b"old".to_vec()));
+
+        // So does running out of space.
+        assert_eq!(mock_storage.used(), 33);
+        assert_eq!(
+            FileUploader::new(&mock_storage).upload_chunked("f", 80, 30),
+            Err(UploadError::NoSpace(13))
+        );
+        assert!(!mock_storage.contains("f"));
+        assert_eq!(mock_storage.used(), 33);
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk size must be positive")]
+    fn test_upload_chunked_empty_chunk() {
+        let mock_storage = MockStorage::new(100);
+        let _unused = FileUploader::new(&mock_storage).upload_chunked("a", 10, 0);
+    }
 }
@@ -14,9 +14,11 @@
 pub mod graph;
 pub mod linked_list;
 pub mod mock_storage;
+pub mod sync_graph;
 pub mod tv_room;
 
 mod graph_grade;
 mod linked_list_grade;
 mod mock_storage_grade;
+mod sync_graph_grade;
 mod tv_room_grade;
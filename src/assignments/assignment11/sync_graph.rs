@@ -0,0 +1,321 @@
+//! A thread-safe variant of the graph library in `graph.rs`.
+//!
+//! `SyncNodeHandle` and `SyncSubGraph` behave like `NodeHandle` and `SubGraph`, but they are
+//! `Send` and `Sync`, so a graph can be built and queried from multiple threads, e.g., with the
+//! scoped threads of assignment 12. Nodes are shared with `Arc`, and their edges are guarded by
+//! `RwLock`.
+//!
+//! As in `graph.rs`, edges do not own their targets, so that cyclic graphs are freed. Nodes are
+//! owned by a `SyncGraph` and by their handles, and an edge to a freed node is regarded as removed.
+//!
+//! Refer `sync_graph_grade.rs` for test cases.
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, PoisonError, RwLock, RwLockWriteGuard, Weak};
+
+use super::graph::GraphError;
+
+#[derive(PartialEq, Eq, Debug)]
+enum VisitStatus {
+    Visiting,
+    Visited,
+}
+
+/// Edges by the values of their targets, which do not keep them alive.
+type Edges = HashMap<i32, Weak<SyncNode>>;
+
+/// Inner node, shared between threads.
+#[derive(Debug)]
+pub struct SyncNode {
+    value: i32,
+    edges: RwLock<Edges>,
+}
+
+/// Thread-safe handle to a graph node.
+///
+/// Like `NodeHandle`, cloning a handle does not clone the underlying node, and handles are equal
+/// iff their nodes have the same value.
+#[derive(Debug, Clone)]
+pub struct SyncNodeHandle(Arc<SyncNode>);
+
+impl PartialEq for SyncNodeHandle {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.value == other.0.value
+    }
+}
+
+impl Eq for SyncNodeHandle {}
+
+impl Hash for SyncNodeHandle {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.value.hash(state);
+    }
+}
+
+/// Thread-safe subgraph.
+#[derive(Debug, Default)]
+pub struct SyncSubGraph {
+    nodes: HashSet<SyncNodeHandle>,
+}
+
+/// Thread-safe owner of nodes, as `Graph`.
+#[derive(Debug, Default)]
+pub struct SyncGraph {
+    nodes: HashMap<i32, SyncNodeHandle>,
+}
+
+impl SyncGraph {
+    /// Creates an empty graph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a node owned by the graph and returns the handle to it, as `Graph::add_node`.
+    pub fn add_node(&mut self, value: i32) -> Result<SyncNodeHandle, GraphError> {
+        let node = SyncNodeHandle::new(value);
+        if !self.insert_node(node.clone()) {
+            return Err(GraphError);
+        }
+        Ok(node)
+    }
+
+    /// Makes the graph own `node`, as `Graph::insert_node`.
+    pub fn insert_node(&mut self, node: SyncNodeHandle) -> bool {
+        match self.nodes.entry(node.0.value) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                let _unused = entry.insert(node);
+                true
+            }
+        }
+    }
+
+    /// Releases `node` from the graph, as `Graph::remove_node`.
+    pub fn remove_node(&mut self, node: &SyncNodeHandle) -> bool {
+        match self.nodes.entry(node.0.value) {
+            Entry::Occupied(entry) if Arc::ptr_eq(&entry.get().0, &node.0) => {
+                let _unused = entry.remove();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the handle to the node with `value`, if any.
+    pub fn node(&self, value: i32) -> Option<SyncNodeHandle> {
+        self.nodes.get(&value).cloned()
+    }
+
+    /// Returns the number of nodes of the graph.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns a subgraph with every node of the graph.
+    pub fn subgraph(&self) -> SyncSubGraph {
+        SyncSubGraph {
+            nodes: self.nodes.values().cloned().collect(),
+        }
+    }
+}
+
+impl SyncNodeHandle {
+    /// Creates a node and returns the handle to it. The node is owned only by its handles unless it
+    /// is inserted into a `SyncGraph`.
+    pub fn new(value: i32) -> Self {
+        Self(Arc::new(SyncNode {
+            value,
+            edges: RwLock::new(HashMap::new()),
+        }))
+    }
+
+    /// Locks the edges for writing, waiting for the other threads accessing them.
+    /// Returns `Err(GraphError)` if a thread panicked while holding the lock.
+    fn edges_mut(&self) -> Result<RwLockWriteGuard<'_, Edges>, GraphError> {
+        self.0.edges.write().map_err(|_| GraphError)
+    }
+
+    /// Adds an edge to `to`, as `NodeHandle::add_edge`.
+    pub fn add_edge(&self, to: SyncNodeHandle) -> Result<bool, GraphError> {
+        to.check_owned()?;
+        Ok(insert_edge(&mut *self.edges_mut()?, &to))
+    }
+
+    /// Removes the edge to `to`, as `NodeHandle::remove_edge`.
+    pub fn remove_edge(&self, to: &SyncNodeHandle) -> Result<bool, GraphError> {
+        Ok(remove_edge(&mut *self.edges_mut()?, to))
+    }
+
+    /// Adds edges to `to` and back, both or neither, as `NodeHandle::add_undirected_edge`.
+    pub fn add_undirected_edge(&self, to: SyncNodeHandle) -> Result<bool, GraphError> {
+        if Arc::ptr_eq(&self.0, &to.0) {
+            return self.add_edge(to);
+        }
+        to.check_owned()?;
+        let (mut edges, mut back_edges) = self.lock_both(&to)?;
+        let added = insert_edge(&mut back_edges, self);
+        Ok(insert_edge(&mut edges, &to) | added)
+    }
+
+    /// Removes the edges to `to` and back, both or neither, as
+    /// `NodeHandle::remove_undirected_edge`.
+    pub fn remove_undirected_edge(&self, to: &SyncNodeHandle) -> Result<bool, GraphError> {
+        if Arc::ptr_eq(&self.0, &to.0) {
+            return self.remove_edge(to);
+        }
+        let (mut edges, mut back_edges) = self.lock_both(to)?;
+        let removed = remove_edge(&mut back_edges, self);
+        Ok(remove_edge(&mut edges, to) | removed)
+    }
+
+    /// Removes all edges, as `NodeHandle::clear_edges`.
+    pub fn clear_edges(&self) -> Result<(), GraphError> {
+        self.edges_mut()?.clear();
+        Ok(())
+    }
+
+    /// Locks the edges of `self` and `other`, which are different nodes, for writing. The locks are
+    /// always taken in the order of the addresses of the nodes, so that two threads adding edges
+    /// between the same nodes in the opposite directions do not deadlock.
+    fn lock_both<'a>(
+        &'a self,
+        other: &'a SyncNodeHandle,
+    ) -> Result<(RwLockWriteGuard<'a, Edges>, RwLockWriteGuard<'a, Edges>), GraphError> {
+        if Arc::as_ptr(&self.0) < Arc::as_ptr(&other.0) {
+            let edges = self.edges_mut()?;
+            Ok((edges, other.edges_mut()?))
+        } else {
+            let other_edges = other.edges_mut()?;
+            Ok((self.edges_mut()?, other_edges))
+        }
+    }
+
+    /// Returns the number of handles to the node, as `NodeHandle::handle_count`.
+    pub fn handle_count(&self) -> usize {
+        Arc::strong_count(&self.0)
+    }
+
+    /// Returns `Err(GraphError)` if `self` is the last handle to its node, i.e., an edge to it
+    /// would be dead once `self` is dropped. Other threads may still drop their handles meanwhile.
+    fn check_owned(&self) -> Result<(), GraphError> {
+        if self.handle_count() == 1 {
+            return Err(GraphError);
+        }
+        Ok(())
+    }
+}
+
+/// Adds an edge to `to` to `edges` if it does not exist. Returns true iff the edge is newly added.
+fn insert_edge(edges: &mut Edges, to: &SyncNodeHandle) -> bool {
+    match edges.entry(to.0.value) {
+        Entry::Occupied(entry) if entry.get().strong_count() > 0 => false,
+        Entry::Occupied(mut entry) => {
+            let _unused = entry.insert(Arc::downgrade(&to.0));
+            true
+        }
+        Entry::Vacant(entry) => {
+            let _unused = entry.insert(Arc::downgrade(&to.0));
+            true
+        }
+    }
+}
+
+/// Removes the edge to `to` from `edges`. Returns true iff the edge existed.
+fn remove_edge(edges: &mut Edges, to: &SyncNodeHandle) -> bool {
+    edges
+        .remove(&to.0.value)
+        .is_some_and(|to| to.strong_count() > 0)
+}
+
+impl SyncSubGraph {
+    /// Creates a new subgraph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a node to the subgraph. Returns true iff the node is newly added.
+    pub fn add_node(&mut self, node: SyncNodeHandle) -> bool {
+        self.nodes.insert(node)
+    }
+
+    /// Removes a node from the subgraph. Returns true iff the node is successfully removed.
+    pub fn remove_node(&mut self, node: &SyncNodeHandle) -> bool {
+        self.nodes.remove(node)
+    }
+
+    /// Returns an iterator over the nodes of the subgraph, in arbitrary order.
+    pub fn iter_nodes(&self) -> impl Iterator<Item = &SyncNodeHandle> {
+        self.nodes.iter()
+    }
+
+    /// Returns the number of nodes of the subgraph.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the number of edges between the nodes of the subgraph.
+    pub fn edge_count(&self) -> usize {
+        self.nodes
+            .iter()
+            .map(|node| self.neighbors(node).len())
+            .sum()
+    }
+
+    /// Returns true iff the subgraph contains a cycle, as `SubGraph::detect_cycle`.
+    pub fn detect_cycle(&self) -> bool {
+        self.find_cycle().is_some()
+    }
+
+    /// Returns a cycle of the subgraph if any, as `SubGraph::find_cycle`.
+    ///
+    /// Each node's edges are read at once, so edges modified by other threads meanwhile may or may
+    /// not be seen.
+    pub fn find_cycle(&self) -> Option<Vec<SyncNodeHandle>> {
+        #[allow(clippy::mutable_key_type)]
+        let mut status = HashMap::new();
+
+        for root in &self.nodes {
+            if status.contains_key(root) {
+                continue;
+            }
+
+            let _unused = status.insert(root.clone(), VisitStatus::Visiting);
+            let mut stack = vec![(root.clone(), self.neighbors(root).into_iter())];
+            while let Some((node, neighbors)) = stack.last_mut() {
+                let Some(neighbor) = neighbors.next() else {
+                    let _unused = status.insert(node.clone(), VisitStatus::Visited);
+                    let _unused = stack.pop();
+                    continue;
+                };
+                match status.get(&neighbor) {
+                    Some(VisitStatus::Visiting) => {
+                        let start = stack.iter().position(|(node, _)| *node == neighbor)?;
+                        return Some(stack.drain(start..).map(|(node, _)| node).collect());
+                    }
+                    Some(VisitStatus::Visited) => {}
+                    None => {
+                        let _unused = status.insert(neighbor.clone(), VisitStatus::Visiting);
+                        let neighbors = self.neighbors(&neighbor).into_iter();
+                        stack.push((neighbor, neighbors));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns the neighbors of `node` that belong to this subgraph. The edges are read even if a
+    /// thread panicked while modifying them, since a `HashSet` is left consistent anyway.
+    fn neighbors(&self, node: &SyncNodeHandle) -> Vec<SyncNodeHandle> {
+        node.0
+            .edges
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .values()
+            .filter_map(|neighbor| neighbor.upgrade().map(SyncNodeHandle))
+            .filter(|neighbor| self.nodes.contains(neighbor))
+            .collect()
+    }
+}
@@ -0,0 +1,147 @@
+//! Test cases for assignment11/sync_graph.rs
+
+#[cfg(test)]
+mod test_sync_graph {
+    use std::sync::{Arc, RwLock};
+    use std::thread;
+
+    use crate::assignments::assignment11::sync_graph::*;
+
+    #[test]
+    fn test_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<SyncNodeHandle>();
+        assert_send_sync::<SyncSubGraph>();
+        assert_send_sync::<SyncGraph>();
+    }
+
+    #[test]
+    fn test_sync_graph() {
+        let nodes = (0..6).map(SyncNodeHandle::new).collect::<Vec<_>>();
+        let edges = [
+            (0, 1),
+            (0, 3),
+            (1, 4),
+            (2, 4),
+            (2, 5),
+            (3, 1),
+            (4, 3),
+            (5, 5),
+        ];
+
+        // Adds the edges from multiple threads.
+        thread::scope(|s| {
+            for (from, to) in edges {
+                let (from, to) = (nodes[from].clone(), nodes[to].clone());
+                let _unused = s.spawn(move || assert!(from.add_edge(to).unwrap()));
+            }
+        });
+        for (from, to) in edges {
+            assert!(!nodes[from].add_edge(nodes[to].clone()).unwrap());
+        }
+
+        let mut graph = SyncSubGraph::new();
+        for node in &nodes {
+            assert!(graph.add_node(node.clone()));
+        }
+        assert_eq!(graph.node_count(), 6);
+        assert_eq!(graph.edge_count(), 8);
+
+        // Queries the graph from multiple threads.
+        thread::scope(|s| {
+            for _ in 0..4 {
+                let _unused = s.spawn(|| assert!(graph.detect_cycle()));
+            }
+        });
+
+        assert!(graph.remove_node(&nodes[5]));
+        assert_eq!(graph.find_cycle().unwrap().len(), 3);
+        assert!(nodes[4].remove_edge(&nodes[3]).unwrap());
+        assert!(!graph.detect_cycle());
+    }
+
+    #[test]
+    fn test_shared_subgraph() {
+        let graph = Arc::new(RwLock::new(SyncSubGraph::new()));
+        let nodes = (0..100).map(SyncNodeHandle::new).collect::<Vec<_>>();
+
+        // Builds a chain, i.e., `i -> i + 1`, with a thread per node.
+        let handles = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| {
+                let graph = Arc::clone(&graph);
+                let node = node.clone();
+                let next = nodes.get(i + 1).cloned();
+                thread::spawn(move || {
+                    if let Some(next) = next {
+                        assert!(node.add_edge(next).unwrap());
+                    }
+                    assert!(graph.write().unwrap().add_node(node));
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let graph = graph.read().unwrap();
+        assert_eq!(graph.node_count(), 100);
+        assert_eq!(graph.edge_count(), 99);
+        assert!(!graph.detect_cycle());
+    }
+
+    #[test]
+    fn test_undirected_edges() {
+        let nodes = (0..2).map(SyncNodeHandle::new).collect::<Vec<_>>();
+
+        // Adding the same undirected edge from both ends at once does not deadlock.
+        for _ in 0..100 {
+            thread::scope(|s| {
+                let _unused = s.spawn(|| nodes[0].add_undirected_edge(nodes[1].clone()).unwrap());
+                let _unused = s.spawn(|| nodes[1].add_undirected_edge(nodes[0].clone()).unwrap());
+            });
+            assert!(nodes[0].remove_undirected_edge(&nodes[1]).unwrap());
+            assert!(!nodes[1].remove_edge(&nodes[0]).unwrap());
+        }
+
+        assert!(nodes[0].add_undirected_edge(nodes[0].clone()).unwrap());
+        assert!(!nodes[0].add_undirected_edge(nodes[0].clone()).unwrap());
+        assert!(nodes[0].remove_undirected_edge(&nodes[0]).unwrap());
+    }
+
+    #[test]
+    fn test_sync_cycle_freed() {
+        let mut graph = SyncGraph::new();
+        let nodes = (0..3)
+            .map(|i| graph.add_node(i).unwrap())
+            .collect::<Vec<_>>();
+        assert!(graph.add_node(0).is_err());
+
+        // Builds a cycle from multiple threads.
+        thread::scope(|s| {
+            for (from, to) in [(0, 1), (1, 2), (2, 0)] {
+                let (from, to) = (&nodes[from], nodes[to].clone());
+                let _unused = s.spawn(move || assert!(from.add_edge(to).unwrap()));
+            }
+        });
+        assert!(nodes[0].add_edge(SyncNodeHandle::new(3)).is_err());
+
+        // The graph keeps the nodes alive, and edges do not.
+        for node in &nodes {
+            assert_eq!(node.handle_count(), 2);
+        }
+        let node0 = nodes.into_iter().next().unwrap();
+        assert!(graph.subgraph().detect_cycle());
+        assert_eq!(graph.subgraph().edge_count(), 3);
+
+        // Dropping the graph frees the cycle.
+        drop(graph);
+        assert_eq!(node0.handle_count(), 1);
+        let mut subgraph = SyncSubGraph::new();
+        assert!(subgraph.add_node(node0.clone()));
+        assert_eq!(subgraph.edge_count(), 0);
+        assert!(!node0.remove_edge(&SyncNodeHandle::new(1)).unwrap());
+    }
+}
@@ -45,6 +45,18 @@ struct Args {
     /// Expression filepath.
     #[clap(value_parser)]
     filepath: Option<String>,
+
+    /// Runs the input as a script, and prints the variables at the end.
+    #[clap(long)]
+    script: bool,
+
+    /// Calculates with exact rationals if possible.
+    #[clap(long, conflicts_with = "integer")]
+    rational: bool,
+
+    /// Calculates with exact integers of arbitrary size if possible.
+    #[clap(long)]
+    integer: bool,
 }
 
 fn main() -> Result<()> {
@@ -52,17 +64,59 @@ fn main() -> Result<()> {
     let args = Args::parse();
 
     let stdin = io::stdin();
-    let input = if let Some(filepath) = args.filepath {
+    let mut input = if let Some(filepath) = args.filepath {
         Input::file(&filepath)?
     } else {
         Input::console(&stdin)
     };
 
-    let mut context = context::Context::new();
+    let mut context = context::Context::with_mode(if args.rational {
+        context::Mode::Rational
+    } else if args.integer {
+        context::Mode::Integer
+    } else {
+        context::Mode::Float
+    });
+    if args.script {
+        let mut script = String::new();
+        let _ = input.read_to_string(&mut script)?;
+        let variables = context.run_script(&script)?.clone();
+        let mut variables = variables.iter().collect::<Vec<_>>();
+        variables.sort_by_key(|(variable, _)| *variable);
+        for (variable, value) in variables {
+            println!("{} = {}", variable, context.render(value));
+        }
+        return Ok(());
+    }
+
     for line in input.lines() {
-        let command = parser::parse_command(&line?)?;
-        let (variable, value) = context.calc_command(&command)?;
-        println!("{} = {}", variable, value);
+        let line = line?;
+        let result = parser::parse_statement(&line).and_then(|statement| match statement {
+            Some(syntax::Statement::Command(command)) => {
+                context.calc_command_value(&command).map(Some)
+            }
+            Some(syntax::Statement::Set(setting)) => {
+                context.set(setting);
+                Ok(None)
+            }
+            None => Ok(None),
+        });
+        match result {
+            Ok(Some((variable, value))) => println!("{} = {}", variable, context.render(&value)),
+            Ok(None) => {}
+            Err(error) => {
+                // Underlines the bad token of parse errors.
+                if let Some(error) = error.downcast_ref::<parser::ParseError>() {
+                    eprintln!("{}", line);
+                    eprintln!(
+                        "{}{}",
+                        " ".repeat(error.span.start),
+                        "^".repeat(error.span.len().max(1))
+                    );
+                }
+                eprintln!("{}", error);
+            }
+        }
     }
 
     Ok(())